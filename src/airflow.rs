@@ -5,3 +5,4 @@ pub mod managed_services;
 pub mod model;
 pub mod topological_sort;
 pub mod traits;
+pub mod watch;
@@ -1,4 +1,9 @@
 pub mod base;
+pub mod favorites;
+pub mod jwt;
+pub mod oidc;
+pub mod retry;
+pub mod token_cmd;
 pub mod v1;
 pub mod v2;
 
@@ -1,25 +1,35 @@
 use anyhow::{Context, Result};
-use log::{debug, info};
-use reqwest::{Method, Url};
+use log::{debug, info, warn};
+use reqwest::{Method, StatusCode, Url};
+use serde::Serialize;
 use serde_json;
 use std::convert::TryFrom;
 use std::time::Duration;
 
-use crate::airflow::config::{AirflowAuth, AirflowConfig};
+use super::oidc::DeviceLoginPrompt;
+use super::retry::RetryPolicy;
+use crate::airflow::config::{AirflowAuth, AirflowConfig, AirflowVersion, TlsConfig};
 use crate::airflow::managed_services::conveyor::ConveyorClient;
 
+/// Request timeout used when a server doesn't configure `timeout_secs`
+const DEFAULT_TIMEOUT_SECS: u64 = 5;
+
 /// Base HTTP client for Airflow API communication.
 /// Handles authentication and provides base request building functionality.
 #[derive(Debug, Clone)]
 pub struct BaseClient {
     pub client: reqwest::Client,
     pub config: AirflowConfig,
+    retry: RetryPolicy,
 }
 
 impl BaseClient {
     pub fn new(config: AirflowConfig) -> Result<Self> {
+        let timeout = Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        let connect_timeout = config.connect_timeout_secs.map_or(timeout, Duration::from_secs);
         let mut client_builder = reqwest::Client::builder()
-            .timeout(Duration::from_secs(5))
+            .timeout(timeout)
+            .connect_timeout(connect_timeout)
             .use_rustls_tls();
         
         // Configure proxy if specified in config (takes priority)
@@ -45,17 +55,68 @@ impl BaseClient {
             }
         }
         
+        if let Some(tls) = &config.tls {
+            client_builder = Self::configure_tls(client_builder, tls)?;
+        }
+        
         let client = client_builder.build()?;
-        Ok(Self { client, config })
+        let retry = RetryPolicy::from(&config);
+        Ok(Self { client, config, retry })
+    }
+
+    /// Apply per-server TLS settings: extra CA certificates, a client identity for mTLS
+    /// and optionally disabling certificate verification
+    fn configure_tls(
+        mut client_builder: reqwest::ClientBuilder,
+        tls: &TlsConfig,
+    ) -> Result<reqwest::ClientBuilder> {
+        if let Some(ca_bundle) = &tls.ca_bundle {
+            let path = TlsConfig::expand_path(ca_bundle)?;
+            let pem = std::fs::read(&path)
+                .with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("Invalid PEM in CA bundle {}", path.display()))?;
+            info!("🔒 Trusting {} extra CA certificate(s) from {}", certificates.len(), path.display());
+            for certificate in certificates {
+                client_builder = client_builder.add_root_certificate(certificate);
+            }
+        }
+
+        if let Some(client_cert) = &tls.client_cert {
+            let cert_path = TlsConfig::expand_path(client_cert)?;
+            let mut pem = std::fs::read(&cert_path)
+                .with_context(|| format!("Failed to read client certificate {}", cert_path.display()))?;
+            // rustls expects the certificate chain and private key in a single PEM buffer
+            if let Some(client_key) = &tls.client_key {
+                let key_path = TlsConfig::expand_path(client_key)?;
+                pem.push(b'\n');
+                pem.extend(
+                    std::fs::read(&key_path)
+                        .with_context(|| format!("Failed to read client key {}", key_path.display()))?,
+                );
+            }
+            let identity = reqwest::Identity::from_pem(&pem)
+                .with_context(|| format!("Invalid client certificate or key for {}", cert_path.display()))?;
+            info!("🔒 Using client certificate {}", cert_path.display());
+            client_builder = client_builder.identity(identity);
+        }
+
+        if tls.danger_accept_invalid_certs {
+            warn!("⚠️ TLS certificate verification is disabled for this server");
+            client_builder = client_builder.danger_accept_invalid_certs(true);
+        }
+
+        Ok(client_builder)
     }
 
-    /// Build a base request with authentication for the specified API version
+    /// Build a base request for the specified API version
+    /// Authentication is applied when the returned request is sent
     pub fn base_api(
         &self,
         method: Method,
         endpoint: &str,
         api_version: &str,
-    ) -> Result<reqwest::RequestBuilder> {
+    ) -> Result<ApiRequest<'_>> {
         // Ensure base URL ends with a trailing slash for proper path joining
         let mut base_endpoint = self.config.endpoint.clone();
         if !base_endpoint.ends_with('/') {
@@ -66,80 +127,98 @@ impl BaseClient {
         let url = base_url.join(format!("{api_version}/{endpoint}").as_str())?;
         debug!("🔗 Request URL: {url}");
 
+        Ok(ApiRequest {
+            base: self,
+            idempotent: method == Method::GET,
+            builder: self.client.request(method, url),
+        })
+    }
+
+    /// Authenticate and send a single attempt of a request
+    async fn execute(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        Ok(self.authenticate(request).await?.send().await?)
+    }
+
+    /// Apply the configured authentication to a request
+    async fn authenticate(&self, request: reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder> {
         match &self.config.auth {
             AirflowAuth::Basic(auth) => {
                 let username = crate::airflow::config::expand_env_vars(&auth.username)?;
                 let password = crate::airflow::config::expand_env_vars(&auth.password)?;
                 info!("🔑 Basic Auth: {}", username);
-                Ok(self
-                    .client
-                    .request(method, url)
-                    .basic_auth(&username, Some(&password)))
+                Ok(request.basic_auth(&username, Some(&password)))
+            }
+            AirflowAuth::Jwt(auth) => {
+                info!("🔑 JWT Auth: {}", auth.username);
+                let token = auth.get_token(&self.client, &self.config.endpoint).await?;
+                Ok(request.bearer_auth(token))
+            }
+            AirflowAuth::Oidc(auth) => {
+                info!("🔑 OIDC Auth: {}", auth.issuer);
+                let token = auth.get_token(&self.client).await?;
+                Ok(request.bearer_auth(token))
             }
             AirflowAuth::Token(token) => {
                 info!("🔑 Token Auth: {:?}", token.cmd);
-                if let Some(cmd) = &token.cmd {
-                    let output = std::process::Command::new("sh")
-                        .arg("-c")
-                        .arg(cmd)
-                        .output()
-                        .context("Failed to run token helper command")?;
-
-                    if !output.status.success() {
-                        let stderr = String::from_utf8_lossy(&output.stderr);
-                        let stdout = String::from_utf8_lossy(&output.stdout);
-                        return Err(anyhow::anyhow!(
-                            "Token helper command failed with exit code {:?}\nstdout: {}\nstderr: {}",
-                            output.status.code(),
-                            stdout,
-                            stderr
-                        ));
-                    }
-
-                    let token = String::from_utf8(output.stdout)
-                        .context("Token helper returned invalid UTF-8")?
-                        .trim()
-                        .replace('"', "");
-                    Ok(self.client.request(method, url).bearer_auth(token))
-                } else {
-                    if let Some(token) = &token.token {
-                        let expanded_token = crate::airflow::config::expand_env_vars(token.trim())?;
-                        return Ok(self.client.request(method, url).bearer_auth(expanded_token));
-                    }
-                    Err(anyhow::anyhow!("Token not found"))
-                }
+                Ok(request.bearer_auth(token.get_token().await?))
             }
             AirflowAuth::Conveyor => {
                 info!("🔑 Conveyor Auth");
                 let token: String = ConveyorClient::get_token()?;
-                Ok(self.client.request(method, url).bearer_auth(token))
+                Ok(request.bearer_auth(token))
             }
             AirflowAuth::Mwaa(auth) => {
                 info!("🔑 MWAA Auth: {}", auth.environment_name);
-                Ok(self
-                    .client
-                    .request(method, url)
-                    .header("Cookie", format!("session={}", auth.session_cookie)))
+                let credential = auth.credential(&self.config.version).await?;
+                Ok(match self.config.version {
+                    AirflowVersion::V2 => request.header("Cookie", format!("session={credential}")),
+                    AirflowVersion::V3 => request.bearer_auth(credential),
+                })
             }
             AirflowAuth::Astronomer(auth) => {
                 info!("🔑 Astronomer Auth");
-                Ok(self
-                    .client
-                    .request(method, url)
-                    .bearer_auth(&auth.api_token))
+                Ok(request.bearer_auth(&auth.api_token))
             }
             AirflowAuth::Composer(auth) => {
                 info!("🔑 Google Cloud Composer Auth");
                 // Get the client and fetch a fresh token
-                // Note: This is a blocking call in an async context, but it's brief
-                let token = tokio::task::block_in_place(|| {
-                    tokio::runtime::Handle::current().block_on(async {
-                        let client = auth.get_client().await?;
-                        client.get_token().await
-                    })
-                })?;
-                Ok(self.client.request(method, url).bearer_auth(token))
+                let client = auth.get_client().await?;
+                let token = client.get_token().await?;
+                Ok(request.bearer_auth(token))
+            }
+        }
+    }
+
+    /// The OIDC device login waiting for the user to approve it, if any
+    pub fn pending_login(&self) -> Option<DeviceLoginPrompt> {
+        match &self.config.auth {
+            AirflowAuth::Oidc(auth) => auth.pending_login(),
+            _ => None,
+        }
+    }
+
+    /// Discard cached credentials after a 401 response
+    /// Returns true if the credentials can be refreshed, i.e. the request is worth retrying
+    async fn invalidate_credentials(&self) -> bool {
+        match &self.config.auth {
+            AirflowAuth::Jwt(auth) => {
+                auth.invalidate().await;
+                true
+            }
+            AirflowAuth::Oidc(auth) => {
+                auth.invalidate().await;
+                true
+            }
+            AirflowAuth::Mwaa(auth) => {
+                auth.invalidate().await;
+                true
+            }
+            // Only helper commands can produce a different token, static tokens won't change
+            AirflowAuth::Token(token) if token.cmd.is_some() => {
+                token.invalidate().await;
+                true
             }
+            _ => false,
         }
     }
 
@@ -170,6 +249,114 @@ impl BaseClient {
     }
 }
 
+/// A request against the Airflow API that is authenticated when it is sent.
+///
+/// Deferring authentication to `send` allows expired credentials to be refreshed
+/// and the request to be replayed once when the server responds with 401 Unauthorized.
+pub struct ApiRequest<'a> {
+    base: &'a BaseClient,
+    /// Only idempotent requests are retried on transient failures
+    idempotent: bool,
+    builder: reqwest::RequestBuilder,
+}
+
+impl ApiRequest<'_> {
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Self {
+        self.builder = self.builder.query(query);
+        self
+    }
+
+    pub fn json<T: Serialize + ?Sized>(mut self, json: &T) -> Self {
+        self.builder = self.builder.json(json);
+        self
+    }
+
+    pub fn header(mut self, key: &'static str, value: &str) -> Self {
+        self.builder = self.builder.header(key, value);
+        self
+    }
+
+    /// Authenticate and send the request
+    /// On 401 (or a redirect to a login page) the cached credentials are invalidated
+    /// and the request is retried once.
+    /// GET requests are additionally retried with backoff on 429/502/503/504 and
+    /// connection errors or timeouts, up to the configured `max_retries`.
+    pub async fn send(self) -> Result<reqwest::Response> {
+        let template = self.builder.try_clone();
+        let mut response = self.base.execute(self.builder).await;
+
+        // Requests with streaming bodies can't be cloned and thus not be replayed
+        let Some(template) = template else {
+            return response;
+        };
+        let request_url = template.try_clone().and_then(|b| b.build().ok()).map(|r| r.url().clone());
+
+        let policy = &self.base.retry;
+        let mut attempt = 0;
+        let mut reauthenticated = false;
+        loop {
+            let retryable = self.idempotent && attempt < policy.max_retries;
+            match &response {
+                Ok(r) if !reauthenticated && is_auth_failure(r, request_url.as_ref(), self.idempotent) => {
+                    reauthenticated = true;
+                    if !self.base.invalidate_credentials().await {
+                        break;
+                    }
+                    info!("🔑 Received {} from {}, refreshing credentials and retrying", r.status(), r.url());
+                }
+                Ok(r) if retryable && RetryPolicy::is_retryable_status(r.status()) => {
+                    let delay = policy.delay(attempt, Some(r));
+                    attempt += 1;
+                    debug!(
+                        "🔁 {} returned {}, retry {attempt}/{} in {delay:?}",
+                        r.url(),
+                        r.status(),
+                        policy.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) if retryable && is_transient_error(e) => {
+                    let delay = policy.delay(attempt, None);
+                    attempt += 1;
+                    debug!("🔁 Request failed ({e}), retry {attempt}/{} in {delay:?}", policy.max_retries);
+                    tokio::time::sleep(delay).await;
+                }
+                _ => break,
+            }
+
+            let Some(request) = template.try_clone() else {
+                break;
+            };
+            response = self.base.execute(request).await;
+        }
+
+        response
+    }
+}
+
+/// Expired sessions show up as 401, or as a redirect to the login page
+/// (e.g. MWAA and other deployments with a web UI session in front of the API).
+/// Only idempotent requests are replayed after a login redirect, since the
+/// server may not have rejected the original request.
+fn is_auth_failure(response: &reqwest::Response, request_url: Option<&reqwest::Url>, idempotent: bool) -> bool {
+    if response.status() == StatusCode::UNAUTHORIZED {
+        return true;
+    }
+    let url = response.url();
+    idempotent && request_url.is_some_and(|request_url| request_url != url) && is_login_route(url)
+}
+
+fn is_login_route(url: &reqwest::Url) -> bool {
+    url.path().trim_end_matches('/').ends_with("/login")
+}
+
+/// Timeouts and connection failures are worth retrying, other errors (e.g. auth) are not
+fn is_transient_error(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(|e| e.is_timeout() || e.is_connect())
+}
+
 impl TryFrom<&AirflowConfig> for BaseClient {
     type Error = anyhow::Error;
 
@@ -177,3 +364,331 @@ impl TryFrom<&AirflowConfig> for BaseClient {
         Self::new(config.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airflow::client::jwt::JwtAuth;
+    use crate::airflow::config::{Protection, TokenCmd};
+
+    fn retry_test_client(endpoint: String, max_retries: u32) -> BaseClient {
+        BaseClient::new(AirflowConfig {
+            name: "test".to_string(),
+            endpoint,
+            auth: AirflowAuth::Basic(crate::airflow::config::BasicAuth {
+                username: "airflow".to_string(),
+                password: "airflow".to_string(),
+            }),
+            managed: None,
+            version: AirflowVersion::V2,
+            proxy: None,
+            tls: None,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+            max_retries: Some(max_retries),
+            backoff_ms: Some(1),
+            max_backoff_secs: None,
+            protection: Protection::None,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_is_retried_on_service_unavailable() {
+        let mut server = mockito::Server::new_async().await;
+        let unavailable = server
+            .mock("GET", "/api/v1/dags")
+            .with_status(503)
+            .with_header("Retry-After", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", "/api/v1/dags")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = retry_test_client(server.url(), 2);
+        let response = client
+            .base_api(Method::GET, "dags", "api/v1")
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        unavailable.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_retries_are_bounded() {
+        let mut server = mockito::Server::new_async().await;
+        let throttled = server
+            .mock("GET", "/api/v1/dags")
+            .with_status(429)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = retry_test_client(server.url(), 2);
+        let response = client
+            .base_api(Method::GET, "dags", "api/v1")
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        throttled.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_non_idempotent_requests_are_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let bad_gateway = server
+            .mock("POST", "/api/v1/dags/example/dagRuns")
+            .with_status(502)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = retry_test_client(server.url(), 2);
+        let response = client
+            .base_api(Method::POST, "dags/example/dagRuns", "api/v1")
+            .unwrap()
+            .json(&serde_json::json!({}))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        bad_gateway.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_token_helper_is_rerun_on_401() {
+        let mut server = mockito::Server::new_async().await;
+        let unauthorized = server
+            .mock("GET", "/api/v1/health")
+            .with_status(401)
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", "/api/v1/health")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let counter = std::env::temp_dir().join(format!("flowrs-base-401-{}", std::process::id()));
+        let _ = std::fs::remove_file(&counter);
+        let client = BaseClient::new(AirflowConfig {
+            name: "test".to_string(),
+            endpoint: server.url(),
+            auth: AirflowAuth::Token(TokenCmd {
+                cmd: Some(format!("echo run >> {}; echo token", counter.display())),
+                ..Default::default()
+            }),
+            managed: None,
+            version: AirflowVersion::V2,
+            proxy: None,
+            tls: None,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+            max_retries: None,
+            backoff_ms: None,
+            max_backoff_secs: None,
+            protection: Protection::None,
+        })
+        .unwrap();
+
+        let response = client
+            .base_api(Method::GET, "health", "api/v1")
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        unauthorized.assert_async().await;
+        ok.assert_async().await;
+        // Helper ran once for the rejected request and once more for the retry
+        assert_eq!(std::fs::read_to_string(&counter).unwrap().lines().count(), 2);
+        let _ = std::fs::remove_file(counter);
+    }
+
+    #[tokio::test]
+    async fn test_login_redirect_refreshes_credentials() {
+        let mut server = mockito::Server::new_async().await;
+        let token_mock = server
+            .mock("POST", "/auth/token")
+            .with_status(201)
+            .with_body(r#"{"access_token": "token"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        // An expired session is redirected to the login page instead of returning 401
+        let redirect = server
+            .mock("GET", "/api/v2/dags")
+            .with_status(302)
+            .with_header("location", "/login/?next=%2Fapi%2Fv2%2Fdags")
+            .expect(1)
+            .create_async()
+            .await;
+        let login_page = server
+            .mock("GET", "/login/")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body("<html>Sign in</html>")
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", "/api/v2/dags")
+            .with_status(200)
+            .with_body(r#"{"dags": [], "total_entries": 0}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = BaseClient::new(AirflowConfig {
+            name: "test".to_string(),
+            endpoint: server.url(),
+            auth: AirflowAuth::Jwt(JwtAuth::new("admin".to_string(), "admin".to_string())),
+            managed: None,
+            version: AirflowVersion::V3,
+            proxy: None,
+            tls: None,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+            max_retries: None,
+            backoff_ms: None,
+            max_backoff_secs: None,
+            protection: Protection::None,
+        })
+        .unwrap();
+
+        let response = client
+            .base_api(Method::GET, "dags", "api/v2")
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.url().path(), "/api/v2/dags");
+        token_mock.assert_async().await;
+        redirect.assert_async().await;
+        login_page.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_login_in_dag_id_is_not_an_auth_failure() {
+        let mut server = mockito::Server::new_async().await;
+        let token_mock = server
+            .mock("POST", "/auth/token")
+            .with_status(201)
+            .with_body(r#"{"access_token": "token"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let trigger = server
+            .mock("POST", "/api/v2/dags/login_sync/dagRuns")
+            .with_status(200)
+            .with_body(r#"{"dag_run_id": "manual"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = BaseClient::new(AirflowConfig {
+            name: "test".to_string(),
+            endpoint: server.url(),
+            auth: AirflowAuth::Jwt(JwtAuth::new("admin".to_string(), "admin".to_string())),
+            managed: None,
+            version: AirflowVersion::V3,
+            proxy: None,
+            tls: None,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+            max_retries: None,
+            backoff_ms: None,
+            max_backoff_secs: None,
+            protection: Protection::None,
+        })
+        .unwrap();
+
+        let response = client
+            .base_api(Method::POST, "dags/login_sync/dagRuns", "api/v2")
+            .unwrap()
+            .json(&serde_json::json!({}))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        token_mock.assert_async().await;
+        trigger.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_jwt_token_is_refreshed_and_request_retried_on_401() {
+        let mut server = mockito::Server::new_async().await;
+        let token_mock = server
+            .mock("POST", "/auth/token")
+            .with_status(201)
+            .with_body(r#"{"access_token": "token"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        // First request is rejected (e.g. the token was revoked), the retry succeeds
+        let unauthorized = server
+            .mock("GET", "/api/v2/version")
+            .match_header("authorization", "Bearer token")
+            .with_status(401)
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", "/api/v2/version")
+            .match_header("authorization", "Bearer token")
+            .with_status(200)
+            .with_body(r#"{"version": "3.0.0"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = BaseClient::new(AirflowConfig {
+            name: "test".to_string(),
+            endpoint: server.url(),
+            auth: AirflowAuth::Jwt(JwtAuth::new("admin".to_string(), "admin".to_string())),
+            managed: None,
+            version: AirflowVersion::V3,
+            proxy: None,
+            tls: None,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+            max_retries: None,
+            backoff_ms: None,
+            max_backoff_secs: None,
+            protection: Protection::None,
+        })
+        .unwrap();
+
+        let response = client
+            .base_api(Method::GET, "version", "api/v2")
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        token_mock.assert_async().await;
+        unauthorized.assert_async().await;
+        ok.assert_async().await;
+    }
+}
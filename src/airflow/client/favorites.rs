@@ -0,0 +1,75 @@
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use crate::app::environment_state::sanitize_filename;
+
+/// Favorite DAGs of one environment, kept in the state directory
+/// (`~/.local/state/flowrs/favorites/<environment>.json`).
+/// Used for Airflow 2 and 3.0, which have no favorites API.
+#[derive(Debug, Clone)]
+pub struct LocalFavorites {
+    path: PathBuf,
+}
+
+impl LocalFavorites {
+    pub fn for_environment(environment: &str) -> Self {
+        Self::with_path(
+            crate::get_state_dir()
+                .join("favorites")
+                .join(format!("{}.json", sanitize_filename(environment))),
+        )
+    }
+
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Ids of the pinned DAGs, empty if nothing was pinned yet
+    pub fn load(&self) -> Result<HashSet<String>> {
+        if !self.path.exists() {
+            return Ok(HashSet::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid favorites file {}", self.path.display()))
+    }
+
+    pub fn set(&self, dag_id: &str, favorite: bool) -> Result<()> {
+        // Sorted, so the file stays readable and diffs stay small
+        let mut favorites: BTreeSet<String> = self.load()?.into_iter().collect();
+        if favorite {
+            favorites.insert(dag_id.to_string());
+        } else {
+            favorites.remove(dag_id);
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&favorites)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_favorites() {
+        let path =
+            std::env::temp_dir().join(format!("flowrs-favorites-{}.json", std::process::id()));
+        let favorites = LocalFavorites::with_path(path.clone());
+        assert!(favorites.load().unwrap().is_empty());
+
+        favorites.set("b", true).unwrap();
+        favorites.set("a", true).unwrap();
+        favorites.set("b", false).unwrap();
+        assert_eq!(favorites.load().unwrap(), HashSet::from(["a".to_string()]));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
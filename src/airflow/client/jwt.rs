@@ -0,0 +1,228 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use log::{debug, info};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+/// Refresh tokens this long before they actually expire to absorb clock skew
+/// and requests that are already in flight
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// Airflow 3 auth manager authentication.
+/// Exchanges username/password for a JWT access token at `/auth/token`.
+///
+/// The access token is only ever kept in memory: it is cached (with its expiry)
+/// in `cache` which is skipped by serde, so it never ends up in the config file.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct JwtAuth {
+    pub username: String,
+    pub password: String,
+    #[serde(skip)]
+    cache: Arc<Mutex<Option<CachedToken>>>,
+}
+
+impl fmt::Debug for JwtAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtAuth")
+            .field("username", &self.username)
+            .field("password", &"***redacted***")
+            .finish_non_exhaustive()
+    }
+}
+
+/// An access token together with the moment it should no longer be used
+#[derive(Clone)]
+pub(crate) struct CachedToken {
+    pub(crate) token: String,
+    pub(crate) expires_at: Option<SystemTime>,
+}
+
+impl CachedToken {
+    pub(crate) fn is_valid(&self) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| SystemTime::now() + EXPIRY_MARGIN < expires_at)
+    }
+}
+
+#[derive(Serialize)]
+struct TokenRequest<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+impl JwtAuth {
+    pub fn new(username: String, password: String) -> Self {
+        Self {
+            username,
+            password,
+            cache: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Get a valid access token, requesting a new one from `/auth/token` if none
+    /// is cached or the cached one is about to expire.
+    /// The cache lock is held during the exchange so concurrent requests share one refresh.
+    pub async fn get_token(&self, client: &reqwest::Client, endpoint: &str) -> Result<String> {
+        let mut cache = self.cache.lock().await;
+        if let Some(cached) = cache.as_ref().filter(|c| c.is_valid()) {
+            return Ok(cached.token.clone());
+        }
+
+        let token = self.request_token(client, endpoint).await?;
+        let expires_at = jwt_expiry(&token);
+        debug!("🔑 Obtained JWT access token, expires at {expires_at:?}");
+        *cache = Some(CachedToken {
+            token: token.clone(),
+            expires_at,
+        });
+        Ok(token)
+    }
+
+    /// Drop the cached access token so the next request fetches a fresh one
+    pub async fn invalidate(&self) {
+        *self.cache.lock().await = None;
+    }
+
+    async fn request_token(&self, client: &reqwest::Client, endpoint: &str) -> Result<String> {
+        let username = crate::airflow::config::expand_env_vars(&self.username)?;
+        let password = crate::airflow::config::expand_env_vars(&self.password)?;
+
+        let mut base_endpoint = endpoint.to_string();
+        if !base_endpoint.ends_with('/') {
+            base_endpoint.push('/');
+        }
+        let url = Url::parse(&base_endpoint)?.join("auth/token")?;
+        info!("🔑 Requesting JWT access token for {username} at {url}");
+
+        let response = client
+            .post(url)
+            .json(&TokenRequest {
+                username: &username,
+                password: &password,
+            })
+            .send()
+            .await
+            .context("Failed to request JWT access token")?
+            .error_for_status()
+            .context("Airflow rejected the JWT token request, check username and password")?;
+
+        let body: TokenResponse = response
+            .json()
+            .await
+            .context("Invalid response from /auth/token")?;
+        Ok(body.access_token)
+    }
+}
+
+/// Read the `exp` claim from a JWT without verifying its signature.
+/// Returns None if the token is not a JWT or has no expiry.
+pub fn jwt_expiry(token: &str) -> Option<SystemTime> {
+    #[derive(Deserialize)]
+    struct Claims {
+        exp: Option<u64>,
+    }
+
+    let payload = token.split('.').nth(1)?;
+    let decoded = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    let claims: Claims = serde_json::from_slice(&decoded).ok()?;
+    claims.exp.map(|exp| UNIX_EPOCH + Duration::from_secs(exp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build an unsigned JWT with the given claims
+    fn make_jwt(claims: &serde_json::Value) -> String {
+        let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        format!(
+            "{}.{}.signature",
+            engine.encode(r#"{"alg":"HS512","typ":"JWT"}"#),
+            engine.encode(claims.to_string())
+        )
+    }
+
+    #[test]
+    fn test_jwt_expiry_reads_exp_claim() {
+        let token = make_jwt(&serde_json::json!({"sub": "admin", "exp": 1_900_000_000}));
+        assert_eq!(
+            jwt_expiry(&token),
+            Some(UNIX_EPOCH + Duration::from_secs(1_900_000_000))
+        );
+    }
+
+    #[test]
+    fn test_jwt_expiry_without_exp_or_invalid_token() {
+        let token = make_jwt(&serde_json::json!({"sub": "admin"}));
+        assert_eq!(jwt_expiry(&token), None);
+        assert_eq!(jwt_expiry("not-a-jwt"), None);
+    }
+
+    #[test]
+    fn test_jwt_auth_never_serializes_token() {
+        let auth = JwtAuth::new("admin".to_string(), "secret".to_string());
+        let serialized = toml::to_string(&auth).unwrap();
+        assert_eq!(serialized, "username = \"admin\"\npassword = \"secret\"\n");
+    }
+
+    #[tokio::test]
+    async fn test_get_token_caches_until_invalidated() {
+        let mut server = mockito::Server::new_async().await;
+        let token = make_jwt(&serde_json::json!({"exp": 4_000_000_000_u64}));
+        let mock = server
+            .mock("POST", "/auth/token")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"username": "admin", "password": "secret"}),
+            ))
+            .with_status(201)
+            .with_body(serde_json::json!({"access_token": token}).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let auth = JwtAuth::new("admin".to_string(), "secret".to_string());
+        let client = reqwest::Client::new();
+
+        assert_eq!(auth.get_token(&client, &server.url()).await.unwrap(), token);
+        // Served from the cache
+        assert_eq!(auth.get_token(&client, &server.url()).await.unwrap(), token);
+
+        auth.invalidate().await;
+        assert_eq!(auth.get_token(&client, &server.url()).await.unwrap(), token);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_token_refreshes_expired_token() {
+        let mut server = mockito::Server::new_async().await;
+        let expired = make_jwt(&serde_json::json!({"exp": 1}));
+        let mock = server
+            .mock("POST", "/auth/token")
+            .with_status(201)
+            .with_body(serde_json::json!({"access_token": expired}).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let auth = JwtAuth::new("admin".to_string(), "secret".to_string());
+        let client = reqwest::Client::new();
+
+        auth.get_token(&client, &server.url()).await.unwrap();
+        auth.get_token(&client, &server.url()).await.unwrap();
+
+        mock.assert_async().await;
+    }
+}
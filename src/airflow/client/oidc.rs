@@ -0,0 +1,579 @@
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{Context, Result};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use super::jwt::{jwt_expiry, CachedToken};
+use crate::app::environment_state::sanitize_filename;
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Default polling interval for the device flow when the provider doesn't specify one (RFC 8628)
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 5;
+
+/// OIDC / `OAuth2` authentication for Airflow deployments behind an SSO proxy.
+///
+/// On first use the device authorization flow is started: the verification page is opened
+/// in the browser and flowrs polls the identity provider until the login is approved.
+/// The refresh token is stored in the state directory (0600) so later sessions can obtain
+/// new access tokens silently. Access tokens are only kept in memory.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct OidcAuth {
+    pub issuer: String,
+    pub client_id: String,
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+    #[serde(skip)]
+    session: Arc<Mutex<OidcSession>>,
+    /// Held while a device login waits for the user, so only one login runs at a time
+    #[serde(skip)]
+    login: Arc<Mutex<()>>,
+    /// The device login waiting for the user, shown by the TUI
+    #[serde(skip)]
+    prompt: Arc<std::sync::Mutex<Option<DeviceLoginPrompt>>>,
+    /// Overrides the directory refresh tokens are stored in (used by tests)
+    #[serde(skip)]
+    token_dir: Option<PathBuf>,
+}
+
+impl fmt::Debug for OidcAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OidcAuth")
+            .field("issuer", &self.issuer)
+            .field("client_id", &self.client_id)
+            .field("scopes", &self.scopes)
+            .finish_non_exhaustive()
+    }
+}
+
+fn default_scopes() -> Vec<String> {
+    vec!["openid".to_string(), "offline_access".to_string()]
+}
+
+/// What the user has to do to approve a pending device login
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceLoginPrompt {
+    pub user_code: String,
+    pub verification_uri: String,
+}
+
+impl fmt::Display for DeviceLoginPrompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "🔑 Login required: visit {} and enter code {}",
+            self.verification_uri, self.user_code
+        )
+    }
+}
+
+#[derive(Default)]
+struct OidcSession {
+    provider: Option<ProviderMetadata>,
+    access_token: Option<CachedToken>,
+    refresh_token: Option<String>,
+}
+
+/// The subset of the `OpenID` provider metadata flowrs needs
+#[derive(Deserialize, Clone, Debug)]
+struct ProviderMetadata {
+    token_endpoint: String,
+    device_authorization_endpoint: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DeviceAuthorization {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    interval: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct StoredTokens {
+    refresh_token: String,
+}
+
+impl OidcAuth {
+    pub fn new(issuer: String, client_id: String, scopes: Vec<String>) -> Self {
+        Self {
+            issuer,
+            client_id,
+            scopes,
+            session: Arc::default(),
+            login: Arc::default(),
+            prompt: Arc::default(),
+            token_dir: None,
+        }
+    }
+
+    /// Get a valid access token.
+    /// Uses the cached access token if it is still valid, otherwise the (stored) refresh token,
+    /// and falls back to an interactive device login when no refresh token is available.
+    pub async fn get_token(&self, client: &reqwest::Client) -> Result<String> {
+        let provider = {
+            let mut session = self.session.lock().await;
+            if let Some(cached) = session.access_token.as_ref().filter(|t| t.is_valid()) {
+                return Ok(cached.token.clone());
+            }
+
+            let provider = if let Some(provider) = &session.provider {
+                provider.clone()
+            } else {
+                let provider = self.discover(client).await?;
+                session.provider = Some(provider.clone());
+                provider
+            };
+
+            if session.refresh_token.is_none() {
+                session.refresh_token = self.load_refresh_token();
+            }
+
+            if let Some(refresh_token) = session.refresh_token.clone() {
+                match self.refresh(client, &provider, &refresh_token).await {
+                    Ok(response) => return self.accept(&mut session, response),
+                    Err(e) => warn!("🔑 OIDC token refresh failed, starting a new login: {e:#}"),
+                }
+            }
+            provider
+        };
+
+        // The session stays unlocked while the user approves the login, which can take minutes
+        let _login = self.login.lock().await;
+        {
+            let session = self.session.lock().await;
+            if let Some(cached) = session.access_token.as_ref().filter(|t| t.is_valid()) {
+                // Another request completed the login while this one was waiting
+                return Ok(cached.token.clone());
+            }
+        }
+        let response = self.device_login(client, &provider).await;
+        self.prompt.lock().unwrap().take();
+        let response = response?;
+        self.accept(&mut *self.session.lock().await, response)
+    }
+
+    /// The device login currently waiting for the user, if any
+    pub fn pending_login(&self) -> Option<DeviceLoginPrompt> {
+        self.prompt.lock().unwrap().clone()
+    }
+
+    /// Cache the access token of a token response and store its refresh token
+    fn accept(&self, session: &mut OidcSession, response: TokenResponse) -> Result<String> {
+        // Providers that don't rotate refresh tokens omit them from the refresh response
+        let refresh_token = response.refresh_token.or_else(|| session.refresh_token.clone());
+        if refresh_token != session.refresh_token {
+            if let Some(refresh_token) = &refresh_token {
+                self.store_refresh_token(refresh_token)?;
+            }
+        }
+        session.refresh_token = refresh_token;
+
+        let expires_at = response
+            .expires_in
+            .map(|secs| SystemTime::now() + Duration::from_secs(secs))
+            .or_else(|| jwt_expiry(&response.access_token));
+        debug!("🔑 Obtained OIDC access token, expires at {expires_at:?}");
+        session.access_token = Some(CachedToken {
+            token: response.access_token.clone(),
+            expires_at,
+        });
+        Ok(response.access_token)
+    }
+
+    /// Drop the cached access token so the next request refreshes it.
+    /// The refresh token is kept.
+    pub async fn invalidate(&self) {
+        self.session.lock().await.access_token = None;
+    }
+
+    async fn discover(&self, client: &reqwest::Client) -> Result<ProviderMetadata> {
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.issuer.trim_end_matches('/')
+        );
+        debug!("🔑 Fetching OIDC provider metadata from {url}");
+        client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach OIDC issuer {}", self.issuer))?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("Invalid OIDC provider metadata at {url}"))
+    }
+
+    async fn refresh(
+        &self,
+        client: &reqwest::Client,
+        provider: &ProviderMetadata,
+        refresh_token: &str,
+    ) -> Result<TokenResponse> {
+        info!("🔑 Refreshing OIDC access token for client {}", self.client_id);
+        let response = client
+            .post(&provider.token_endpoint)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+                ("client_id", &self.client_id),
+            ])
+            .send()
+            .await?
+            .error_for_status()
+            .context("Identity provider rejected the refresh token")?;
+        Ok(response.json().await?)
+    }
+
+    /// Run the OAuth 2.0 device authorization grant (RFC 8628)
+    async fn device_login(
+        &self,
+        client: &reqwest::Client,
+        provider: &ProviderMetadata,
+    ) -> Result<TokenResponse> {
+        let endpoint = provider.device_authorization_endpoint.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "OIDC issuer {} does not support the device authorization flow",
+                self.issuer
+            )
+        })?;
+
+        let scope = self.scopes.join(" ");
+        let device: DeviceAuthorization = client
+            .post(endpoint)
+            .form(&[("client_id", self.client_id.as_str()), ("scope", scope.as_str())])
+            .send()
+            .await?
+            .error_for_status()
+            .context("Identity provider rejected the device authorization request")?
+            .json()
+            .await?;
+
+        let prompt = DeviceLoginPrompt {
+            user_code: device.user_code.clone(),
+            verification_uri: device.verification_uri.clone(),
+        };
+        *self.prompt.lock().unwrap() = Some(prompt);
+        prompt_user(&device);
+
+        let mut interval =
+            Duration::from_secs(device.interval.unwrap_or(DEFAULT_POLL_INTERVAL_SECS));
+        let deadline = Instant::now() + Duration::from_secs(device.expires_in);
+
+        while Instant::now() < deadline {
+            tokio::time::sleep(interval).await;
+
+            let response = client
+                .post(&provider.token_endpoint)
+                .form(&[
+                    ("grant_type", DEVICE_CODE_GRANT),
+                    ("device_code", device.device_code.as_str()),
+                    ("client_id", self.client_id.as_str()),
+                ])
+                .send()
+                .await?;
+
+            if response.status().is_success() {
+                info!("🔑 OIDC device login completed");
+                return Ok(response.json().await?);
+            }
+
+            let error: TokenErrorResponse = response
+                .json()
+                .await
+                .context("Invalid error response from the OIDC token endpoint")?;
+            match error.error.as_str() {
+                "authorization_pending" => {}
+                "slow_down" => interval += Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS),
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "OIDC device login failed: {} {}",
+                        error.error,
+                        error.error_description.unwrap_or_default()
+                    ))
+                }
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "OIDC device login timed out, code {} was not approved in time",
+            device.user_code
+        ))
+    }
+
+    fn refresh_token_path(&self) -> PathBuf {
+        let dir = self
+            .token_dir
+            .clone()
+            .unwrap_or_else(|| crate::get_state_dir().join("oidc"));
+        dir.join(format!(
+            "{}_{}.json",
+            sanitize_filename(self.issuer.trim_end_matches('/')),
+            sanitize_filename(&self.client_id)
+        ))
+    }
+
+    fn load_refresh_token(&self) -> Option<String> {
+        let content = fs::read_to_string(self.refresh_token_path()).ok()?;
+        let stored: StoredTokens = serde_json::from_str(&content).ok()?;
+        Some(stored.refresh_token)
+    }
+
+    /// Persist the refresh token, readable by the current user only
+    fn store_refresh_token(&self, refresh_token: &str) -> Result<()> {
+        let path = self.refresh_token_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string(&StoredTokens {
+            refresh_token: refresh_token.to_string(),
+        })?;
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        std::io::Write::write_all(&mut options.open(&path)?, content.as_bytes())
+            .with_context(|| format!("Failed to store OIDC refresh token at {}", path.display()))?;
+
+        // The mode only applies on creation, tighten permissions of pre-existing files too
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        }
+
+        debug!("🔑 Stored OIDC refresh token at {}", path.display());
+        Ok(())
+    }
+}
+
+/// Ask the user to approve the device login.
+/// The TUI owns the terminal, so the verification page is opened in the browser.
+/// The user code is shown by the TUI through `OidcAuth::pending_login` and logged.
+fn prompt_user(device: &DeviceAuthorization) {
+    warn!(
+        "🔑 OIDC login required: visit {} and enter code {}",
+        device.verification_uri, device.user_code
+    );
+    if cfg!(test) {
+        return;
+    }
+    let url = device
+        .verification_uri_complete
+        .as_deref()
+        .unwrap_or(&device.verification_uri);
+    if let Err(e) = webbrowser::open(url) {
+        warn!("Failed to open browser for OIDC login: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn test_auth(server: &mockito::ServerGuard, name: &str) -> OidcAuth {
+        let dir = std::env::temp_dir().join(format!("flowrs-oidc-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut auth = OidcAuth::new(server.url(), "flowrs".to_string(), default_scopes());
+        auth.token_dir = Some(dir);
+        auth
+    }
+
+    async fn mock_discovery(server: &mut mockito::ServerGuard) -> mockito::Mock {
+        let url = server.url();
+        server
+            .mock("GET", "/.well-known/openid-configuration")
+            .with_body(
+                serde_json::json!({
+                    "issuer": url,
+                    "token_endpoint": format!("{url}/token"),
+                    "device_authorization_endpoint": format!("{url}/device"),
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await
+    }
+
+    #[test]
+    fn test_oidc_auth_defaults_scopes_and_never_serializes_tokens() {
+        let auth: OidcAuth = toml::from_str(
+            r#"
+            issuer = "https://idp.example.com/realms/airflow"
+            client_id = "flowrs"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(auth.scopes, vec!["openid", "offline_access"]);
+
+        let serialized = toml::to_string(&auth).unwrap();
+        assert!(!serialized.contains("token"));
+    }
+
+    #[tokio::test]
+    async fn test_device_login_stores_refresh_token() {
+        let mut server = mockito::Server::new_async().await;
+        let discovery = mock_discovery(&mut server).await;
+        let device = server
+            .mock("POST", "/device")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("client_id".into(), "flowrs".into()),
+                Matcher::UrlEncoded("scope".into(), "openid offline_access".into()),
+            ]))
+            .with_body(
+                serde_json::json!({
+                    "device_code": "device-code",
+                    "user_code": "ABCD-EFGH",
+                    "verification_uri": "https://idp.example.com/device",
+                    "expires_in": 60,
+                    "interval": 0
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let pending = server
+            .mock("POST", "/token")
+            .match_body(Matcher::UrlEncoded("grant_type".into(), DEVICE_CODE_GRANT.into()))
+            .with_status(400)
+            .with_body(r#"{"error": "authorization_pending"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let approved = server
+            .mock("POST", "/token")
+            .match_body(Matcher::UrlEncoded("device_code".into(), "device-code".into()))
+            .with_body(
+                r#"{"access_token": "access-1", "refresh_token": "refresh-1", "expires_in": 300}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let auth = test_auth(&server, "device");
+        let client = reqwest::Client::new();
+
+        assert_eq!(auth.get_token(&client).await.unwrap(), "access-1");
+        // Served from the in-memory cache
+        assert_eq!(auth.get_token(&client).await.unwrap(), "access-1");
+
+        discovery.assert_async().await;
+        device.assert_async().await;
+        pending.assert_async().await;
+        approved.assert_async().await;
+
+        let path = auth.refresh_token_path();
+        assert_eq!(auth.load_refresh_token().as_deref(), Some("refresh-1"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_stored_refresh_token_is_used_and_rotated() {
+        let mut server = mockito::Server::new_async().await;
+        let _discovery = mock_discovery(&mut server).await;
+        let device = server
+            .mock("POST", "/device")
+            .expect(0)
+            .create_async()
+            .await;
+        let refresh = server
+            .mock("POST", "/token")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+                Matcher::UrlEncoded("refresh_token".into(), "stored".into()),
+            ]))
+            .with_body(r#"{"access_token": "access-2", "refresh_token": "rotated"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let auth = test_auth(&server, "refresh");
+        auth.store_refresh_token("stored").unwrap();
+        let client = reqwest::Client::new();
+
+        assert_eq!(auth.get_token(&client).await.unwrap(), "access-2");
+        assert_eq!(auth.load_refresh_token().as_deref(), Some("rotated"));
+
+        device.assert_async().await;
+        refresh.assert_async().await;
+        let _ = fs::remove_dir_all(auth.refresh_token_path().parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_pending_login_is_shown_without_locking_the_session() {
+        let mut server = mockito::Server::new_async().await;
+        let _discovery = mock_discovery(&mut server).await;
+        server
+            .mock("POST", "/device")
+            .with_body(
+                serde_json::json!({
+                    "device_code": "device-code",
+                    "user_code": "WXYZ-1234",
+                    "verification_uri": "https://idp.example.com/device",
+                    "expires_in": 60,
+                    "interval": 1
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/token")
+            .with_body(r#"{"access_token": "access-3", "expires_in": 300}"#)
+            .create_async()
+            .await;
+
+        let auth = test_auth(&server, "prompt");
+        let login = tokio::spawn({
+            let auth = auth.clone();
+            async move { auth.get_token(&reqwest::Client::new()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let prompt = auth.pending_login().unwrap();
+        assert_eq!(prompt.user_code, "WXYZ-1234");
+        assert!(prompt.to_string().contains("https://idp.example.com/device"));
+        // Other users of the session aren't blocked by the pending login
+        tokio::time::timeout(Duration::from_millis(100), auth.invalidate())
+            .await
+            .unwrap();
+
+        assert_eq!(login.await.unwrap().unwrap(), "access-3");
+        assert_eq!(auth.pending_login(), None);
+        let _ = fs::remove_dir_all(auth.refresh_token_path().parent().unwrap());
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+
+use crate::airflow::config::AirflowConfig;
+
+const DEFAULT_MAX_RETRIES: u32 = 2;
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Retry behaviour for idempotent requests, configured per server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on every following attempt
+    pub backoff: Duration,
+    /// Upper bound for a single delay, including server provided `Retry-After` values
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+}
+
+impl From<&AirflowConfig> for RetryPolicy {
+    fn from(config: &AirflowConfig) -> Self {
+        let default = Self::default();
+        Self {
+            max_retries: config.max_retries.unwrap_or(default.max_retries),
+            backoff: config.backoff_ms.map_or(default.backoff, Duration::from_millis),
+            max_backoff: config
+                .max_backoff_secs
+                .map_or(default.max_backoff, Duration::from_secs),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Statuses that indicate a transient problem worth retrying
+    pub fn is_retryable_status(status: StatusCode) -> bool {
+        matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT
+        )
+    }
+
+    /// Delay before retry number `attempt` (starting at 0).
+    /// A `Retry-After` header on the response takes precedence over the exponential backoff.
+    pub fn delay(&self, attempt: u32, response: Option<&reqwest::Response>) -> Duration {
+        let retry_after = response
+            .and_then(|r| r.headers().get(RETRY_AFTER))
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+
+        retry_after
+            .unwrap_or_else(|| self.backoff.saturating_mul(2_u32.saturating_pow(attempt)))
+            .min(self.max_backoff)
+    }
+}
+
+/// Parse a `Retry-After` value, either delay-seconds or an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    // Dates in the past mean "retry now"
+    Some(
+        (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_mins(2)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_exponential_backoff_is_capped() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(3),
+        };
+        assert_eq!(policy.delay(0, None), Duration::from_millis(500));
+        assert_eq!(policy.delay(1, None), Duration::from_secs(1));
+        assert_eq!(policy.delay(2, None), Duration::from_secs(2));
+        assert_eq!(policy.delay(3, None), Duration::from_secs(3));
+        assert_eq!(policy.delay(40, None), Duration::from_secs(3));
+    }
+}
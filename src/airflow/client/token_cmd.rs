@@ -0,0 +1,137 @@
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use log::{debug, info};
+
+use super::jwt::{jwt_expiry, CachedToken};
+use crate::airflow::config::TokenCmd;
+
+/// How long tokens from a helper command are reused when they have no `exp` claim
+/// and no `ttl_secs` is configured
+const DEFAULT_TOKEN_TTL: Duration = Duration::from_mins(5);
+
+impl TokenCmd {
+    /// Get the bearer token, either the (env-expanded) static token or the output
+    /// of the helper command. Helper output is cached until its TTL expires.
+    pub async fn get_token(&self) -> Result<String> {
+        let Some(cmd) = &self.cmd else {
+            let token = self.token.as_ref().ok_or_else(|| anyhow::anyhow!("Token not found"))?;
+            return crate::airflow::config::expand_env_vars(token.trim());
+        };
+
+        // Holding the lock while the helper runs makes concurrent requests share one run
+        let mut cache = self.cache.lock().await;
+        if let Some(cached) = cache.as_ref().filter(|c| c.is_valid()) {
+            return Ok(cached.token.clone());
+        }
+
+        let token = run_token_helper(cmd).await?;
+        let expires_at = match self.ttl_secs {
+            Some(ttl) => Some(SystemTime::now() + Duration::from_secs(ttl)),
+            None => jwt_expiry(&token).or_else(|| Some(SystemTime::now() + DEFAULT_TOKEN_TTL)),
+        };
+        debug!("🔑 Cached token from helper command, expires at {expires_at:?}");
+        *cache = Some(CachedToken {
+            token: token.clone(),
+            expires_at,
+        });
+        Ok(token)
+    }
+
+    /// Drop the cached helper token so the command runs again on the next request
+    pub async fn invalidate(&self) {
+        *self.cache.lock().await = None;
+    }
+}
+
+async fn run_token_helper(cmd: &str) -> Result<String> {
+    info!("🔑 Running token helper command");
+    let output = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .output()
+        .await
+        .context("Failed to run token helper command")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        return Err(anyhow::anyhow!(
+            "Token helper command failed with exit code {:?}\nstdout: {}\nstderr: {}",
+            output.status.code(),
+            stdout,
+            stderr
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)
+        .context("Token helper returned invalid UTF-8")?
+        .trim()
+        .replace('"', ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A helper command that counts its invocations in a temp file and prints the count
+    fn counting_helper(name: &str) -> (TokenCmd, std::path::PathBuf) {
+        let counter = std::env::temp_dir().join(format!(
+            "flowrs-token-cmd-{}-{name}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&counter);
+        let cmd = TokenCmd {
+            cmd: Some(format!(
+                "echo run >> {0}; echo token-$(wc -l < {0} | tr -d ' ')",
+                counter.display()
+            )),
+            ..Default::default()
+        };
+        (cmd, counter)
+    }
+
+    #[tokio::test]
+    async fn test_helper_token_is_cached_until_invalidated() {
+        let (cmd, counter) = counting_helper("cached");
+
+        assert_eq!(cmd.get_token().await.unwrap(), "token-1");
+        assert_eq!(cmd.get_token().await.unwrap(), "token-1");
+
+        cmd.invalidate().await;
+        assert_eq!(cmd.get_token().await.unwrap(), "token-2");
+
+        let _ = std::fs::remove_file(counter);
+    }
+
+    #[tokio::test]
+    async fn test_helper_token_ttl_expires() {
+        let (mut cmd, counter) = counting_helper("ttl");
+        cmd.ttl_secs = Some(0);
+
+        assert_eq!(cmd.get_token().await.unwrap(), "token-1");
+        assert_eq!(cmd.get_token().await.unwrap(), "token-2");
+
+        let _ = std::fs::remove_file(counter);
+    }
+
+    #[tokio::test]
+    async fn test_helper_failure_is_reported() {
+        let cmd = TokenCmd {
+            cmd: Some("echo oops >&2; exit 3".to_string()),
+            ..Default::default()
+        };
+        let err = cmd.get_token().await.unwrap_err().to_string();
+        assert!(err.contains("exit code Some(3)"));
+        assert!(err.contains("oops"));
+    }
+
+    #[test]
+    fn test_ttl_is_not_serialized_when_unset() {
+        let cmd = TokenCmd {
+            cmd: Some("echo token".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(toml::to_string(&cmd).unwrap(), "cmd = \"echo token\"\n");
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use log::{debug, info};
@@ -100,19 +102,21 @@ impl DagOperations for V1Client {
     }
 
     async fn get_dag_code(&self, dag: &crate::airflow::model::common::Dag) -> Result<String> {
-        let r = self
+        let response = self
             .base_api(Method::GET, &format!("dagSources/{}", dag.file_token))?
-            .build()?;
-        let response = self.base.client.execute(r).await?.error_for_status()?;
+            .send()
+            .await?
+            .error_for_status()?;
         let code = response.text().await?;
         Ok(code)
     }
 
     async fn get_dag_details(&self, dag_id: &str) -> Result<crate::airflow::model::common::Dag> {
-        let r = self
+        let response = self
             .base_api(Method::GET, &format!("dags/{}/details", dag_id))?
-            .build()?;
-        let response = self.base.client.execute(r).await?.error_for_status()?;
+            .send()
+            .await?
+            .error_for_status()?;
 
         // Try to get the response text first for better error messages
         let response_text = response.text().await?;
@@ -129,6 +133,15 @@ impl DagOperations for V1Client {
             }
         }
     }
+
+    // Airflow 2 has no favorites API, pins are kept locally per environment
+    async fn list_favorite_dags(&self) -> Result<HashSet<String>> {
+        self.favorites.load()
+    }
+
+    async fn set_dag_favorite(&self, dag_id: &str, favorite: bool) -> Result<()> {
+        self.favorites.set(dag_id, favorite)
+    }
 }
 
 #[cfg(test)]
@@ -4,7 +4,9 @@ mod connection;
 mod dag;
 mod dagrun;
 mod dagstats;
+mod eventlog;
 mod log;
+mod monitor;
 mod task;
 mod taskinstance;
 mod variable;
@@ -14,7 +16,9 @@ use async_trait::async_trait;
 use reqwest::Method;
 use url::{form_urlencoded, Url};
 
-use super::base::BaseClient;
+use super::base::{ApiRequest, BaseClient};
+use super::favorites::LocalFavorites;
+use super::oidc::DeviceLoginPrompt;
 use crate::airflow::{config::AirflowVersion, traits::AirflowClient};
 use crate::app::worker::OpenItem;
 
@@ -22,16 +26,18 @@ use crate::app::worker::OpenItem;
 #[derive(Debug, Clone)]
 pub struct V1Client {
     base: BaseClient,
+    favorites: LocalFavorites,
 }
 
 impl V1Client {
     const API_VERSION: &'static str = "api/v1";
 
     pub fn new(base: BaseClient) -> Self {
-        Self { base }
+        let favorites = LocalFavorites::for_environment(&base.config.name);
+        Self { base, favorites }
     }
 
-    fn base_api(&self, method: Method, endpoint: &str) -> Result<reqwest::RequestBuilder> {
+    fn base_api(&self, method: Method, endpoint: &str) -> Result<ApiRequest<'_>> {
         self.base.base_api(method, endpoint, Self::API_VERSION)
     }
 }
@@ -42,6 +48,10 @@ impl AirflowClient for V1Client {
         AirflowVersion::V2
     }
     
+    fn pending_login(&self) -> Option<DeviceLoginPrompt> {
+        self.base.pending_login()
+    }
+
     async fn get_import_error_count(&self) -> Result<usize> {
         let response = self
             .base_api(Method::GET, "importErrors")?
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventLogCollection {
+    pub event_logs: Vec<EventLogResponse>,
+    pub total_entries: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventLogResponse {
+    pub event_log_id: i64,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    pub when: Option<OffsetDateTime>,
+    pub dag_id: Option<String>,
+    pub task_id: Option<String>,
+    pub run_id: Option<String>,
+    pub map_index: Option<i64>,
+    pub try_number: Option<i64>,
+    pub event: String,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    pub execution_date: Option<OffsetDateTime>,
+    pub owner: Option<String>,
+    pub extra: Option<String>,
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthInfo {
+    pub metadatabase: Option<ComponentStatus>,
+    pub scheduler: Option<ComponentStatus>,
+    pub triggerer: Option<ComponentStatus>,
+    pub dag_processor: Option<ComponentStatus>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentStatus {
+    pub status: Option<String>,
+    #[serde(
+        default,
+        alias = "latest_scheduler_heartbeat",
+        alias = "latest_triggerer_heartbeat",
+        alias = "latest_dag_processor_heartbeat"
+    )]
+    pub latest_heartbeat: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub version: String,
+    pub git_version: Option<String>,
+}
+
+/// Any collection response, used to count entries without fetching them
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TotalEntries {
+    pub total_entries: i64,
+}
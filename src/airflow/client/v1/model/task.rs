@@ -13,4 +13,21 @@ pub struct TaskResponse {
     pub downstream_task_ids: Vec<String>,
     pub pool: Option<String>,
     pub retries: Option<f64>,
+    #[serde(default)]
+    pub class_ref: Option<ClassReference>,
+    #[serde(default)]
+    pub trigger_rule: Option<String>,
+    #[serde(default)]
+    pub queue: Option<String>,
+    #[serde(default)]
+    pub template_fields: Vec<String>,
+    /// Not part of the v1 specification, but returned since Airflow 2.9
+    #[serde(default)]
+    pub doc_md: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassReference {
+    pub class_name: Option<String>,
+    pub module_path: Option<String>,
 }
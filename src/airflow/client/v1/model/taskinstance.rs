@@ -45,6 +45,20 @@ pub struct TaskInstanceResponse {
     pub note: Option<String>,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskInstanceReferenceCollection {
+    pub task_instances: Vec<TaskInstanceReference>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskInstanceReference {
+    pub task_id: String,
+    pub dag_id: String,
+    pub dag_run_id: String,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    pub execution_date: Option<OffsetDateTime>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TriggerResponse {
     pub id: i64,
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use reqwest::Method;
+
+use super::model;
+use crate::airflow::{model::common::Health, traits::MonitorOperations};
+
+use super::V1Client;
+
+#[async_trait]
+impl MonitorOperations for V1Client {
+    async fn get_health(&self) -> Result<Health> {
+        let response = self
+            .base_api(Method::GET, "health")?
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let health = response.json::<model::monitor::HealthInfo>().await?;
+        Ok(health.into())
+    }
+
+    async fn get_server_version(&self) -> Result<String> {
+        let response = self
+            .base_api(Method::GET, "version")?
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let version = response.json::<model::monitor::VersionInfo>().await?;
+        Ok(version.version)
+    }
+
+    async fn count_dag_runs(&self, state: &str, since: DateTime<Utc>) -> Result<u64> {
+        let response = self
+            .base_api(Method::GET, "dags/~/dagRuns")?
+            .query(&[
+                ("state", state),
+                (
+                    "end_date_gte",
+                    &since.to_rfc3339_opts(SecondsFormat::Secs, true),
+                ),
+                ("limit", "1"),
+            ])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let count = response.json::<model::monitor::TotalEntries>().await?;
+        Ok(u64::try_from(count.total_entries).unwrap_or_default())
+    }
+
+    async fn count_task_instances(&self, state: &str) -> Result<u64> {
+        let response = self
+            .base_api(Method::GET, "dags/~/dagRuns/~/taskInstances")?
+            .query(&[("state", state), ("limit", "1")])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let count = response.json::<model::monitor::TotalEntries>().await?;
+        Ok(u64::try_from(count.total_entries).unwrap_or_default())
+    }
+}
@@ -4,7 +4,7 @@ use log::info;
 use reqwest::Method;
 
 use super::model;
-use crate::airflow::traits::TaskOperations;
+use crate::airflow::{model::common::Task, traits::TaskOperations};
 
 use super::V1Client;
 
@@ -28,4 +28,15 @@ impl TaskOperations for V1Client {
             .map(|t| (t.task_id, t.downstream_task_ids))
             .collect())
     }
+
+    async fn get_task(&self, dag_id: &str, task_id: &str) -> Result<Task> {
+        let task: model::task::TaskResponse = self
+            .base_api(Method::GET, &format!("dags/{dag_id}/tasks/{task_id}"))?
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(task.into())
+    }
 }
@@ -4,12 +4,61 @@ use log::{debug, info};
 use reqwest::{Method, Response};
 
 use super::model;
-use crate::airflow::{model::common::TaskInstanceList, traits::TaskInstanceOperations};
+use crate::airflow::{
+    model::common::{ClearTaskInstances, MarkScope, TaskInstanceList, TaskInstanceReference},
+    traits::TaskInstanceOperations,
+};
 
 use super::V1Client;
 
 const PAGE_SIZE: usize = 100;
 
+impl V1Client {
+    /// Set the state of a task instance. Only `updateTaskInstancesState` takes the related task
+    /// instances into account, but it can't mark a task as skipped, so it is used only when needed.
+    async fn set_task_instance_state(
+        &self,
+        dag_id: &str,
+        dag_run_id: &str,
+        task_id: &str,
+        status: &str,
+        scope: &MarkScope,
+        dry_run: bool,
+    ) -> Result<Vec<TaskInstanceReference>> {
+        if scope.is_empty() {
+            let task_instance = self
+                .base_api(
+                    Method::PATCH,
+                    &format!("dags/{dag_id}/dagRuns/{dag_run_id}/taskInstances/{task_id}"),
+                )?
+                .json(&serde_json::json!({"new_state": status, "dry_run": dry_run}))
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<model::taskinstance::TaskInstanceReference>()
+                .await?;
+            return Ok(vec![task_instance.into()]);
+        }
+        let task_instances = self
+            .base_api(
+                Method::POST,
+                &format!("dags/{dag_id}/updateTaskInstancesState"),
+            )?
+            .json(&scope.extend(serde_json::json!({
+                "dry_run": dry_run,
+                "task_id": task_id,
+                "dag_run_id": dag_run_id,
+                "new_state": status,
+            })))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<model::taskinstance::TaskInstanceReferenceCollection>()
+            .await?;
+        Ok(task_instances.task_instances.into_iter().map(Into::into).collect())
+    }
+}
+
 #[async_trait]
 impl TaskInstanceOperations for V1Client {
     async fn list_task_instances(
@@ -118,23 +167,73 @@ impl TaskInstanceOperations for V1Client {
         })
     }
 
-    async fn mark_task_instance(
+    async fn list_task_instances_in_state(&self, state: &str, limit: i64) -> Result<TaskInstanceList> {
+        let response: Response = self
+            .base_api(Method::GET, "dags/~/dagRuns/~/taskInstances")?
+            .query(&[("state", state), ("limit", &limit.to_string())])
+            .send()
+            .await?
+            .error_for_status()?;
+        let page: model::taskinstance::TaskInstanceCollectionResponse = response.json::<model::taskinstance::TaskInstanceCollectionResponse>().await?;
+        Ok(TaskInstanceList {
+            task_instances: page.task_instances.into_iter().map(Into::into).collect(),
+            total_entries: page.total_entries,
+        })
+    }
+
+    async fn preview_mark_task_instances(
         &self,
         dag_id: &str,
         dag_run_id: &str,
-        task_id: &str,
+        task_ids: &[String],
         status: &str,
+        scope: &MarkScope,
+    ) -> Result<Vec<TaskInstanceReference>> {
+        let mut task_instances = Vec::new();
+        for task_id in task_ids {
+            task_instances.extend(
+                self.set_task_instance_state(dag_id, dag_run_id, task_id, status, scope, true)
+                    .await?,
+            );
+        }
+        Ok(task_instances)
+    }
+
+    async fn mark_task_instances(
+        &self,
+        dag_id: &str,
+        dag_run_id: &str,
+        task_ids: &[String],
+        status: &str,
+        scope: &MarkScope,
+    ) -> Vec<(String, Result<()>)> {
+        // API v1 sets the state of one task at a time
+        let mut results = Vec::new();
+        for task_id in task_ids {
+            let result = self
+                .set_task_instance_state(dag_id, dag_run_id, task_id, status, scope, false)
+                .await
+                .map(|_| ());
+            results.push((task_id.clone(), result));
+        }
+        results
+    }
+
+    async fn set_task_instance_note(
+        &self,
+        dag_id: &str,
+        dag_run_id: &str,
+        task_id: &str,
+        note: &str,
     ) -> Result<()> {
-        let resp: Response = self
-            .base_api(
-                Method::PATCH,
-                &format!("dags/{dag_id}/dagRuns/{dag_run_id}/taskInstances/{task_id}"),
-            )?
-            .json(&serde_json::json!({"new_state": status, "dry_run": false}))
-            .send()
-            .await?
-            .error_for_status()?;
-        debug!("{resp:?}");
+        self.base_api(
+            Method::PATCH,
+            &format!("dags/{dag_id}/dagRuns/{dag_run_id}/taskInstances/{task_id}/setNote"),
+        )?
+        .json(&serde_json::json!({"note": note}))
+        .send()
+        .await?
+        .error_for_status()?;
         Ok(())
     }
 
@@ -162,4 +261,21 @@ impl TaskInstanceOperations for V1Client {
         debug!("{resp:?}");
         Ok(())
     }
+
+    async fn clear_task_instances(
+        &self,
+        dag_id: &str,
+        clear: &ClearTaskInstances,
+        dry_run: bool,
+    ) -> Result<Vec<TaskInstanceReference>> {
+        let cleared = self
+            .base_api(Method::POST, &format!("dags/{dag_id}/clearTaskInstances"))?
+            .json(&clear.body(dry_run))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<model::taskinstance::TaskInstanceReferenceCollection>()
+            .await?;
+        Ok(cleared.task_instances.into_iter().map(Into::into).collect())
+    }
 }
@@ -1,13 +1,49 @@
+use std::collections::HashSet;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use log::{debug, info};
 use reqwest::Method;
 
 use super::model;
-use crate::airflow::{model::common::DagList, traits::DagOperations};
+use crate::airflow::{
+    model::common::DagList,
+    traits::{DagOperations, MonitorOperations},
+};
 
 use super::V2Client;
 
+impl V2Client {
+    /// Airflow 3.1 added favorites and bulk task instance updates to the API
+    pub(super) async fn is_at_least_3_1(&self) -> Result<bool> {
+        if let Some(supported) = self.at_least_3_1.get() {
+            return Ok(*supported);
+        }
+        let version = self.get_server_version().await?;
+        let supported = is_at_least(&version, (3, 1));
+        debug!("Airflow {version} is at least 3.1: {supported}");
+        Ok(*self.at_least_3_1.get_or_init(|| supported))
+    }
+
+    /// Servers before Airflow 3.1 ignore the `is_favorite` filter and would return every DAG
+    async fn has_favorites_api(&self) -> Result<bool> {
+        self.is_at_least_3_1().await
+    }
+}
+
+/// Compare the major and minor part of a version such as `3.1.0` or `3.0.2rc1`
+fn is_at_least(version: &str, (major, minor): (u32, u32)) -> bool {
+    let mut parts = version.split('.').map(|part| {
+        part.chars()
+            .take_while(char::is_ascii_digit)
+            .collect::<String>()
+            .parse::<u32>()
+            .unwrap_or_default()
+    });
+    let actual = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    actual >= (major, minor)
+}
+
 #[async_trait]
 impl DagOperations for V2Client {
     async fn list_dags_paginated(&self, offset: i64, limit: i64) -> Result<DagList> {
@@ -85,19 +121,21 @@ impl DagOperations for V2Client {
     }
 
     async fn get_dag_code(&self, dag: &crate::airflow::model::common::Dag) -> Result<String> {
-        let r = self
+        let response = self
             .base_api(Method::GET, &format!("dagSources/{}", dag.dag_id))?
-            .build()?;
-        let response = self.base.client.execute(r).await?.error_for_status()?;
+            .send()
+            .await?
+            .error_for_status()?;
         let dag_source: model::dag::DagSource = response.json().await?;
         Ok(dag_source.content)
     }
 
     async fn get_dag_details(&self, dag_id: &str) -> Result<crate::airflow::model::common::Dag> {
-        let r = self
+        let response = self
             .base_api(Method::GET, &format!("dags/{}/details", dag_id))?
-            .build()?;
-        let response = self.base.client.execute(r).await?.error_for_status()?;
+            .send()
+            .await?
+            .error_for_status()?;
         
         response
             .json::<model::dag::Dag>()
@@ -105,13 +143,57 @@ impl DagOperations for V2Client {
             .map(std::convert::Into::into)
             .map_err(std::convert::Into::into)
     }
+
+    async fn list_favorite_dags(&self) -> Result<HashSet<String>> {
+        if !self.has_favorites_api().await? {
+            return self.favorites.load();
+        }
+        let mut favorites = HashSet::new();
+        let limit: usize = 100;
+        let mut offset = 0;
+        loop {
+            let page: model::dag::DagIdList = self
+                .base_api(Method::GET, "dags")?
+                .query(&[
+                    ("limit", limit.to_string()),
+                    ("offset", offset.to_string()),
+                    ("is_favorite", "true".to_string()),
+                ])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let fetched_count = page.dags.len();
+            favorites.extend(page.dags.into_iter().map(|dag| dag.dag_id));
+            let total = usize::try_from(page.total_entries).unwrap_or_default();
+            if fetched_count < limit || favorites.len() >= total {
+                break;
+            }
+            offset += limit;
+        }
+        debug!("Favorite DAGs fetched: {}", favorites.len());
+        Ok(favorites)
+    }
+
+    async fn set_dag_favorite(&self, dag_id: &str, favorite: bool) -> Result<()> {
+        if !self.has_favorites_api().await? {
+            return self.favorites.set(dag_id, favorite);
+        }
+        let action = if favorite { "favorite" } else { "unfavorite" };
+        self.base_api(Method::POST, &format!("dags/{dag_id}/{action}"))?
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::airflow::{
-        client::base::BaseClient,
+        client::{base::BaseClient, favorites::LocalFavorites},
         config::AirflowVersion,
         managed_services::conveyor::get_conveyor_environment_servers,
     };
@@ -154,4 +236,112 @@ mod tests {
         let code = client.get_dag_code(dag).await.unwrap();
         assert!(code.contains(&dag.dag_id));
     }
+
+    fn mock_client(endpoint: String) -> V2Client {
+        use crate::airflow::config::{AirflowAuth, AirflowConfig, BasicAuth, Protection};
+
+        V2Client::new(
+            BaseClient::new(AirflowConfig {
+                name: "test".to_string(),
+                endpoint,
+                auth: AirflowAuth::Basic(BasicAuth {
+                    username: "airflow".to_string(),
+                    password: "airflow".to_string(),
+                }),
+                managed: None,
+                version: AirflowVersion::V3,
+                proxy: None,
+                tls: None,
+                timeout_secs: None,
+                connect_timeout_secs: None,
+                max_retries: None,
+                backoff_ms: None,
+                max_backoff_secs: None,
+                protection: Protection::None,
+            })
+            .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_favorite_dags() {
+        let mut server = mockito::Server::new_async().await;
+        let version = server
+            .mock("GET", "/api/v2/version")
+            .with_body(r#"{"version": "3.1.0", "git_version": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let list = server
+            .mock("GET", "/api/v2/dags")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "is_favorite".into(),
+                "true".into(),
+            ))
+            .with_body(r#"{"dags": [{"dag_id": "etl"}, {"dag_id": "export"}], "total_entries": 2}"#)
+            .create_async()
+            .await;
+        let favorite = server
+            .mock("POST", "/api/v2/dags/etl/favorite")
+            .with_status(204)
+            .create_async()
+            .await;
+        let unfavorite = server
+            .mock("POST", "/api/v2/dags/export/unfavorite")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url());
+        let favorites = client.list_favorite_dags().await.unwrap();
+        assert_eq!(
+            favorites,
+            HashSet::from(["etl".to_string(), "export".to_string()])
+        );
+        client.set_dag_favorite("etl", true).await.unwrap();
+        client.set_dag_favorite("export", false).await.unwrap();
+
+        version.assert_async().await;
+        list.assert_async().await;
+        favorite.assert_async().await;
+        unfavorite.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_favorite_dags_are_kept_locally_before_airflow_3_1() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v2/version")
+            .with_body(r#"{"version": "3.0.2", "git_version": null}"#)
+            .create_async()
+            .await;
+        // Airflow 3.0 ignores the filter and would return every DAG
+        let list = server
+            .mock("GET", "/api/v2/dags")
+            .match_query(mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let path = std::env::temp_dir().join(format!("flowrs-v2-favorites-{}.json", std::process::id()));
+        let client = V2Client {
+            favorites: LocalFavorites::with_path(path.clone()),
+            ..mock_client(server.url())
+        };
+        client.set_dag_favorite("etl", true).await.unwrap();
+        assert_eq!(
+            client.list_favorite_dags().await.unwrap(),
+            HashSet::from(["etl".to_string()])
+        );
+        list.assert_async().await;
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_is_at_least() {
+        assert!(is_at_least("3.1.0", (3, 1)));
+        assert!(is_at_least("3.10.0", (3, 1)));
+        assert!(!is_at_least("3.0.2rc1", (3, 1)));
+        assert!(!is_at_least("", (3, 1)));
+    }
 }
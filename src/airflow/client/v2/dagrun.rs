@@ -40,6 +40,20 @@ impl DagRunOperations for V2Client {
         Ok(dagruns.into())
     }
 
+    async fn list_recent_dagruns_in_states(&self, states: &[&str], limit: i64) -> Result<DagRunList> {
+        let mut query: Vec<(&str, &str)> = states.iter().map(|state| ("state", *state)).collect();
+        let limit = limit.to_string();
+        query.extend([("order_by", "-start_date"), ("limit", limit.as_str())]);
+        let response: Response = self
+            .base_api(Method::GET, "dags/~/dagRuns")?
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?;
+        let dagruns: model::dagrun::DagRunList = response.json::<model::dagrun::DagRunList>().await?;
+        Ok(dagruns.into())
+    }
+
     async fn list_dagruns_batch(&self, dag_ids: Vec<String>, limit_per_dag: i64) -> Result<DagRunList> {
         let response = self.base.request_batch_dagruns("api/v2", dag_ids, limit_per_dag).await?;
         let dagruns: model::dagrun::DagRunList = response.json::<model::dagrun::DagRunList>().await?;
@@ -59,6 +73,20 @@ impl DagRunOperations for V2Client {
         Ok(())
     }
 
+    async fn set_dag_run_note(&self, dag_id: &str, dag_run_id: &str, note: &str) -> Result<()> {
+        self
+            .base_api(
+                Method::PATCH,
+                &format!("dags/{dag_id}/dagRuns/{dag_run_id}"),
+            )?
+            .query(&[("update_mask", "note")])
+            .json(&serde_json::json!({"note": note}))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
     async fn clear_dagrun(&self, dag_id: &str, dag_run_id: &str) -> Result<()> {
         self
             .base_api(
@@ -121,4 +149,53 @@ mod tests {
         let dagruns = client.list_dagruns(dag_id).await.unwrap();
         assert!(!dagruns.dag_runs.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_set_notes() {
+        use crate::airflow::traits::TaskInstanceOperations;
+
+        let mut server = mockito::Server::new_async().await;
+        let dag_run_note = server
+            .mock("PATCH", "/api/v2/dags/etl/dagRuns/manual__1")
+            .match_query(mockito::Matcher::UrlEncoded("update_mask".into(), "note".into()))
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"note": "rerun after upstream fix, see INC-123"}),
+            ))
+            .with_body("{}")
+            .create_async()
+            .await;
+        let task_note = server
+            .mock("PATCH", "/api/v2/dags/etl/dagRuns/manual__1/taskInstances/load")
+            .match_query(mockito::Matcher::UrlEncoded("update_mask".into(), "note".into()))
+            .match_body(mockito::Matcher::Json(serde_json::json!({"note": ""})))
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let config: crate::airflow::config::FlowrsConfig = toml::from_str(&format!(
+            r#"[[servers]]
+            name = "test"
+            endpoint = "{}"
+            version = "V3"
+
+            [servers.auth.Basic]
+            username = "airflow"
+            password = "airflow"
+            "#,
+            server.url()
+        ))
+        .unwrap();
+        let client = V2Client::new(BaseClient::new(config.servers.unwrap()[0].clone()).unwrap());
+
+        client
+            .set_dag_run_note("etl", "manual__1", "rerun after upstream fix, see INC-123")
+            .await
+            .unwrap();
+        client
+            .set_task_instance_note("etl", "manual__1", "load", "")
+            .await
+            .unwrap();
+        dag_run_note.assert_async().await;
+        task_note.assert_async().await;
+    }
 }
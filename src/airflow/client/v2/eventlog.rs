@@ -0,0 +1,96 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Method;
+
+use super::model;
+use crate::airflow::{
+    model::common::{EventLogList, EventLogQuery},
+    traits::EventLogOperations,
+};
+
+use super::V2Client;
+
+/// Number of event log entries fetched per query
+const EVENT_LOG_LIMIT: &str = "100";
+
+#[async_trait]
+impl EventLogOperations for V2Client {
+    async fn list_event_logs(&self, query: &EventLogQuery) -> Result<EventLogList> {
+        let response = self
+            .base_api(Method::GET, "eventLogs")?
+            .query(&[("order_by", "-when"), ("limit", EVENT_LOG_LIMIT)])
+            .query(&query.params())
+            .send()
+            .await?
+            .error_for_status()?;
+        let event_logs = response
+            .json::<model::eventlog::EventLogCollection>()
+            .await?;
+        Ok(event_logs.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airflow::client::base::BaseClient;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_list_event_logs() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v2/eventLogs")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("order_by".into(), "-when".into()),
+                Matcher::UrlEncoded("dag_id".into(), "etl".into()),
+                Matcher::UrlEncoded("run_id".into(), "manual__1".into()),
+            ]))
+            .with_body(
+                serde_json::json!({
+                    "event_logs": [{
+                        "event_log_id": 7,
+                        "when": "2024-03-10T11:58:12+00:00",
+                        "dag_id": "etl",
+                        "task_id": null,
+                        "run_id": "manual__1",
+                        "map_index": null,
+                        "try_number": null,
+                        "event": "clear",
+                        "logical_date": "2024-03-10T00:00:00+00:00",
+                        "owner": "alice",
+                        "extra": null
+                    }],
+                    "total_entries": 1
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let config: crate::airflow::config::FlowrsConfig = toml::from_str(&format!(
+            r#"[[servers]]
+            name = "test"
+            endpoint = "{}"
+            version = "V3"
+
+            [servers.auth.Basic]
+            username = "airflow"
+            password = "airflow"
+            "#,
+            server.url()
+        ))
+        .unwrap();
+        let client = V2Client::new(BaseClient::new(config.servers.unwrap()[0].clone()).unwrap());
+
+        let event_logs = client
+            .list_event_logs(&EventLogQuery::for_dag_run("etl", "manual__1"))
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert_eq!(event_logs.total_entries, 1);
+        assert_eq!(event_logs.event_logs[0].event, "clear");
+        assert_eq!(event_logs.event_logs[0].owner.as_deref(), Some("alice"));
+        assert!(event_logs.event_logs[0].logical_date.is_some());
+    }
+}
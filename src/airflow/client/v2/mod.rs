@@ -4,17 +4,23 @@ mod connection;
 mod dag;
 mod dagrun;
 mod dagstats;
+mod eventlog;
 mod log;
+mod monitor;
 mod task;
 mod taskinstance;
 mod variable;
 
+use std::sync::{Arc, OnceLock};
+
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::Method;
 use url::{form_urlencoded, Url};
 
-use super::base::BaseClient;
+use super::base::{ApiRequest, BaseClient};
+use super::favorites::LocalFavorites;
+use super::oidc::DeviceLoginPrompt;
 use crate::airflow::{config::AirflowVersion, traits::AirflowClient};
 use crate::app::worker::OpenItem;
 
@@ -22,16 +28,25 @@ use crate::app::worker::OpenItem;
 #[derive(Debug, Clone)]
 pub struct V2Client {
     base: BaseClient,
+    /// Pins of servers without a favorites API (before Airflow 3.1)
+    favorites: LocalFavorites,
+    /// Whether the server runs Airflow 3.1 or later, looked up once
+    at_least_3_1: Arc<OnceLock<bool>>,
 }
 
 impl V2Client {
     const API_VERSION: &'static str = "api/v2";
 
     pub fn new(base: BaseClient) -> Self {
-        Self { base }
+        let favorites = LocalFavorites::for_environment(&base.config.name);
+        Self {
+            base,
+            favorites,
+            at_least_3_1: Arc::default(),
+        }
     }
 
-    fn base_api(&self, method: Method, endpoint: &str) -> Result<reqwest::RequestBuilder> {
+    fn base_api(&self, method: Method, endpoint: &str) -> Result<ApiRequest<'_>> {
         self.base.base_api(method, endpoint, Self::API_VERSION)
     }
 }
@@ -42,6 +57,10 @@ impl AirflowClient for V2Client {
         AirflowVersion::V3
     }
     
+    fn pending_login(&self) -> Option<DeviceLoginPrompt> {
+        self.base.pending_login()
+    }
+
     async fn get_import_error_count(&self) -> Result<usize> {
         let response = self
             .base_api(Method::GET, "importErrors")?
@@ -7,6 +7,18 @@ pub struct DagList {
     pub total_entries: i64,
 }
 
+/// A DAG list of which only the ids are needed, e.g. the favorite DAGs
+#[derive(Debug, Deserialize)]
+pub struct DagIdList {
+    pub dags: Vec<DagId>,
+    pub total_entries: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DagId {
+    pub dag_id: String,
+}
+
 #[allow(clippy::struct_excessive_bools, clippy::struct_field_names)]
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
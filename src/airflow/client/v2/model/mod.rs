@@ -2,8 +2,10 @@ pub mod connection;
 pub mod dag;
 pub mod dagrun;
 pub mod dagstats;
+pub mod eventlog;
 pub mod importerror;
 pub mod log;
+pub mod monitor;
 pub mod task;
 pub mod taskinstance;
 pub mod variable;
\ No newline at end of file
@@ -13,4 +13,14 @@ pub struct TaskResponse {
     pub downstream_task_ids: Vec<String>,
     pub pool: Option<String>,
     pub retries: Option<f64>,
+    #[serde(default)]
+    pub operator_name: Option<String>,
+    #[serde(default)]
+    pub trigger_rule: Option<String>,
+    #[serde(default)]
+    pub queue: Option<String>,
+    #[serde(default)]
+    pub template_fields: Option<Vec<String>>,
+    #[serde(default)]
+    pub doc_md: Option<String>,
 }
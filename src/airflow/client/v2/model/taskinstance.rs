@@ -81,3 +81,33 @@ pub struct Job {
     pub hostname: Option<String>,
     pub unixname: Option<String>,
 }
+
+/// The task instances affected by clearing or marking, only the fields shown in previews
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskInstanceReferenceList {
+    pub task_instances: Vec<TaskInstanceReference>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskInstanceReference {
+    pub task_id: String,
+    pub dag_id: String,
+    pub dag_run_id: String,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    pub logical_date: Option<OffsetDateTime>,
+    pub state: Option<String>,
+}
+
+/// Result of a bulk request, only updates are sent by flowrs
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkResponse {
+    pub update: Option<BulkActionResponse>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkActionResponse {
+    #[serde(default)]
+    pub success: Vec<String>,
+    #[serde(default)]
+    pub errors: Vec<serde_json::Value>,
+}
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use reqwest::Method;
+
+use super::model;
+use crate::airflow::{model::common::Health, traits::MonitorOperations};
+
+use super::V2Client;
+
+#[async_trait]
+impl MonitorOperations for V2Client {
+    async fn get_health(&self) -> Result<Health> {
+        let response = self
+            .base_api(Method::GET, "monitor/health")?
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let health = response.json::<model::monitor::HealthInfo>().await?;
+        Ok(health.into())
+    }
+
+    async fn get_server_version(&self) -> Result<String> {
+        let response = self
+            .base_api(Method::GET, "version")?
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let version = response.json::<model::monitor::VersionInfo>().await?;
+        Ok(version.version)
+    }
+
+    async fn count_dag_runs(&self, state: &str, since: DateTime<Utc>) -> Result<u64> {
+        let response = self
+            .base_api(Method::GET, "dags/~/dagRuns")?
+            .query(&[
+                ("state", state),
+                (
+                    "end_date_gte",
+                    &since.to_rfc3339_opts(SecondsFormat::Secs, true),
+                ),
+                ("limit", "1"),
+            ])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let count = response.json::<model::monitor::TotalEntries>().await?;
+        Ok(u64::try_from(count.total_entries).unwrap_or_default())
+    }
+
+    async fn count_task_instances(&self, state: &str) -> Result<u64> {
+        let response = self
+            .base_api(Method::GET, "dags/~/dagRuns/~/taskInstances")?
+            .query(&[("state", state), ("limit", "1")])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let count = response.json::<model::monitor::TotalEntries>().await?;
+        Ok(u64::try_from(count.total_entries).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airflow::client::BaseClient;
+    use crate::airflow::config::{AirflowAuth, AirflowConfig, AirflowVersion, BasicAuth, Protection};
+
+    fn test_client(endpoint: String) -> V2Client {
+        V2Client::new(
+            BaseClient::new(AirflowConfig {
+                name: "test".to_string(),
+                endpoint,
+                auth: AirflowAuth::Basic(BasicAuth {
+                    username: "airflow".to_string(),
+                    password: "airflow".to_string(),
+                }),
+                managed: None,
+                version: AirflowVersion::V3,
+                proxy: None,
+                tls: None,
+                timeout_secs: None,
+                connect_timeout_secs: None,
+                max_retries: None,
+                backoff_ms: None,
+                max_backoff_secs: None,
+                protection: Protection::None,
+            })
+            .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_monitor_operations() {
+        let mut server = mockito::Server::new_async().await;
+        let health = server
+            .mock("GET", "/api/v2/monitor/health")
+            .with_body(
+                r#"{
+                    "metadatabase": {"status": "healthy"},
+                    "scheduler": {"status": "healthy", "latest_scheduler_heartbeat": "2025-01-01T00:00:00Z"},
+                    "dag_processor": {"status": "unhealthy", "latest_dag_processor_heartbeat": null}
+                }"#,
+            )
+            .create_async()
+            .await;
+        let version = server
+            .mock("GET", "/api/v2/version")
+            .with_body(r#"{"version": "3.0.2", "git_version": null}"#)
+            .create_async()
+            .await;
+        let failed_runs = server
+            .mock("GET", "/api/v2/dags/~/dagRuns")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("state".into(), "failed".into()),
+                mockito::Matcher::UrlEncoded("end_date_gte".into(), "2025-01-01T00:00:00Z".into()),
+                mockito::Matcher::UrlEncoded("limit".into(), "1".into()),
+            ]))
+            .with_body(r#"{"dag_runs": [], "total_entries": 7}"#)
+            .create_async()
+            .await;
+        let queued = server
+            .mock("GET", "/api/v2/dags/~/dagRuns/~/taskInstances")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "state".into(),
+                "queued".into(),
+            ))
+            .with_body(r#"{"task_instances": [], "total_entries": 3}"#)
+            .create_async()
+            .await;
+
+        let client = test_client(server.url());
+        let since = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let result = client.get_health().await.unwrap();
+        assert_eq!(result.unhealthy_components(), vec!["dag_processor"]);
+        assert_eq!(client.get_server_version().await.unwrap(), "3.0.2");
+        assert_eq!(client.count_dag_runs("failed", since).await.unwrap(), 7);
+        assert_eq!(client.count_task_instances("queued").await.unwrap(), 3);
+
+        health.assert_async().await;
+        version.assert_async().await;
+        failed_runs.assert_async().await;
+        queued.assert_async().await;
+    }
+}
@@ -1,14 +1,87 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use log::{debug, info};
 use reqwest::{Method, Response};
 
 use super::model;
-use crate::airflow::{model::common::TaskInstanceList, traits::TaskInstanceOperations};
+use crate::airflow::{
+    model::common::{ClearTaskInstances, MarkScope, TaskInstanceList, TaskInstanceReference},
+    traits::TaskInstanceOperations,
+};
 
 use super::V2Client;
 const PAGE_SIZE: usize = 100;
 
+/// The error of a failed bulk request, shared by all its tasks without losing its source chain
+#[derive(Debug)]
+struct BulkError(Arc<anyhow::Error>);
+
+impl std::fmt::Display for BulkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for BulkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl V2Client {
+    async fn mark_task_instance(
+        &self,
+        dag_id: &str,
+        dag_run_id: &str,
+        task_id: &str,
+        status: &str,
+        scope: &MarkScope,
+    ) -> Result<()> {
+        let resp: Response = self
+            .base_api(
+                Method::PATCH,
+                &format!("dags/{dag_id}/dagRuns/{dag_run_id}/taskInstances/{task_id}"),
+            )?
+            .json(&scope.extend(serde_json::json!({"new_state": status, "dry_run": false})))
+            .send()
+            .await?
+            .error_for_status()?;
+        debug!("{resp:?}");
+        Ok(())
+    }
+
+    async fn bulk_mark_task_instances(
+        &self,
+        dag_id: &str,
+        dag_run_id: &str,
+        task_ids: &[String],
+        status: &str,
+        scope: &MarkScope,
+    ) -> Result<model::taskinstance::BulkResponse> {
+        let entities: Vec<serde_json::Value> = task_ids
+            .iter()
+            .map(|task_id| {
+                scope.extend(serde_json::json!({"task_id": task_id, "new_state": status}))
+            })
+            .collect();
+        let response = self
+            .base_api(
+                Method::PATCH,
+                &format!("dags/{dag_id}/dagRuns/{dag_run_id}/taskInstances"),
+            )?
+            .json(&serde_json::json!({"actions": [{"action": "update", "entities": entities}]}))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<model::taskinstance::BulkResponse>()
+            .await?;
+        debug!("{response:?}");
+        Ok(response)
+    }
+}
+
 #[async_trait]
 impl TaskInstanceOperations for V2Client {
     async fn list_task_instances(
@@ -110,23 +183,124 @@ impl TaskInstanceOperations for V2Client {
         })
     }
 
-    async fn mark_task_instance(
+    async fn list_task_instances_in_state(&self, state: &str, limit: i64) -> Result<TaskInstanceList> {
+        let response: Response = self
+            .base_api(Method::GET, "dags/~/dagRuns/~/taskInstances")?
+            .query(&[("state", state), ("limit", &limit.to_string())])
+            .send()
+            .await?
+            .error_for_status()?;
+        let page: model::taskinstance::TaskInstanceList = response.json::<model::taskinstance::TaskInstanceList>().await?;
+        Ok(TaskInstanceList {
+            task_instances: page.task_instances.into_iter().map(Into::into).collect(),
+            total_entries: page.total_entries,
+        })
+    }
+
+    async fn preview_mark_task_instances(
         &self,
         dag_id: &str,
         dag_run_id: &str,
-        task_id: &str,
+        task_ids: &[String],
         status: &str,
+        scope: &MarkScope,
+    ) -> Result<Vec<TaskInstanceReference>> {
+        let mut task_instances = Vec::new();
+        for task_id in task_ids {
+            let page = self
+                .base_api(
+                    Method::PATCH,
+                    &format!("dags/{dag_id}/dagRuns/{dag_run_id}/taskInstances/{task_id}/dry_run"),
+                )?
+                .json(&scope.extend(serde_json::json!({"new_state": status})))
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<model::taskinstance::TaskInstanceReferenceList>()
+                .await?;
+            task_instances.extend(page.task_instances.into_iter().map(Into::into));
+        }
+        Ok(task_instances)
+    }
+
+    async fn mark_task_instances(
+        &self,
+        dag_id: &str,
+        dag_run_id: &str,
+        task_ids: &[String],
+        status: &str,
+        scope: &MarkScope,
+    ) -> Vec<(String, Result<()>)> {
+        // The bulk endpoint was only added in Airflow 3.1, older servers and a single task are
+        // patched one task at a time
+        let use_bulk_api = task_ids.len() > 1
+            && match self.is_at_least_3_1().await {
+                Ok(supported) => supported,
+                Err(e) => {
+                    debug!("Failed to get the server version, marking one task at a time: {e}");
+                    false
+                }
+            };
+        if !use_bulk_api {
+            let mut results = Vec::new();
+            for task_id in task_ids {
+                let result = self
+                    .mark_task_instance(dag_id, dag_run_id, task_id, status, scope)
+                    .await;
+                results.push((task_id.clone(), result));
+            }
+            return results;
+        }
+        match self
+            .bulk_mark_task_instances(dag_id, dag_run_id, task_ids, status, scope)
+            .await
+        {
+            // A bulk request succeeds as a whole even when some of its entities failed
+            Ok(response) => {
+                let update = response.update.unwrap_or_default();
+                let errors: Vec<String> = update.errors.iter().map(ToString::to_string).collect();
+                task_ids
+                    .iter()
+                    .map(|task_id| {
+                        let result = if errors.is_empty() || update.success.contains(task_id) {
+                            Ok(())
+                        } else {
+                            Err(anyhow::anyhow!(
+                                "Failed to mark task instances: {}",
+                                errors.join(", ")
+                            ))
+                        };
+                        (task_id.clone(), result)
+                    })
+                    .collect()
+            }
+            Err(e) => {
+                let e = Arc::new(e);
+                task_ids
+                    .iter()
+                    .map(|task_id| (task_id.clone(), Err(BulkError(Arc::clone(&e)).into())))
+                    .collect()
+            }
+        }
+    }
+
+    async fn set_task_instance_note(
+        &self,
+        dag_id: &str,
+        dag_run_id: &str,
+        task_id: &str,
+        note: &str,
     ) -> Result<()> {
-        let resp: Response = self
-            .base_api(
-                Method::PATCH,
-                &format!("dags/{dag_id}/dagRuns/{dag_run_id}/taskInstances/{task_id}"),
-            )?
-            .json(&serde_json::json!({"new_state": status, "dry_run": false}))
-            .send()
-            .await?
-            .error_for_status()?;
-        debug!("{resp:?}");
+        // Only the note is patched, the state of the task instance is left alone
+        self.base_api(
+            Method::PATCH,
+            &format!("dags/{dag_id}/dagRuns/{dag_run_id}/taskInstances/{task_id}"),
+        )?
+        .query(&[("update_mask", "note")])
+        .json(&serde_json::json!({"note": note}))
+        .send()
+        .await?
+        .error_for_status()?;
         Ok(())
     }
 
@@ -154,6 +328,23 @@ impl TaskInstanceOperations for V2Client {
         debug!("{resp:?}");
         Ok(())
     }
+
+    async fn clear_task_instances(
+        &self,
+        dag_id: &str,
+        clear: &ClearTaskInstances,
+        dry_run: bool,
+    ) -> Result<Vec<TaskInstanceReference>> {
+        let cleared = self
+            .base_api(Method::POST, &format!("dags/{dag_id}/clearTaskInstances"))?
+            .json(&clear.body(dry_run))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<model::taskinstance::TaskInstanceReferenceList>()
+            .await?;
+        Ok(cleared.task_instances.into_iter().map(Into::into).collect())
+    }
 }
 
 #[cfg(test)]
@@ -176,6 +367,177 @@ mod tests {
         V2Client::new(base)
     }
 
+    fn get_mock_client(url: &str) -> V2Client {
+        let config: crate::airflow::config::FlowrsConfig = toml::from_str(&format!(
+            r#"[[servers]]
+            name = "test"
+            endpoint = "{url}"
+            version = "V3"
+
+            [servers.auth.Basic]
+            username = "airflow"
+            password = "airflow"
+            "#
+        ))
+        .unwrap();
+        V2Client::new(BaseClient::new(config.servers.unwrap()[0].clone()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_clear_task_instances_dry_run() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v2/dags/etl/clearTaskInstances")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "dry_run": true,
+                "only_failed": true,
+                "start_date": "2024-03-01T00:00:00Z",
+            })))
+            .with_body(
+                serde_json::json!({
+                    "task_instances": [{
+                        "id": "0191",
+                        "task_id": "load",
+                        "dag_id": "etl",
+                        "dag_run_id": "scheduled__2024-03-02",
+                        "map_index": -1,
+                        "logical_date": "2024-03-02T00:00:00+00:00",
+                        "state": "upstream_failed"
+                    }],
+                    "total_entries": 1
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = get_mock_client(&server.url());
+
+        let clear = ClearTaskInstances {
+            start_date: Some(time::macros::datetime!(2024-03-01 00:00 UTC)),
+            end_date: None,
+            only_failed: true,
+        };
+        let cleared = client.clear_task_instances("etl", &clear, true).await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(cleared.len(), 1);
+        assert_eq!(cleared[0].task_id, "load");
+        assert_eq!(cleared[0].state.as_deref(), Some("upstream_failed"));
+        assert!(cleared[0].logical_date.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_mark_single_task_instance() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PATCH", "/api/v2/dags/etl/dagRuns/manual__1/taskInstances/load")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "new_state": "success",
+                "include_upstream": true,
+            })))
+            .with_body(r#"{"task_instances": [], "total_entries": 0}"#)
+            .create_async()
+            .await;
+        let client = get_mock_client(&server.url());
+
+        let scope = MarkScope {
+            upstream: true,
+            ..Default::default()
+        };
+        let results = client
+            .mark_task_instances("etl", "manual__1", &["load".to_string()], "success", &scope)
+            .await;
+        assert!(results[0].1.is_ok());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_mark_task_instances_in_bulk() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v2/version")
+            .with_body(r#"{"version": "3.1.0", "git_version": null}"#)
+            .create_async()
+            .await;
+        let mock = server
+            .mock("PATCH", "/api/v2/dags/etl/dagRuns/manual__1/taskInstances")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "actions": [{
+                    "action": "update",
+                    "entities": [
+                        {"task_id": "extract", "new_state": "failed", "include_upstream": false,
+                         "include_downstream": true, "include_future": false, "include_past": false},
+                        {"task_id": "load", "new_state": "failed", "include_upstream": false,
+                         "include_downstream": true, "include_future": false, "include_past": false},
+                    ]
+                }]
+            })))
+            .with_body(
+                serde_json::json!({
+                    "update": {
+                        "success": ["extract"],
+                        "errors": [{"error": "Task instance load not found", "status_code": 404}]
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let client = get_mock_client(&server.url());
+
+        let scope = MarkScope {
+            downstream: true,
+            ..Default::default()
+        };
+        let task_ids = vec!["extract".to_string(), "load".to_string()];
+        let results = client
+            .mark_task_instances("etl", "manual__1", &task_ids, "failed", &scope)
+            .await;
+        mock.assert_async().await;
+        // A bulk request succeeds as a whole even when some of its entities failed
+        assert_eq!(results[0].0, "extract");
+        assert!(results[0].1.is_ok());
+        assert_eq!(results[1].0, "load");
+        let error = results[1].1.as_ref().unwrap_err();
+        assert!(error.to_string().contains("load not found"));
+    }
+
+    #[tokio::test]
+    async fn test_mark_task_instances_one_at_a_time_before_3_1() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v2/version")
+            .with_body(r#"{"version": "3.0.2", "git_version": null}"#)
+            .create_async()
+            .await;
+        let bulk = server
+            .mock("PATCH", "/api/v2/dags/etl/dagRuns/manual__1/taskInstances")
+            .expect(0)
+            .create_async()
+            .await;
+        let extract = server
+            .mock("PATCH", "/api/v2/dags/etl/dagRuns/manual__1/taskInstances/extract")
+            .with_body(r#"{"task_instances": [], "total_entries": 0}"#)
+            .create_async()
+            .await;
+        let load = server
+            .mock("PATCH", "/api/v2/dags/etl/dagRuns/manual__1/taskInstances/load")
+            .with_status(404)
+            .create_async()
+            .await;
+        let client = get_mock_client(&server.url());
+
+        let task_ids = vec!["extract".to_string(), "load".to_string()];
+        let results = client
+            .mark_task_instances("etl", "manual__1", &task_ids, "failed", &MarkScope::default())
+            .await;
+        bulk.assert_async().await;
+        extract.assert_async().await;
+        load.assert_async().await;
+        assert!(results[0].1.is_ok());
+        assert!(results[1].1.is_err());
+    }
+
     #[tokio::test]
     // TODO: use a docker-compose Airflow v3 setup for testing instead
     async fn test_list_task_instances() {
@@ -9,10 +9,13 @@ use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
 use super::managed_services::astronomer::get_astronomer_environment_servers;
+use super::managed_services::composer::{get_composer_environment_servers, ComposerDiscoveryConfig};
 use super::managed_services::conveyor::get_conveyor_environment_servers;
+use super::managed_services::discovery::{DiscoveryCache, DiscoveryConfig};
 use super::managed_services::mwaa::get_mwaa_environment_servers;
+use super::watch::WatchConfig;
 use crate::CONFIG_FILE;
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 /// Expands environment variables in a string value.
 /// Supports ${VAR} and $VAR syntax.
@@ -109,9 +112,14 @@ impl Display for ManagedService {
 /// when daylight saving time changes (e.g., EST "-05:00" vs EDT "-04:00").
 ///
 /// # Note on Active Environment
-/// The active environment is not persisted. Users must select an environment
+/// By default the active environment is not persisted. Users must select an environment
 /// on each startup. This design prevents confusion from stale cached data and ensures
 /// explicit environment awareness when working with multiple Airflow instances.
+///
+/// Setting `restore_session = true` opts out of this: the last environment, panel,
+/// selected DAG/run/task, filters, sort columns and log level are saved to the state
+/// directory on exit and restored on the next startup. The active environment is always
+/// shown in the top line.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct FlowrsConfig {
     pub servers: Option<Vec<AirflowConfig>>,
@@ -120,6 +128,18 @@ pub struct FlowrsConfig {
     pub show_init_screen: bool,
     #[serde(default = "default_timezone_offset")]
     pub timezone_offset: String,
+    /// Restore the last environment and navigation state on startup
+    #[serde(default)]
+    pub restore_session: bool,
+    /// Projects and locations searched by the Google Cloud Composer managed service
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub composer: Option<ComposerDiscoveryConfig>,
+    /// Filters and caching for the other managed services
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discovery: Option<DiscoveryConfig>,
+    /// Failure watcher used by `flowrs watch` and optionally the TUI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch: Option<WatchConfig>,
     #[serde(skip_serializing)]
     pub path: Option<PathBuf>,
 }
@@ -141,11 +161,102 @@ pub struct AirflowConfig {
     #[serde(default)]
     pub version: AirflowVersion,
     pub proxy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+    /// Total request timeout in seconds (default: 5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Timeout for establishing the connection in seconds (default: the request timeout)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+    /// How often idempotent GET requests are retried on 429/502/503/504 (default: 2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    /// Delay before the first retry in milliseconds, doubled on each attempt (default: 500)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff_ms: Option<u64>,
+    /// Upper bound for the delay between retries in seconds, also caps `Retry-After` (default: 30)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_backoff_secs: Option<u64>,
+    /// Guard against accidental changes, e.g. `protection = "confirm"` for production
+    #[serde(default, skip_serializing_if = "Protection::is_none")]
+    pub protection: Protection,
+}
+
+/// How changes to an environment (pausing, clearing, marking, triggering, editing notes) are guarded
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Protection {
+    /// Changes are sent right away
+    #[default]
+    None,
+    /// The environment name has to be typed before a change is sent
+    Confirm,
+    /// Changes are disabled and hidden from the help
+    ReadOnly,
+}
+
+impl Protection {
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    fn is_none(&self) -> bool {
+        *self == Protection::None
+    }
+}
+
+/// Per-server TLS settings, on top of the default (webpki) root certificates.
+/// Paths may contain `~` and environment variables.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// PEM bundle with additional CA certificates to trust, e.g. for an internal PKI
+    pub ca_bundle: Option<String>,
+    /// PEM client certificate for mTLS protected ingresses
+    pub client_cert: Option<String>,
+    /// PEM private key for `client_cert`, may be omitted if the key is in the certificate file
+    pub client_key: Option<String>,
+    /// Skip certificate verification altogether. Only meant for local development clusters.
+    #[serde(default)]
+    pub danger_accept_invalid_certs: bool,
+}
+
+impl TlsConfig {
+    /// Expand `~` and environment variables in a configured path
+    pub fn expand_path(path: &str) -> Result<PathBuf> {
+        shellexpand::full(path)
+            .map(|p| PathBuf::from(p.into_owned()))
+            .map_err(|e| anyhow::anyhow!("Failed to expand path '{path}': {e}"))
+    }
+
+    fn validate(&self, server_name: &str) -> Result<()> {
+        if self.client_key.is_some() && self.client_cert.is_none() {
+            return Err(anyhow::anyhow!(
+                "Server '{server_name}' has tls.client_key set without tls.client_cert"
+            ));
+        }
+
+        for (field, path) in [
+            ("ca_bundle", &self.ca_bundle),
+            ("client_cert", &self.client_cert),
+            ("client_key", &self.client_key),
+        ] {
+            if let Some(path) = path {
+                let expanded = Self::expand_path(path)?;
+                if !expanded.is_file() {
+                    return Err(anyhow::anyhow!(
+                        "Server '{server_name}' has tls.{field} '{}' which is not a file",
+                        expanded.display()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum AirflowAuth {
     Basic(BasicAuth),
+    Jwt(super::client::jwt::JwtAuth),
+    Oidc(super::client::oidc::OidcAuth),
     Token(TokenCmd),
     Conveyor,
     Mwaa(super::managed_services::mwaa::MwaaAuth),
@@ -175,10 +286,16 @@ impl std::fmt::Debug for BasicAuth {
     }
 }
 
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, Default)]
 pub struct TokenCmd {
     pub cmd: Option<String>,
     pub token: Option<String>,
+    /// How long (in seconds) a token returned by `cmd` is reused before running it again.
+    /// When unset the JWT `exp` claim of the token is used, or a default TTL for opaque tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
+    #[serde(skip)]
+    pub(crate) cache: std::sync::Arc<tokio::sync::Mutex<Option<super::client::jwt::CachedToken>>>,
 }
 
 impl std::fmt::Debug for TokenCmd {
@@ -186,7 +303,8 @@ impl std::fmt::Debug for TokenCmd {
         f.debug_struct("TokenCmd")
             .field("cmd", &self.cmd)
             .field("token", &self.token.as_ref().map(|_| "***redacted***"))
-            .finish()
+            .field("ttl_secs", &self.ttl_secs)
+            .finish_non_exhaustive()
     }
 }
 
@@ -210,6 +328,10 @@ impl FlowrsConfig {
             managed_services: None,
             show_init_screen: true,
             timezone_offset: "+00:00".to_string(),
+            restore_session: false,
+            composer: None,
+            discovery: None,
+            watch: None,
             path: Some(CONFIG_FILE.as_path().to_path_buf()),
         }
     }
@@ -267,8 +389,36 @@ impl FlowrsConfig {
                         server.name
                     ));
                 }
+                if matches!(server.auth, AirflowAuth::Jwt(_)) && server.version != AirflowVersion::V3 {
+                    return Err(anyhow::anyhow!(
+                        "Server '{}' uses Jwt auth, which requires version = \"V3\"",
+                        server.name
+                    ));
+                }
+                if let Some(tls) = &server.tls {
+                    tls.validate(&server.name)?;
+                }
+                if let AirflowAuth::Oidc(auth) = &server.auth {
+                    if auth.client_id.trim().is_empty() {
+                        return Err(anyhow::anyhow!(
+                            "Server '{}' uses Oidc auth but has an empty client_id",
+                            server.name
+                        ));
+                    }
+                    url::Url::parse(&auth.issuer).with_context(|| {
+                        format!("Server '{}' has an invalid Oidc issuer: '{}'", server.name, auth.issuer)
+                    })?;
+                }
             }
         }
+
+        if let Some(discovery) = &self.discovery {
+            discovery.validate()?;
+        }
+
+        if let Some(watch) = &self.watch {
+            watch.validate()?;
+        }
         
         Ok(())
     }
@@ -348,34 +498,68 @@ impl FlowrsConfig {
     /// Expands the config by resolving managed services and adding their servers.
     /// This is an async convenience function that should be called after `from_file`/`from_str`
     /// when you need to resolve managed service environments.
+    /// Servers discovered within the cache TTL are reused instead of asking the services again.
     /// Returns a tuple of (config, errors) where errors contains any non-fatal errors encountered.
-    pub async fn expand_managed_services(mut self) -> Result<(Self, Vec<String>)> {
+    pub async fn expand_managed_services(self) -> Result<(Self, Vec<String>)> {
+        self.discover_managed_services(true).await
+    }
+
+    /// Like `expand_managed_services`, but always asks the services and refreshes the cache.
+    /// Previously discovered servers are replaced.
+    pub async fn rediscover_managed_services(mut self) -> Result<(Self, Vec<String>)> {
+        if let Some(servers) = &mut self.servers {
+            servers.retain(|server| server.managed.is_none());
+        }
+        self.discover_managed_services(false).await
+    }
+
+    async fn discover_managed_services(mut self, use_cache: bool) -> Result<(Self, Vec<String>)> {
         let mut all_errors = Vec::new();
 
         if self.managed_services.is_none() {
             return Ok((self, all_errors));
         }
 
+        let cache = DiscoveryCache::new(self.discovery.as_ref());
         let services = self.managed_services.clone().unwrap();
         for service in services {
-            match service {
-                ManagedService::Conveyor => {
-                    let conveyor_servers = get_conveyor_environment_servers()?;
-                    self.extend_servers(conveyor_servers);
+            let fingerprint = self.discovery_fingerprint(&service)?;
+            if use_cache {
+                if let Some(servers) = cache.load(&service, &fingerprint) {
+                    self.extend_servers(servers);
+                    continue;
                 }
-                ManagedService::Mwaa => {
-                    let mwaa_servers = get_mwaa_environment_servers().await?;
-                    self.extend_servers(mwaa_servers);
+            }
+
+            let discovery = self.discovery.clone().unwrap_or_default();
+            let (servers, errors) = match service {
+                ManagedService::Conveyor => {
+                    let mut conveyor_servers = get_conveyor_environment_servers()?;
+                    if let Some(filter) = &discovery.conveyor {
+                        conveyor_servers.retain(|server| filter.matches_name(&server.name));
+                    }
+                    (conveyor_servers, Vec::new())
                 }
+                ManagedService::Mwaa => (
+                    get_mwaa_environment_servers(discovery.mwaa.as_ref()).await?,
+                    Vec::new(),
+                ),
                 ManagedService::Astronomer => {
-                    let (astronomer_servers, errors) = get_astronomer_environment_servers().await;
-                    all_errors.extend(errors);
-                    self.extend_servers(astronomer_servers);
+                    get_astronomer_environment_servers(discovery.astronomer.as_ref()).await
                 }
                 ManagedService::Gcc => {
-                    log::warn!("ManagedService::Gcc (Google Cloud Composer) expansion not implemented; skipping");
+                    get_composer_environment_servers(self.composer.as_ref()).await
+                }
+            };
+
+            // Partial results are not cached, so the next start retries the failed parts
+            if errors.is_empty() {
+                if let Err(e) = cache.store(&service, &fingerprint, &servers) {
+                    log::warn!("Failed to cache discovered {service} environments: {e}");
                 }
             }
+            all_errors.extend(errors);
+            self.extend_servers(servers);
         }
         let total = self.servers.as_ref().map_or(0, std::vec::Vec::len);
         info!(
@@ -385,6 +569,18 @@ impl FlowrsConfig {
         Ok((self, all_errors))
     }
 
+    /// The discovery settings of a managed service, changing them invalidates its cache
+    fn discovery_fingerprint(&self, service: &ManagedService) -> Result<String> {
+        let discovery = self.discovery.clone().unwrap_or_default();
+        let fingerprint = match service {
+            ManagedService::Conveyor => serde_json::to_string(&discovery.conveyor)?,
+            ManagedService::Mwaa => serde_json::to_string(&discovery.mwaa)?,
+            ManagedService::Astronomer => serde_json::to_string(&discovery.astronomer)?,
+            ManagedService::Gcc => serde_json::to_string(&self.composer)?,
+        };
+        Ok(fingerprint)
+    }
+
     pub fn to_str(&self) -> Result<String> {
         toml::to_string(self).map_err(std::convert::Into::into)
     }
@@ -489,10 +685,21 @@ password = "airflow"
                 managed: None,
                 version: AirflowVersion::V2,
                 proxy: None,
+                tls: None,
+                timeout_secs: None,
+                connect_timeout_secs: None,
+                max_retries: None,
+                backoff_ms: None,
+                max_backoff_secs: None,
+                protection: Protection::None,
             }]),
             managed_services: Some(vec![ManagedService::Conveyor]),
             show_init_screen: true,
             timezone_offset: "+00:00".to_string(),
+            restore_session: false,
+            composer: None,
+            discovery: None,
+            watch: None,
             path: None,
         };
 
@@ -500,6 +707,82 @@ password = "airflow"
         assert_eq!(serialized_config.trim(), TEST_CONFIG_CONVEYOR.trim());
     }
 
+    #[test]
+    fn test_jwt_auth_requires_airflow_3() {
+        let config = r#"
+[[servers]]
+name = "airflow3"
+endpoint = "http://localhost:8080"
+version = "V3"
+
+[servers.auth.Jwt]
+username = "${AIRFLOW_USERNAME}"
+password = "${AIRFLOW_PASSWORD}"
+"#;
+        let result = FlowrsConfig::from_str(config).unwrap();
+        let server = &result.servers.unwrap()[0];
+        assert!(matches!(&server.auth, AirflowAuth::Jwt(auth) if auth.username == "${AIRFLOW_USERNAME}"));
+
+        let result = FlowrsConfig::from_str(&config.replace("V3", "V2"));
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("requires version"));
+    }
+
+    #[test]
+    fn test_tls_config_validation() {
+        let ca_bundle = std::env::temp_dir().join(format!("flowrs-ca-{}.pem", std::process::id()));
+        std::fs::write(&ca_bundle, "").unwrap();
+        let config = format!(
+            r#"
+[[servers]]
+name = "internal"
+endpoint = "https://airflow.internal"
+
+[servers.auth.Basic]
+username = "airflow"
+password = "airflow"
+
+[servers.tls]
+ca_bundle = "{}"
+danger_accept_invalid_certs = true
+"#,
+            ca_bundle.display()
+        );
+        let result = FlowrsConfig::from_str(&config).unwrap();
+        let tls = result.servers.unwrap()[0].tls.clone().unwrap();
+        assert!(tls.danger_accept_invalid_certs);
+        assert!(tls.client_cert.is_none());
+
+        let key_without_cert = format!("{config}client_key = \"{}\"\n", ca_bundle.display());
+        let result = FlowrsConfig::from_str(&key_without_cert);
+        assert!(result.unwrap_err().to_string().contains("without tls.client_cert"));
+
+        std::fs::remove_file(&ca_bundle).unwrap();
+        let result = FlowrsConfig::from_str(&config);
+        assert!(result.unwrap_err().to_string().contains("tls.ca_bundle"));
+    }
+
+    #[test]
+    fn test_oidc_auth_config() {
+        let config = r#"
+[[servers]]
+name = "sso"
+endpoint = "https://airflow.example.com"
+version = "V2"
+
+[servers.auth.Oidc]
+issuer = "https://keycloak.example.com/realms/data"
+client_id = "flowrs"
+scopes = ["openid", "offline_access", "airflow"]
+"#;
+        let result = FlowrsConfig::from_str(config).unwrap();
+        let server = &result.servers.unwrap()[0];
+        assert!(matches!(&server.auth, AirflowAuth::Oidc(auth) if auth.scopes.len() == 3));
+
+        let result = FlowrsConfig::from_str(&config.replace("https://keycloak.example.com", "keycloak"));
+        assert!(result.unwrap_err().to_string().contains("invalid Oidc issuer"));
+    }
+
     #[test]
     fn non_existing_path() {
         let path = PathBuf::from("non-existing.toml");
@@ -568,10 +851,21 @@ password = "airflow"
                 managed: None,
                 version: AirflowVersion::V2,
                 proxy: Some("http://proxy.example.com:8080".to_string()),
+                tls: None,
+                timeout_secs: None,
+                connect_timeout_secs: None,
+                max_retries: None,
+                backoff_ms: None,
+                max_backoff_secs: None,
+                protection: Protection::None,
             }]),
             managed_services: None,
             show_init_screen: true,
             timezone_offset: "+00:00".to_string(),
+            restore_session: false,
+            composer: None,
+            discovery: None,
+            watch: None,
             path: None,
         };
 
@@ -579,6 +873,36 @@ password = "airflow"
         assert!(serialized.contains("proxy = \"http://proxy.example.com:8080\""));
     }
 
+    #[test]
+    fn test_server_protection() {
+        let config_str = r#"[[servers]]
+name = "prod"
+endpoint = "http://localhost:8080"
+protection = "read_only"
+
+[servers.auth.Basic]
+username = "airflow"
+password = "airflow"
+
+[[servers]]
+name = "dev"
+endpoint = "http://localhost:8081"
+
+[servers.auth.Basic]
+username = "airflow"
+password = "airflow"
+"#;
+        let result = FlowrsConfig::from_str(config_str).unwrap();
+        let servers = result.servers.clone().unwrap();
+        assert_eq!(servers[0].protection, Protection::ReadOnly);
+        assert_eq!(servers[1].protection, Protection::None);
+
+        // The default is left out when writing the config back
+        let serialized = result.to_str().unwrap();
+        assert_eq!(serialized.matches("protection").count(), 1);
+        assert!(serialized.contains("protection = \"read_only\""));
+    }
+
     #[test]
     fn test_config_without_proxy() {
         let result = FlowrsConfig::from_str(TEST_CONFIG).unwrap();
@@ -1,4 +1,5 @@
 pub mod astronomer;
 pub mod composer;
 pub mod conveyor;
+pub mod discovery;
 pub mod mwaa;
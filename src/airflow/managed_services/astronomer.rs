@@ -1,4 +1,4 @@
-use crate::airflow::config::{AirflowAuth, AirflowConfig, AirflowVersion, ManagedService};
+use crate::airflow::config::{AirflowAuth, AirflowConfig, AirflowVersion, ManagedService, Protection};
 use anyhow::{Context, Result};
 use log::info;
 use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
@@ -8,11 +8,33 @@ use std::fmt;
 use std::sync::LazyLock;
 use std::time::Duration;
 
+use super::discovery::DiscoveryFilter;
+
 static FLOWRS_USER_AGENT: LazyLock<String> = LazyLock::new(|| {
     let version = env!("CARGO_PKG_VERSION");
     format!("flowrs/{version}")
 });
 
+/// Which Astronomer deployments to list, configured in the `[discovery.astronomer]` section
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct AstronomerDiscoveryConfig {
+    /// Only list deployments in these workspaces, by id or name (all if empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<String>,
+    #[serde(flatten)]
+    pub filter: DiscoveryFilter,
+}
+
+impl AstronomerDiscoveryConfig {
+    fn matches_workspace(&self, deployment: &Deployment) -> bool {
+        self.workspaces.is_empty()
+            || self
+                .workspaces
+                .iter()
+                .any(|w| *w == deployment.workspace_id || *w == deployment.workspace_name)
+    }
+}
+
 /// Astronomer client for managing authentication and deployment discovery
 #[derive(Debug, Clone)]
 pub struct AstronomerClient {
@@ -175,6 +197,10 @@ pub struct Deployment {
     pub airflow_version: String,
     pub web_server_url: String,
     pub status: String,
+    #[serde(default)]
+    pub workspace_id: String,
+    #[serde(default)]
+    pub workspace_name: String,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -202,7 +228,10 @@ impl fmt::Debug for AstronomerAuth {
 
 /// Lists all Astronomer deployments across all organizations and returns them as `AirflowConfig` instances
 /// Returns a tuple of (successful configs, error messages for failed organizations)
-pub async fn get_astronomer_environment_servers() -> (Vec<AirflowConfig>, Vec<String>) {
+pub async fn get_astronomer_environment_servers(
+    discovery: Option<&AstronomerDiscoveryConfig>,
+) -> (Vec<AirflowConfig>, Vec<String>) {
+    let discovery = discovery.cloned().unwrap_or_default();
     let mut servers = Vec::new();
     let mut errors = Vec::new();
 
@@ -242,6 +271,12 @@ pub async fn get_astronomer_environment_servers() -> (Vec<AirflowConfig>, Vec<St
         };
 
         for deployment in deployments {
+            let name = format!("{}/{}", org.name, deployment.name);
+            if !discovery.matches_workspace(&deployment) || !discovery.filter.matches_name(&name) {
+                info!("Skipping Astronomer deployment {name}: excluded by discovery filters");
+                continue;
+            }
+
             // Determine Airflow version from the version string
             let version = if deployment.airflow_version.starts_with("2.") {
                 AirflowVersion::V2
@@ -264,7 +299,7 @@ pub async fn get_astronomer_environment_servers() -> (Vec<AirflowConfig>, Vec<St
             );
 
             servers.push(AirflowConfig {
-                name: format!("{}/{}", org.name, deployment.name),
+                name,
                 endpoint,
                 auth: AirflowAuth::Astronomer(AstronomerAuth {
                     api_token: client.api_token.clone(),
@@ -272,6 +307,13 @@ pub async fn get_astronomer_environment_servers() -> (Vec<AirflowConfig>, Vec<St
                 managed: Some(ManagedService::Astronomer),
                 version,
                 proxy: None,
+                tls: None,
+                timeout_secs: None,
+                connect_timeout_secs: None,
+                max_retries: None,
+                backoff_ms: None,
+                max_backoff_secs: None,
+                protection: Protection::None,
             });
         }
     }
@@ -290,7 +332,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_astronomer_environments() {
-        let (environments, errors) = get_astronomer_environment_servers().await;
+        let (environments, errors) = get_astronomer_environment_servers(None).await;
         // This test will only work if you have ASTRO_API_TOKEN configured
         // and Astronomer deployments available
         println!("Found {} Astronomer deployments", environments.len());
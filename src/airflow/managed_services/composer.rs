@@ -1,12 +1,15 @@
-use crate::airflow::config::{AirflowAuth, AirflowConfig, AirflowVersion, ManagedService};
+use crate::airflow::config::{AirflowAuth, AirflowConfig, AirflowVersion, ManagedService, Protection};
 use anyhow::{Context, Result};
 use gcp_auth::TokenProvider;
 use log::info;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 use tokio::sync::OnceCell;
 
+use super::discovery::DiscoveryFilter;
+
 /// Creates a detailed error message for GCP session expiration issues
 ///
 /// This helper provides consistent, actionable guidance when GCP authentication fails,
@@ -251,7 +254,7 @@ impl<'de> Deserialize<'de> for ComposerAuth {
     }
 }
 
-/// Composer environment metadata from manual configuration
+/// Composer environment metadata from manual configuration or discovery
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComposerEnvironment {
     pub name: String,
@@ -292,9 +295,277 @@ pub async fn create_composer_config(
         managed: Some(ManagedService::Gcc),
         version: airflow_version,
         proxy: None,
+        tls: None,
+        timeout_secs: None,
+        connect_timeout_secs: None,
+        max_retries: None,
+        backoff_ms: None,
+        max_backoff_secs: None,
+        protection: Protection::None,
     })
 }
 
+/// Projects and locations to search when discovering Composer environments.
+/// Configured in the `[composer]` section of the flowrs config.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ComposerDiscoveryConfig {
+    pub projects: Vec<String>,
+    pub locations: Vec<String>,
+    /// Service account keyfile used for discovery and for the discovered environments (ADC if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyfile_path: Option<String>,
+    /// Name patterns and labels the environments must match
+    #[serde(flatten)]
+    pub filter: DiscoveryFilter,
+}
+
+const COMPOSER_API_URL: &str = "https://composer.googleapis.com/v1";
+
+/// Minimal client for the Cloud Composer REST API, used for environment discovery
+pub struct ComposerApi {
+    client: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+/// An environment as returned by `projects.locations.environments.list`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposerApiEnvironment {
+    /// Full resource name: `projects/{project}/locations/{location}/environments/{environment}`
+    pub name: String,
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub config: ComposerApiEnvironmentConfig,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposerApiEnvironmentConfig {
+    pub airflow_uri: Option<String>,
+    #[serde(default)]
+    pub software_config: ComposerSoftwareConfig,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposerSoftwareConfig {
+    pub image_version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListEnvironmentsResponse {
+    #[serde(default)]
+    environments: Vec<ComposerApiEnvironment>,
+    next_page_token: Option<String>,
+}
+
+impl ComposerApiEnvironment {
+    /// The environment id, i.e. the last segment of the resource name
+    pub fn short_name(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or(&self.name)
+    }
+}
+
+impl ComposerApi {
+    pub fn new(token: String) -> Result<Self> {
+        Self::with_base_url(COMPOSER_API_URL, token)
+    }
+
+    /// Creates a client against a different API root, e.g. a local stand-in for tests
+    pub fn with_base_url(base_url: &str, token: String) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .use_rustls_tls()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .context("Failed to build HTTP client")?;
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    /// Lists all environments in a project and location, following pagination
+    pub async fn list_environments(
+        &self,
+        project: &str,
+        location: &str,
+    ) -> Result<Vec<ComposerApiEnvironment>> {
+        let url = format!(
+            "{}/projects/{project}/locations/{location}/environments",
+            self.base_url
+        );
+        let mut environments = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut request = self.client.get(&url).bearer_auth(&self.token);
+            if let Some(token) = &page_token {
+                request = request.query(&[("pageToken", token)]);
+            }
+
+            let response = request
+                .send()
+                .await
+                .with_context(|| format!("Failed to list Composer environments in {project}/{location}"))?;
+            if !response.status().is_success() {
+                anyhow::bail!(
+                    "Failed to list Composer environments in {project}/{location}: HTTP {}",
+                    response.status()
+                );
+            }
+
+            let page: ListEnvironmentsResponse = response
+                .json()
+                .await
+                .context("Failed to parse Composer environments response")?;
+            environments.extend(page.environments);
+
+            match page.next_page_token.filter(|t| !t.is_empty()) {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+
+        Ok(environments)
+    }
+}
+
+/// Derive the Airflow major version from a Composer image version,
+/// e.g. `composer-2.9.7-airflow-2.9.3` or `composer-3-airflow-3.0.1-build.2`
+pub fn airflow_version_from_image(image_version: &str) -> Option<AirflowVersion> {
+    let (_, airflow) = image_version.split_once("-airflow-")?;
+    match airflow.split('.').next()? {
+        "2" => Some(AirflowVersion::V2),
+        "3" => Some(AirflowVersion::V3),
+        _ => None,
+    }
+}
+
+/// Convert discovered environments into server configs.
+/// Environments that don't match the discovery filters or are not running are skipped,
+/// environments without a web server URL or with an unknown image version are reported as errors.
+pub async fn composer_environments_to_servers(
+    project: &str,
+    environments: Vec<ComposerApiEnvironment>,
+    discovery: &ComposerDiscoveryConfig,
+) -> (Vec<AirflowConfig>, Vec<String>) {
+    let mut servers = Vec::new();
+    let mut errors = Vec::new();
+
+    for environment in environments {
+        let name = format!("{project}/{}", environment.short_name());
+        if !discovery.filter.matches_name(&name) || !discovery.filter.matches_tags(&environment.labels) {
+            info!("Skipping Composer environment {name}: excluded by discovery filters");
+            continue;
+        }
+        if environment.state != "RUNNING" {
+            info!("Skipping Composer environment {name} in state {}", environment.state);
+            continue;
+        }
+
+        let Some(airflow_uri) = environment.config.airflow_uri.clone() else {
+            errors.push(format!("Composer environment '{name}' has no Airflow web server URL"));
+            continue;
+        };
+
+        let image_version = environment
+            .config
+            .software_config
+            .image_version
+            .clone()
+            .unwrap_or_default();
+        let Some(version) = airflow_version_from_image(&image_version) else {
+            errors.push(format!(
+                "Unsupported Composer image version '{image_version}' for environment '{name}'"
+            ));
+            continue;
+        };
+
+        let environment = ComposerEnvironment {
+            name,
+            endpoint: airflow_uri,
+            airflow_version: version,
+        };
+        info!(
+            "Discovered Composer environment: {} ({})",
+            environment.name, environment.endpoint
+        );
+        match create_composer_config(
+            environment.name,
+            environment.endpoint,
+            environment.airflow_version,
+            discovery.keyfile_path.clone(),
+        )
+        .await
+        {
+            Ok(server) => servers.push(server),
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+
+    (servers, errors)
+}
+
+/// Discovers Composer environments across the configured projects and locations.
+/// Returns the discovered servers and any non-fatal errors.
+pub async fn get_composer_environment_servers(
+    discovery: Option<&ComposerDiscoveryConfig>,
+) -> (Vec<AirflowConfig>, Vec<String>) {
+    let Some(discovery) = discovery.filter(|d| !d.projects.is_empty() && !d.locations.is_empty()) else {
+        return (
+            Vec::new(),
+            vec!["Composer discovery requires a [composer] section with projects and locations".to_string()],
+        );
+    };
+
+    let client = match &discovery.keyfile_path {
+        Some(keyfile) => ComposerClient::from_keyfile(keyfile).await,
+        None => ComposerClient::new().await,
+    };
+    let token = match client {
+        Ok(client) => client.get_token().await,
+        Err(e) => Err(e),
+    };
+    let api = match token.and_then(ComposerApi::new) {
+        Ok(api) => api,
+        Err(e) => return (Vec::new(), vec![format!("Failed to authenticate to Composer: {e}")]),
+    };
+
+    discover_composer_servers(&api, discovery).await
+}
+
+/// Lists environments for every project/location pair and converts them to servers
+pub async fn discover_composer_servers(
+    api: &ComposerApi,
+    discovery: &ComposerDiscoveryConfig,
+) -> (Vec<AirflowConfig>, Vec<String>) {
+    let mut servers = Vec::new();
+    let mut errors = Vec::new();
+
+    for project in &discovery.projects {
+        for location in &discovery.locations {
+            match api.list_environments(project, location).await {
+                Ok(environments) => {
+                    let (found, conversion_errors) =
+                        composer_environments_to_servers(project, environments, discovery).await;
+                    servers.extend(found);
+                    errors.extend(conversion_errors);
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+    }
+
+    info!("Found {} Composer environment(s)", servers.len());
+    (servers, errors)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,6 +632,103 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_airflow_version_from_image() {
+        assert_eq!(
+            airflow_version_from_image("composer-2.9.7-airflow-2.9.3"),
+            Some(AirflowVersion::V2)
+        );
+        assert_eq!(
+            airflow_version_from_image("composer-3-airflow-3.0.1-build.2"),
+            Some(AirflowVersion::V3)
+        );
+        assert_eq!(airflow_version_from_image("composer-1.20.0-airflow-1.10.15"), None);
+        assert_eq!(airflow_version_from_image("latest"), None);
+    }
+
+    #[tokio::test]
+    async fn test_discover_composer_servers() {
+        let mut server = mockito::Server::new_async().await;
+        let first_page = server
+            .mock("GET", "/projects/data-prod/locations/europe-west1/environments")
+            .match_header("authorization", "Bearer test-token")
+            .match_query(mockito::Matcher::Missing)
+            .with_body(
+                serde_json::json!({
+                    "environments": [{
+                        "name": "projects/data-prod/locations/europe-west1/environments/etl",
+                        "state": "RUNNING",
+                        "config": {
+                            "airflowUri": "https://abc-dot-europe-west1.composer.googleusercontent.com",
+                            "softwareConfig": {"imageVersion": "composer-2.9.7-airflow-2.9.3"}
+                        }
+                    }],
+                    "nextPageToken": "page-2"
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("GET", "/projects/data-prod/locations/europe-west1/environments")
+            .match_query(mockito::Matcher::UrlEncoded("pageToken".into(), "page-2".into()))
+            .with_body(
+                serde_json::json!({
+                    "environments": [
+                        {
+                            "name": "projects/data-prod/locations/europe-west1/environments/ml",
+                            "state": "RUNNING",
+                            "config": {
+                                "airflowUri": "https://def-dot-europe-west1.composer.googleusercontent.com",
+                                "softwareConfig": {"imageVersion": "composer-3-airflow-3.0.1-build.2"}
+                            }
+                        },
+                        {
+                            "name": "projects/data-prod/locations/europe-west1/environments/creating",
+                            "state": "CREATING",
+                            "config": {}
+                        }
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let forbidden = server
+            .mock("GET", "/projects/data-prod/locations/us-central1/environments")
+            .with_status(403)
+            .create_async()
+            .await;
+
+        let api = ComposerApi::with_base_url(&server.url(), "test-token".to_string()).unwrap();
+        let discovery = ComposerDiscoveryConfig {
+            projects: vec!["data-prod".to_string()],
+            locations: vec!["europe-west1".to_string(), "us-central1".to_string()],
+            keyfile_path: None,
+            filter: DiscoveryFilter::default(),
+        };
+
+        let (servers, errors) = discover_composer_servers(&api, &discovery).await;
+
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+        forbidden.assert_async().await;
+
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].name, "data-prod/etl");
+        assert_eq!(
+            servers[0].endpoint,
+            "https://abc-dot-europe-west1.composer.googleusercontent.com/"
+        );
+        assert_eq!(servers[0].version, AirflowVersion::V2);
+        assert_eq!(servers[0].managed, Some(ManagedService::Gcc));
+        assert_eq!(servers[1].name, "data-prod/ml");
+        assert_eq!(servers[1].version, AirflowVersion::V3);
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("us-central1"));
+    }
+
     #[tokio::test]
     async fn test_endpoint_normalization() {
         init_crypto();
@@ -1,4 +1,4 @@
-use crate::airflow::config::{AirflowAuth, AirflowConfig, ManagedService};
+use crate::airflow::config::{AirflowAuth, AirflowConfig, ManagedService, Protection};
 use anyhow::{Context, Result};
 use dirs::home_dir;
 use expectrl::spawn;
@@ -88,6 +88,13 @@ pub fn get_conveyor_environment_servers() -> Result<Vec<AirflowConfig>> {
                 managed: Some(ManagedService::Conveyor),
                 version,
                 proxy: None,
+                tls: None,
+                timeout_secs: None,
+                connect_timeout_secs: None,
+                max_retries: None,
+                backoff_ms: None,
+                max_backoff_secs: None,
+                protection: Protection::None,
             }
         })
         .collect();
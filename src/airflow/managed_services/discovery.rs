@@ -0,0 +1,356 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::astronomer::AstronomerDiscoveryConfig;
+use super::mwaa::MwaaDiscoveryConfig;
+use crate::airflow::config::{AirflowConfig, ManagedService};
+
+const DEFAULT_CACHE_TTL: Duration = Duration::from_hours(1);
+
+/// Settings for managed service discovery, configured in the `[discovery]` section.
+///
+/// ```toml
+/// [discovery]
+/// cache_ttl_secs = 3600
+///
+/// [discovery.mwaa]
+/// regions = ["eu-west-1"]
+/// include = ["prod-*"]
+/// tags = { team = "data" }
+///
+/// [discovery.astronomer]
+/// workspaces = ["Data Platform"]
+/// exclude = ["*/sandbox-*"]
+/// ```
+///
+/// Google Cloud Composer is configured in its own `[composer]` section.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct DiscoveryConfig {
+    /// How long discovered environments are reused before asking the services again (0 disables the cache)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mwaa: Option<MwaaDiscoveryConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub astronomer: Option<AstronomerDiscoveryConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conveyor: Option<DiscoveryFilter>,
+}
+
+impl DiscoveryConfig {
+    pub fn cache_ttl(&self) -> Duration {
+        self.cache_ttl_secs
+            .map_or(DEFAULT_CACHE_TTL, Duration::from_secs)
+    }
+
+    /// Tag filters are only supported where the service exposes tags (MWAA tags, Composer labels)
+    pub fn validate(&self) -> Result<()> {
+        let untagged = [
+            ("astronomer", self.astronomer.as_ref().map(|a| &a.filter)),
+            ("conveyor", self.conveyor.as_ref()),
+        ];
+        for (service, filter) in untagged {
+            if filter.is_some_and(|f| !f.tags.is_empty()) {
+                anyhow::bail!("[discovery.{service}] does not support tag filters");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Filters applied to discovered environments.
+/// Name patterns are matched against the environment name as shown in flowrs and support `*` wildcards.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct DiscoveryFilter {
+    /// Only keep environments matching one of these patterns (all if empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Drop environments matching one of these patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Only keep environments carrying all of these tags (MWAA tags, Composer labels)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+}
+
+impl DiscoveryFilter {
+    pub fn matches_name(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| wildcard_match(p, name)))
+            && !self.exclude.iter().any(|p| wildcard_match(p, name))
+    }
+
+    pub fn matches_tags(&self, tags: &HashMap<String, String>) -> bool {
+        self.tags
+            .iter()
+            .all(|(key, value)| tags.get(key) == Some(value))
+    }
+}
+
+/// Match `text` against a pattern where `*` matches any sequence of characters
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // split always yields at least one item
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No wildcard in the pattern
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedDiscovery {
+    /// Serialized discovery settings the servers were found with, a change invalidates the cache
+    fingerprint: String,
+    discovered_at: DateTime<Utc>,
+    servers: Vec<AirflowConfig>,
+}
+
+/// Discovered servers per managed service, stored in the state directory.
+/// Entries can hold session cookies and API tokens, so files are only readable by the user.
+pub struct DiscoveryCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl DiscoveryCache {
+    pub fn new(config: Option<&DiscoveryConfig>) -> Self {
+        Self::with_dir(
+            crate::get_state_dir().join("discovery"),
+            config.map_or(DEFAULT_CACHE_TTL, DiscoveryConfig::cache_ttl),
+        )
+    }
+
+    pub fn with_dir(dir: PathBuf, ttl: Duration) -> Self {
+        Self { dir, ttl }
+    }
+
+    fn path(&self, service: &ManagedService) -> PathBuf {
+        self.dir
+            .join(format!("{}.json", format!("{service:?}").to_lowercase()))
+    }
+
+    /// Servers discovered within the TTL with the same settings, if any
+    pub fn load(&self, service: &ManagedService, fingerprint: &str) -> Option<Vec<AirflowConfig>> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+        let content = std::fs::read_to_string(self.path(service)).ok()?;
+        let cached: CachedDiscovery = match serde_json::from_str(&content) {
+            Ok(cached) => cached,
+            Err(e) => {
+                warn!("Ignoring unreadable discovery cache for {service}: {e}");
+                return None;
+            }
+        };
+        let age = (Utc::now() - cached.discovered_at)
+            .to_std()
+            .unwrap_or_default();
+        if cached.fingerprint != fingerprint || age > self.ttl {
+            debug!("Discovery cache for {service} is stale");
+            return None;
+        }
+        info!(
+            "Using {} cached {service} environment(s) discovered {}s ago",
+            cached.servers.len(),
+            age.as_secs()
+        );
+        Some(cached.servers)
+    }
+
+    pub fn store(
+        &self,
+        service: &ManagedService,
+        fingerprint: &str,
+        servers: &[AirflowConfig],
+    ) -> Result<()> {
+        if self.ttl.is_zero() {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        let cached = CachedDiscovery {
+            fingerprint: fingerprint.to_string(),
+            discovered_at: Utc::now(),
+            servers: servers.to_vec(),
+        };
+
+        let mut options = OpenOptions::new();
+        options.write(true).truncate(true).create(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let path = self.path(service);
+        let mut file = options.open(&path)?;
+        file.write_all(serde_json::to_string(&cached)?.as_bytes())?;
+
+        // The mode only applies on creation, tighten permissions of pre-existing files too
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airflow::config::{AirflowAuth, AirflowVersion, BasicAuth, Protection};
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("prod", "prod"));
+        assert!(!wildcard_match("prod", "prod-etl"));
+        assert!(wildcard_match("prod-*", "prod-etl"));
+        assert!(wildcard_match("*-etl", "prod-etl"));
+        assert!(wildcard_match("*etl*", "org/prod-etl-2"));
+        assert!(wildcard_match("a*b*c", "a-b-b-c"));
+        assert!(!wildcard_match("a*b*c", "a-c-b"));
+        assert!(!wildcard_match("ab*ba", "aba"));
+        assert!(wildcard_match("*", ""));
+    }
+
+    #[test]
+    fn test_discovery_filter() {
+        let filter = DiscoveryFilter {
+            include: vec!["prod-*".to_string(), "shared".to_string()],
+            exclude: vec!["*-sandbox".to_string()],
+            tags: BTreeMap::from([("team".to_string(), "data".to_string())]),
+        };
+        assert!(filter.matches_name("prod-etl"));
+        assert!(filter.matches_name("shared"));
+        assert!(!filter.matches_name("prod-sandbox"));
+        assert!(!filter.matches_name("dev-etl"));
+        assert!(DiscoveryFilter::default().matches_name("anything"));
+
+        let tags = HashMap::from([
+            ("team".to_string(), "data".to_string()),
+            ("env".to_string(), "prod".to_string()),
+        ]);
+        assert!(filter.matches_tags(&tags));
+        assert!(!filter.matches_tags(&HashMap::new()));
+    }
+
+    #[test]
+    fn test_discovery_config_from_toml() {
+        let config: DiscoveryConfig = toml::from_str(
+            r#"
+            cache_ttl_secs = 60
+
+            [mwaa]
+            regions = ["eu-west-1"]
+            include = ["prod-*"]
+            tags = { team = "data" }
+
+            [conveyor]
+            exclude = ["*-dev"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.cache_ttl(), Duration::from_mins(1));
+        assert!(config.validate().is_ok());
+        let mwaa = config.mwaa.unwrap();
+        assert_eq!(mwaa.regions, vec!["eu-west-1"]);
+        assert_eq!(mwaa.filter.include, vec!["prod-*"]);
+        assert_eq!(mwaa.filter.tags["team"], "data");
+        assert_eq!(config.conveyor.unwrap().exclude, vec!["*-dev"]);
+        assert!(config.astronomer.is_none());
+    }
+
+    #[test]
+    fn test_tags_are_rejected_for_untagged_services() {
+        let config: DiscoveryConfig = toml::from_str(
+            r#"
+            [astronomer]
+            tags = { team = "data" }
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+
+        let config: DiscoveryConfig = toml::from_str(
+            r#"
+            [conveyor]
+            tags = { team = "data" }
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_discovery_cache() {
+        let dir =
+            std::env::temp_dir().join(format!("flowrs-discovery-test-{}", std::process::id()));
+        let cache = DiscoveryCache::with_dir(dir.clone(), Duration::from_mins(1));
+        let servers = vec![AirflowConfig {
+            name: "prod".to_string(),
+            endpoint: "http://localhost:8080/".to_string(),
+            auth: AirflowAuth::Basic(BasicAuth {
+                username: "airflow".to_string(),
+                password: "airflow".to_string(),
+            }),
+            managed: Some(ManagedService::Conveyor),
+            version: AirflowVersion::V2,
+            proxy: None,
+            tls: None,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+            max_retries: None,
+            backoff_ms: None,
+            max_backoff_secs: None,
+            protection: Protection::None,
+        }];
+
+        assert!(cache.load(&ManagedService::Conveyor, "a").is_none());
+        cache
+            .store(&ManagedService::Conveyor, "a", &servers)
+            .unwrap();
+
+        let cached = cache.load(&ManagedService::Conveyor, "a").unwrap();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].name, "prod");
+        // Other settings or services don't reuse the entry
+        assert!(cache.load(&ManagedService::Conveyor, "b").is_none());
+        assert!(cache.load(&ManagedService::Mwaa, "a").is_none());
+        // A zero TTL disables the cache
+        let disabled = DiscoveryCache::with_dir(dir.clone(), Duration::ZERO);
+        assert!(disabled.load(&ManagedService::Conveyor, "a").is_none());
+
+        // Pre-existing cache files are tightened as well
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let path = cache.path(&ManagedService::Conveyor);
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+            cache
+                .store(&ManagedService::Conveyor, "a", &servers)
+                .unwrap();
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
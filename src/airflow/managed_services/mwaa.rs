@@ -1,9 +1,24 @@
-use crate::airflow::config::{AirflowAuth, AirflowConfig, AirflowVersion, ManagedService};
+use crate::airflow::config::{AirflowAuth, AirflowConfig, AirflowVersion, ManagedService, Protection};
 use anyhow::{Context, Result};
-use aws_config::BehaviorVersion;
+use aws_config::{BehaviorVersion, Region};
 use aws_sdk_mwaa as mwaa;
 use log::info;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use super::discovery::DiscoveryFilter;
+
+/// Where to look for MWAA environments, configured in the `[discovery.mwaa]` section
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct MwaaDiscoveryConfig {
+    /// AWS regions to search (the default region of the AWS profile if empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+    #[serde(flatten)]
+    pub filter: DiscoveryFilter,
+}
 
 /// MWAA client for managing authentication and environment discovery
 #[derive(Debug, Clone)]
@@ -12,9 +27,14 @@ pub struct MwaaClient {
 }
 
 impl MwaaClient {
-    /// Creates a new MWAA client using default AWS configuration
-    pub async fn new() -> Result<Self> {
-        let config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    /// Creates a new MWAA client using default AWS configuration,
+    /// optionally overriding the region of the AWS profile
+    pub async fn for_region(region: Option<&str>) -> Result<Self> {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(region) = region {
+            loader = loader.region(Region::new(region.to_string()));
+        }
+        let config = loader.load().await;
         let client = mwaa::Client::new(&config);
         Ok(Self { client })
     }
@@ -31,7 +51,7 @@ impl MwaaClient {
         Ok(response.environments().to_vec())
     }
 
-    /// Gets detailed information about a specific MWAA environment
+    /// Gets detailed information about a specific MWAA environment, including its tags
     pub async fn get_environment(&self, name: &str) -> Result<MwaaEnvironment> {
         let response = self
             .client
@@ -59,6 +79,7 @@ impl MwaaClient {
             name: name.to_string(),
             airflow_version,
             webserver_url,
+            tags: env.tags().cloned().unwrap_or_default(),
         })
     }
 
@@ -83,47 +104,66 @@ impl MwaaClient {
         })
     }
 
-    /// Exchanges a web login token for a session cookie
+    /// Exchanges a web login token for a session cookie (Airflow 2)
     pub async fn get_session_cookie(&self, web_token: &MwaaWebToken) -> Result<String> {
         let login_url = format!("https://{}/aws_mwaa/login", web_token.hostname);
+        login_with_web_token(&login_url, &web_token.token, "session").await
+    }
 
-        let form_data = LoginForm {
-            token: &web_token.token,
-        };
-
-        let client = reqwest::Client::builder()
-            .redirect(reqwest::redirect::Policy::none()) // Don't follow redirects
-            .build()
-            .context("Failed to build HTTP client")?;
-
-        let response = client
-            .post(&login_url)
-            .form(&form_data)
-            .send()
-            .await
-            .context("Failed to send login request")?;
+    /// Exchanges a web login token for a JWT access token (Airflow 3).
+    /// Airflow 3 environments log in through the `pluginsv2` endpoint, which returns the
+    /// token in the `_token` cookie. It is used as bearer token for the REST API.
+    pub async fn get_airflow3_token(&self, web_token: &MwaaWebToken) -> Result<String> {
+        let login_url = format!("https://{}/pluginsv2/aws_mwaa/login", web_token.hostname);
+        login_with_web_token(&login_url, &web_token.token, "_token").await
+    }
 
-        // MWAA login returns a redirect with Set-Cookie header
-        if !response.status().is_redirection() && !response.status().is_success() {
-            anyhow::bail!("Failed to log in: HTTP {}", response.status());
+    /// Logs in with a fresh web login token and returns the credential for the REST API
+    pub async fn login(&self, environment_name: &str, version: &AirflowVersion) -> Result<String> {
+        let web_token = self.create_web_login_token(environment_name).await?;
+        match version {
+            AirflowVersion::V2 => self.get_session_cookie(&web_token).await,
+            AirflowVersion::V3 => self.get_airflow3_token(&web_token).await,
         }
+    }
+}
+
+/// Posts a web login token to an MWAA login endpoint and extracts `cookie_name` from the response
+async fn login_with_web_token(login_url: &str, token: &str, cookie_name: &str) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none()) // Don't follow redirects
+        .build()
+        .context("Failed to build HTTP client")?;
+
+    let response = client
+        .post(login_url)
+        .form(&LoginForm { token })
+        .send()
+        .await
+        .context("Failed to send login request")?;
+
+    // MWAA login returns a redirect with Set-Cookie header
+    if !response.status().is_redirection() && !response.status().is_success() {
+        anyhow::bail!("Failed to log in: HTTP {}", response.status());
+    }
 
-        // Extract the session cookie from Set-Cookie header
-        let cookies = response.headers().get_all("set-cookie");
+    // Extract the cookie from the Set-Cookie headers
+    let cookies = response.headers().get_all("set-cookie");
 
-        for cookie_header in cookies {
-            let cookie_str = cookie_header.to_str().context("Invalid cookie header")?;
+    for cookie_header in cookies {
+        let cookie_str = cookie_header.to_str().context("Invalid cookie header")?;
 
-            // Parse the cookie to extract session value
-            if let Some(session_part) = cookie_str.split(';').next() {
-                if let Some(("session", value)) = session_part.split_once('=') {
+        // Parse the cookie to extract its value
+        if let Some(cookie_part) = cookie_str.split(';').next() {
+            if let Some((name, value)) = cookie_part.split_once('=') {
+                if name.trim() == cookie_name {
                     return Ok(value.to_string());
                 }
             }
         }
-
-        anyhow::bail!("No session cookie found in response")
     }
+
+    anyhow::bail!("No {cookie_name} cookie found in response")
 }
 
 /// MWAA environment metadata
@@ -132,6 +172,7 @@ pub struct MwaaEnvironment {
     pub name: String,
     pub airflow_version: String,
     pub webserver_url: String,
+    pub tags: std::collections::HashMap<String, String>,
 }
 
 /// MWAA web login token and hostname
@@ -141,11 +182,31 @@ pub struct MwaaWebToken {
     pub hostname: String,
 }
 
-/// MWAA authentication data including session cookie
+/// Don't renew a session again if it was renewed this recently. Requests that were sent with
+/// the old credential fail after the renewal and would otherwise trigger another login.
+const RENEWAL_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// MWAA authentication data.
+/// `session_cookie` holds the credential obtained during discovery: the `session` cookie for
+/// Airflow 2 or the JWT from the `_token` cookie for Airflow 3. Once it expires, the session is
+/// renewed with a new web login token and kept in memory.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct MwaaAuth {
     pub session_cookie: String,
     pub environment_name: String,
+    /// Region the environment was discovered in, used to renew the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip)]
+    session: Arc<Mutex<MwaaSession>>,
+}
+
+/// Renewed credential, guarded by a mutex so concurrent requests share one renewal
+#[derive(Default)]
+struct MwaaSession {
+    renewed: Option<String>,
+    renewed_at: Option<Instant>,
+    stale: bool,
 }
 
 impl std::fmt::Debug for MwaaAuth {
@@ -153,7 +214,49 @@ impl std::fmt::Debug for MwaaAuth {
         f.debug_struct("MwaaAuth")
             .field("session_cookie", &"***redacted***")
             .field("environment_name", &self.environment_name)
-            .finish()
+            .field("region", &self.region)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MwaaAuth {
+    pub fn new(environment_name: String, session_cookie: String, region: Option<String>) -> Self {
+        Self {
+            session_cookie,
+            environment_name,
+            region,
+            session: Arc::default(),
+        }
+    }
+
+    /// The credential to send with requests, logging in again first if the session was invalidated
+    pub async fn credential(&self, version: &AirflowVersion) -> Result<String> {
+        let mut session = self.session.lock().await;
+        if session.stale {
+            info!("🔑 Renewing MWAA session for {}", self.environment_name);
+            let client = MwaaClient::for_region(self.region.as_deref()).await?;
+            let credential = client.login(&self.environment_name, version).await?;
+            session.renewed = Some(credential);
+            session.renewed_at = Some(Instant::now());
+            session.stale = false;
+        }
+        Ok(session
+            .renewed
+            .clone()
+            .unwrap_or_else(|| self.session_cookie.clone()))
+    }
+
+    /// Mark the session as expired so the next request logs in again.
+    /// Ignored right after a renewal, see `RENEWAL_GRACE_PERIOD`.
+    pub async fn invalidate(&self) {
+        let mut session = self.session.lock().await;
+        if session
+            .renewed_at
+            .is_some_and(|renewed_at| renewed_at.elapsed() < RENEWAL_GRACE_PERIOD)
+        {
+            return;
+        }
+        session.stale = true;
     }
 }
 
@@ -162,47 +265,75 @@ struct LoginForm<'a> {
     token: &'a str,
 }
 
-/// Lists all MWAA environments and returns them as `AirflowConfig` instances
-pub async fn get_mwaa_environment_servers() -> Result<Vec<AirflowConfig>> {
-    let client = MwaaClient::new().await?;
-    let env_names = client.list_environments().await?;
+/// Lists all MWAA environments in the configured regions and returns them as `AirflowConfig` instances
+pub async fn get_mwaa_environment_servers(
+    discovery: Option<&MwaaDiscoveryConfig>,
+) -> Result<Vec<AirflowConfig>> {
+    let discovery = discovery.cloned().unwrap_or_default();
+    let regions: Vec<Option<&str>> = if discovery.regions.is_empty() {
+        vec![None]
+    } else {
+        discovery.regions.iter().map(|r| Some(r.as_str())).collect()
+    };
 
     let mut servers = Vec::new();
 
-    for env_name in env_names {
-        let env = client.get_environment(&env_name).await?;
-
-        // Determine Airflow version from the version string
-        let version = if env.airflow_version.starts_with("2.") {
-            AirflowVersion::V2
-        } else if env.airflow_version.starts_with("3.") {
-            AirflowVersion::V3
-        } else {
-            anyhow::bail!(
-                "Unsupported Airflow version '{}' for environment '{}'",
-                env.airflow_version,
-                env_name
-            );
-        };
-
-        // Create web token and session for this environment
-        let web_token = client.create_web_login_token(&env_name).await?;
-        let session_cookie = client.get_session_cookie(&web_token).await?;
-
-        // Normalize the endpoint URL
-        let endpoint = crate::airflow::config::normalize_endpoint(env.webserver_url);
-
-        servers.push(AirflowConfig {
-            name: env.name.clone(),
-            endpoint,
-            auth: AirflowAuth::Mwaa(MwaaAuth {
-                session_cookie,
-                environment_name: env.name.clone(),
-            }),
-            managed: Some(ManagedService::Mwaa),
-            version,
-            proxy: None,
-        });
+    for region in regions {
+        let client = MwaaClient::for_region(region).await?;
+        let env_names = client.list_environments().await?;
+
+        for env_name in env_names {
+            // Filter on name first to avoid the extra API calls for skipped environments
+            if !discovery.filter.matches_name(&env_name) {
+                info!("Skipping MWAA environment {env_name}: excluded by name filter");
+                continue;
+            }
+
+            let env = client.get_environment(&env_name).await?;
+            if !discovery.filter.matches_tags(&env.tags) {
+                info!("Skipping MWAA environment {env_name}: tags don't match");
+                continue;
+            }
+
+            // Determine Airflow version from the version string
+            let version = if env.airflow_version.starts_with("2.") {
+                AirflowVersion::V2
+            } else if env.airflow_version.starts_with("3.") {
+                AirflowVersion::V3
+            } else {
+                anyhow::bail!(
+                    "Unsupported Airflow version '{}' for environment '{}'",
+                    env.airflow_version,
+                    env_name
+                );
+            };
+
+            // Log in to get a session (Airflow 2) or access token (Airflow 3) for this environment
+            let session_cookie = client.login(&env_name, &version).await?;
+
+            // Normalize the endpoint URL
+            let endpoint = crate::airflow::config::normalize_endpoint(env.webserver_url);
+
+            servers.push(AirflowConfig {
+                name: env.name.clone(),
+                endpoint,
+                auth: AirflowAuth::Mwaa(MwaaAuth::new(
+                    env.name.clone(),
+                    session_cookie,
+                    region.map(str::to_string),
+                )),
+                managed: Some(ManagedService::Mwaa),
+                version,
+                proxy: None,
+                tls: None,
+                timeout_secs: None,
+                connect_timeout_secs: None,
+                max_retries: None,
+                backoff_ms: None,
+                max_backoff_secs: None,
+                protection: Protection::None,
+            });
+        }
     }
 
     info!("Found {} MWAA environment(s)", servers.len());
@@ -215,7 +346,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_mwaa_environments() {
-        let result = get_mwaa_environment_servers().await;
+        let result = get_mwaa_environment_servers(None).await;
         // This test will only work if you have MWAA environments configured
         // and AWS credentials available
         if let Ok(environments) = result {
@@ -226,9 +357,68 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_login_with_web_token_extracts_cookie() {
+        let mut server = mockito::Server::new_async().await;
+        let airflow2 = server
+            .mock("POST", "/aws_mwaa/login")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "token".into(),
+                "web-token".into(),
+            ))
+            .with_status(302)
+            .with_header("location", "/home")
+            .with_header("set-cookie", "session=abc123; Path=/; HttpOnly")
+            .expect(2)
+            .create_async()
+            .await;
+        let airflow3 = server
+            .mock("POST", "/pluginsv2/aws_mwaa/login")
+            .with_status(302)
+            .with_header("set-cookie", "other=1; Path=/")
+            .with_header("set-cookie", "_token=jwt-token; Path=/; Secure")
+            .create_async()
+            .await;
+
+        let url = |path: &str| format!("{}{path}", server.url());
+        let cookie = login_with_web_token(&url("/aws_mwaa/login"), "web-token", "session")
+            .await
+            .unwrap();
+        assert_eq!(cookie, "abc123");
+
+        let token = login_with_web_token(&url("/pluginsv2/aws_mwaa/login"), "web-token", "_token")
+            .await
+            .unwrap();
+        assert_eq!(token, "jwt-token");
+
+        let missing = login_with_web_token(&url("/aws_mwaa/login"), "web-token", "_token").await;
+        assert!(missing.is_err());
+
+        airflow2.assert_async().await;
+        airflow3.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_mwaa_auth_uses_discovered_credential_until_invalidated() {
+        let auth = MwaaAuth::new("env".to_string(), "cookie".to_string(), None);
+        assert_eq!(
+            auth.credential(&AirflowVersion::V2).await.unwrap(),
+            "cookie"
+        );
+
+        // A renewal that just happened is not repeated
+        auth.session.lock().await.renewed_at = Some(Instant::now());
+        auth.invalidate().await;
+        assert!(!auth.session.lock().await.stale);
+
+        auth.session.lock().await.renewed_at = None;
+        auth.invalidate().await;
+        assert!(auth.session.lock().await.stale);
+    }
+
     #[tokio::test]
     async fn test_mwaa_client_new() {
-        let client = MwaaClient::new().await;
+        let client = MwaaClient::for_region(None).await;
         assert!(client.is_ok());
     }
 }
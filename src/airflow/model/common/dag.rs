@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
 /// Common DAG model used by the application
-#[allow(clippy::struct_field_names)]
+#[allow(clippy::struct_excessive_bools, clippy::struct_field_names)]
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Dag {
     pub dag_id: String,
@@ -39,6 +39,10 @@ pub struct Dag {
     /// Used for sorting by schedule frequency
     #[serde(skip)]
     pub computed_schedule_frequency: Option<u64>,
+
+    /// Pinned as a favorite by the user, set when filtering the DAG table
+    #[serde(skip)]
+    pub is_favorite: bool,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -85,6 +89,7 @@ impl From<v1::model::dag::DagResponse> for Dag {
             schedule_interval: value.schedule_interval.clone(),
             computed_state_priority: None,
             computed_schedule_frequency: None,
+            is_favorite: false,
         }
     }
 }
@@ -132,6 +137,7 @@ impl From<v2::model::dag::Dag> for Dag {
             schedule_interval: None,  // V2 API doesn't provide schedule_interval
             computed_state_priority: None,
             computed_schedule_frequency: None,
+            is_favorite: false,
         }
     }
 }
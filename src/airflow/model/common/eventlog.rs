@@ -0,0 +1,289 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::{Date, Duration, OffsetDateTime};
+
+use crate::airflow::client::{v1, v2};
+
+/// An entry of the Airflow event log, e.g. a DAG that was paused or a run that was cleared
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventLog {
+    pub id: i64,
+    pub when: Option<OffsetDateTime>,
+    pub dag_id: Option<String>,
+    pub task_id: Option<String>,
+    pub run_id: Option<String>,
+    pub map_index: Option<i64>,
+    pub try_number: Option<i64>,
+    pub event: String,
+    pub logical_date: Option<OffsetDateTime>,
+    pub owner: Option<String>,
+    pub extra: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventLogList {
+    pub event_logs: Vec<EventLog>,
+    pub total_entries: i64,
+}
+
+/// Filters applied by the server when listing the event log.
+///
+/// Written as `key:value` terms, e.g. `dag:etl run:manual__1 event:clear owner:alice since:24h`.
+/// `since` and `until` take a duration back from now (`30m`, `24h`, `7d`), a date or an RFC 3339 timestamp.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventLogQuery {
+    pub dag_id: Option<String>,
+    pub run_id: Option<String>,
+    pub task_id: Option<String>,
+    pub event: Option<String>,
+    pub owner: Option<String>,
+    pub after: Option<OffsetDateTime>,
+    pub before: Option<OffsetDateTime>,
+}
+
+impl EventLogQuery {
+    pub fn for_dag(dag_id: &str) -> Self {
+        Self {
+            dag_id: Some(dag_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn for_dag_run(dag_id: &str, run_id: &str) -> Self {
+        Self {
+            run_id: Some(run_id.to_string()),
+            ..Self::for_dag(dag_id)
+        }
+    }
+
+    /// Parse the `key:value` terms of a filter, relative times are resolved against `now`
+    pub fn parse(text: &str, now: OffsetDateTime) -> Result<Self> {
+        let mut query = Self::default();
+        for term in text.split_whitespace() {
+            let Some((key, value)) = term.split_once(':') else {
+                bail!("Expected key:value, got '{term}'");
+            };
+            let value = value.to_string();
+            match key {
+                "dag" => query.dag_id = Some(value),
+                "run" => query.run_id = Some(value),
+                "task" => query.task_id = Some(value),
+                "event" => query.event = Some(value),
+                "owner" => query.owner = Some(value),
+                "since" => query.after = Some(parse_time(&value, now)?),
+                "until" => query.before = Some(parse_time(&value, now)?),
+                _ => bail!(
+                    "Unknown filter '{key}', use dag, run, task, event, owner, since or until"
+                ),
+            }
+        }
+        Ok(query)
+    }
+
+    /// The query as filter text, the inverse of [`EventLogQuery::parse`]
+    pub fn to_filter_text(&self) -> String {
+        let time = |t: &OffsetDateTime| t.format(&Rfc3339).unwrap_or_default();
+        [
+            self.dag_id.as_ref().map(|v| format!("dag:{v}")),
+            self.run_id.as_ref().map(|v| format!("run:{v}")),
+            self.task_id.as_ref().map(|v| format!("task:{v}")),
+            self.event.as_ref().map(|v| format!("event:{v}")),
+            self.owner.as_ref().map(|v| format!("owner:{v}")),
+            self.after.as_ref().map(|v| format!("since:{}", time(v))),
+            self.before.as_ref().map(|v| format!("until:{}", time(v))),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+
+    /// Query parameters understood by the `eventLogs` endpoint of both API versions
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        let time = |t: &OffsetDateTime| t.format(&Rfc3339).unwrap_or_default();
+        [
+            ("dag_id", self.dag_id.clone()),
+            ("run_id", self.run_id.clone()),
+            ("task_id", self.task_id.clone()),
+            ("event", self.event.clone()),
+            ("owner", self.owner.clone()),
+            ("after", self.after.as_ref().map(time)),
+            ("before", self.before.as_ref().map(time)),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect()
+    }
+}
+
+/// Parse a duration back from `now` (`30m`, `24h`, `7d`), a date or an RFC 3339 timestamp
+pub fn parse_time(value: &str, now: OffsetDateTime) -> Result<OffsetDateTime> {
+    if let Ok(time) = OffsetDateTime::parse(value, &Rfc3339) {
+        return Ok(time);
+    }
+    if let Ok(date) = Date::parse(
+        value,
+        time::macros::format_description!("[year]-[month]-[day]"),
+    ) {
+        return Ok(date.midnight().assume_utc());
+    }
+    let invalid = || format!("Invalid time '{value}', use e.g. 30m, 24h, 7d or 2024-01-31");
+    let (unit_at, unit) = value.char_indices().last().with_context(invalid)?;
+    let amount: i64 = value[..unit_at].parse().with_context(invalid)?;
+    let seconds_per_unit = match unit {
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        _ => bail!(invalid()),
+    };
+    amount
+        .checked_mul(seconds_per_unit)
+        .and_then(|seconds| now.checked_sub(Duration::seconds(seconds)))
+        .with_context(|| format!("Time '{value}' is out of range"))
+}
+
+// From trait implementations for v1 models
+impl From<v1::model::eventlog::EventLogResponse> for EventLog {
+    fn from(value: v1::model::eventlog::EventLogResponse) -> Self {
+        EventLog {
+            id: value.event_log_id,
+            when: value.when,
+            dag_id: value.dag_id,
+            task_id: value.task_id,
+            run_id: value.run_id,
+            map_index: value.map_index,
+            try_number: value.try_number,
+            event: value.event,
+            logical_date: value.execution_date,
+            owner: value.owner,
+            extra: value.extra,
+        }
+    }
+}
+
+impl From<v1::model::eventlog::EventLogCollection> for EventLogList {
+    fn from(value: v1::model::eventlog::EventLogCollection) -> Self {
+        EventLogList {
+            event_logs: value.event_logs.into_iter().map(Into::into).collect(),
+            total_entries: value.total_entries,
+        }
+    }
+}
+
+// From trait implementations for v2 models
+impl From<v2::model::eventlog::EventLogResponse> for EventLog {
+    fn from(value: v2::model::eventlog::EventLogResponse) -> Self {
+        EventLog {
+            id: value.event_log_id,
+            when: value.when,
+            dag_id: value.dag_id,
+            task_id: value.task_id,
+            run_id: value.run_id,
+            map_index: value.map_index,
+            try_number: value.try_number,
+            event: value.event,
+            logical_date: value.logical_date,
+            owner: value.owner,
+            extra: value.extra,
+        }
+    }
+}
+
+impl From<v2::model::eventlog::EventLogCollection> for EventLogList {
+    fn from(value: v2::model::eventlog::EventLogCollection) -> Self {
+        EventLogList {
+            event_logs: value.event_logs.into_iter().map(Into::into).collect(),
+            total_entries: value.total_entries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_parse_query() {
+        let now = datetime!(2024-03-10 12:00 UTC);
+        let query = EventLogQuery::parse(
+            "dag:etl run:manual__1 event:clear since:24h until:2024-03-10",
+            now,
+        )
+        .unwrap();
+        assert_eq!(query.dag_id.as_deref(), Some("etl"));
+        assert_eq!(query.run_id.as_deref(), Some("manual__1"));
+        assert_eq!(query.event.as_deref(), Some("clear"));
+        assert_eq!(query.after, Some(datetime!(2024-03-09 12:00 UTC)));
+        assert_eq!(query.before, Some(datetime!(2024-03-10 00:00 UTC)));
+        assert_eq!(
+            query.params(),
+            vec![
+                ("dag_id", "etl".to_string()),
+                ("run_id", "manual__1".to_string()),
+                ("event", "clear".to_string()),
+                ("after", "2024-03-09T12:00:00Z".to_string()),
+                ("before", "2024-03-10T00:00:00Z".to_string()),
+            ]
+        );
+        // The filter text of a query parses back into the same query
+        assert_eq!(
+            EventLogQuery::parse(&query.to_filter_text(), now).unwrap(),
+            query
+        );
+
+        assert!(EventLogQuery::parse("etl", now).is_err());
+        assert!(EventLogQuery::parse("user:alice", now).is_err());
+        assert!(EventLogQuery::parse("since:yesterday", now).is_err());
+        assert_eq!(
+            EventLogQuery::parse("", now).unwrap(),
+            EventLogQuery::default()
+        );
+    }
+
+    #[test]
+    fn test_parse_time_rejects_invalid_input() {
+        let now = datetime!(2024-03-10 12:00 UTC);
+        assert_eq!(
+            parse_time("30m", now).unwrap(),
+            datetime!(2024-03-10 11:30 UTC)
+        );
+        // Multi-byte unit
+        assert!(parse_time("5é", now).is_err());
+        assert!(parse_time("é", now).is_err());
+        assert!(parse_time("", now).is_err());
+        // Overflowing duration
+        assert!(parse_time("99999999999999d", now).is_err());
+        assert!(parse_time(&format!("{}m", i64::MAX), now).is_err());
+        // Before the earliest supported date
+        assert!(parse_time("9999999d", now).is_err());
+    }
+
+    #[test]
+    fn test_event_log_from_v2() {
+        let collection: v2::model::eventlog::EventLogCollection = serde_json::from_str(
+            r#"{
+                "event_logs": [{
+                    "event_log_id": 42,
+                    "when": "2024-03-10T11:58:12.345678Z",
+                    "dag_id": "etl",
+                    "task_id": null,
+                    "run_id": "manual__1",
+                    "map_index": null,
+                    "try_number": null,
+                    "event": "clear",
+                    "logical_date": null,
+                    "owner": "alice",
+                    "extra": "{\"dry_run\": false}"
+                }],
+                "total_entries": 1
+            }"#,
+        )
+        .unwrap();
+        let list: EventLogList = collection.into();
+        assert_eq!(list.event_logs[0].id, 42);
+        assert_eq!(list.event_logs[0].owner.as_deref(), Some("alice"));
+        assert!(list.event_logs[0].when.is_some());
+    }
+}
@@ -2,8 +2,11 @@ pub mod connection;
 pub mod dag;
 pub mod dagrun;
 pub mod dagstats;
+pub mod eventlog;
 pub mod importerror;
 pub mod log;
+pub mod monitor;
+pub mod task;
 pub mod taskinstance;
 pub mod variable;
 
@@ -12,7 +15,12 @@ pub use connection::{Connection, ConnectionCollection};
 pub use dag::{Dag, DagList};
 pub use dagrun::{DagRun, DagRunList};
 pub use dagstats::{DagStatistic, DagStatsResponse};
+pub use eventlog::{EventLog, EventLogList, EventLogQuery};
 pub use importerror::{ImportError, ImportErrorList};
 pub use log::Log;
-pub use taskinstance::{TaskInstance, TaskInstanceList};
+pub use monitor::Health;
+pub use task::Task;
+pub use taskinstance::{
+    ClearTaskInstances, MarkScope, TaskInstance, TaskInstanceList, TaskInstanceReference,
+};
 pub use variable::{Variable, VariableCollection};
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::airflow::client::{v1, v2};
+
+/// Health of the Airflow components as reported by the health endpoint
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Health {
+    pub components: Vec<ComponentHealth>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    pub name: String,
+    /// `healthy` or `unhealthy`, or `None` when the component is not deployed
+    pub status: Option<String>,
+    pub latest_heartbeat: Option<String>,
+}
+
+impl Health {
+    /// Names of the components that report themselves as unhealthy
+    pub fn unhealthy_components(&self) -> Vec<&str> {
+        self.components
+            .iter()
+            .filter(|c| c.status.as_deref() == Some("unhealthy"))
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.unhealthy_components().is_empty()
+    }
+}
+
+// From trait implementations for v1 models
+impl From<v1::model::monitor::HealthInfo> for Health {
+    fn from(value: v1::model::monitor::HealthInfo) -> Self {
+        let components = [
+            ("metadatabase", value.metadatabase),
+            ("scheduler", value.scheduler),
+            ("triggerer", value.triggerer),
+            ("dag_processor", value.dag_processor),
+        ];
+        Health {
+            components: components
+                .into_iter()
+                .filter_map(|(name, status)| {
+                    status.map(|s| ComponentHealth {
+                        name: name.to_string(),
+                        status: s.status,
+                        latest_heartbeat: s.latest_heartbeat,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+// From trait implementations for v2 models
+impl From<v2::model::monitor::HealthInfo> for Health {
+    fn from(value: v2::model::monitor::HealthInfo) -> Self {
+        let components = [
+            ("metadatabase", value.metadatabase),
+            ("scheduler", value.scheduler),
+            ("triggerer", value.triggerer),
+            ("dag_processor", value.dag_processor),
+        ];
+        Health {
+            components: components
+                .into_iter()
+                .filter_map(|(name, status)| {
+                    status.map(|s| ComponentHealth {
+                        name: name.to_string(),
+                        status: s.status,
+                        latest_heartbeat: s.latest_heartbeat,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_from_v1() {
+        let info: v1::model::monitor::HealthInfo = serde_json::from_str(
+            r#"{
+                "metadatabase": {"status": "healthy"},
+                "scheduler": {"status": "unhealthy", "latest_scheduler_heartbeat": "2024-01-01T00:00:00+00:00"},
+                "triggerer": {"status": null, "latest_triggerer_heartbeat": null}
+            }"#,
+        )
+        .unwrap();
+        let health: Health = info.into();
+
+        assert_eq!(health.components.len(), 3);
+        assert_eq!(
+            health.components[1].latest_heartbeat.as_deref(),
+            Some("2024-01-01T00:00:00+00:00")
+        );
+        // A triggerer that is not deployed does not make the environment unhealthy
+        assert_eq!(health.unhealthy_components(), vec!["scheduler"]);
+        assert!(!health.is_healthy());
+    }
+}
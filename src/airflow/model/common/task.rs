@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use crate::airflow::client::v1;
+use crate::airflow::client::v2;
+
+/// Common Task model used by the application, the definition shared by all task instances of a task
+#[allow(clippy::struct_field_names)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    pub task_id: String,
+    pub owner: Option<String>,
+    pub operator: Option<String>,
+    pub trigger_rule: Option<String>,
+    pub retries: Option<f64>,
+    pub pool: Option<String>,
+    pub queue: Option<String>,
+    /// Names of the fields rendered with Jinja before the task runs
+    pub template_fields: Vec<String>,
+    pub downstream_task_ids: Vec<String>,
+    /// Task documentation in Markdown
+    pub doc_md: Option<String>,
+}
+
+// From trait implementations for v1 models
+impl From<v1::model::task::TaskResponse> for Task {
+    fn from(value: v1::model::task::TaskResponse) -> Self {
+        Task {
+            task_id: value.task_id,
+            owner: value.owner,
+            operator: value.class_ref.and_then(|class_ref| class_ref.class_name),
+            trigger_rule: value.trigger_rule,
+            retries: value.retries,
+            pool: value.pool,
+            queue: value.queue,
+            template_fields: value.template_fields,
+            downstream_task_ids: value.downstream_task_ids,
+            doc_md: value.doc_md,
+        }
+    }
+}
+
+// From trait implementations for v2 models
+impl From<v2::model::task::TaskResponse> for Task {
+    fn from(value: v2::model::task::TaskResponse) -> Self {
+        Task {
+            task_id: value.task_id,
+            owner: value.owner,
+            operator: value.operator_name,
+            trigger_rule: value.trigger_rule,
+            retries: value.retries,
+            pool: value.pool,
+            queue: value.queue,
+            template_fields: value.template_fields.unwrap_or_default(),
+            downstream_task_ids: value.downstream_task_ids,
+            doc_md: value.doc_md,
+        }
+    }
+}
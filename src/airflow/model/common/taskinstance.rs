@@ -1,6 +1,7 @@
 use crate::airflow::client::v1;
 use crate::airflow::client::v2;
 use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
 /// Common `TaskInstance` model used by the application
@@ -27,6 +28,11 @@ pub struct TaskInstance {
     pub queued_when: Option<OffsetDateTime>,
     pub pid: Option<i64>,
     pub note: Option<String>,
+    pub executor: Option<String>,
+    /// Serialized executor config, e.g. a Kubernetes pod override
+    pub executor_config: Option<String>,
+    /// Values of the template fields after rendering, by field name
+    pub rendered_fields: Option<serde_json::Value>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -35,6 +41,72 @@ pub struct TaskInstanceList {
     pub total_entries: i64,
 }
 
+/// Selects the task instances of a DAG cleared by `clearTaskInstances`
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ClearTaskInstances {
+    /// Earliest logical date of the runs to clear
+    pub start_date: Option<OffsetDateTime>,
+    /// Latest logical date of the runs to clear
+    pub end_date: Option<OffsetDateTime>,
+    /// Only clear `failed` and `upstream_failed` task instances
+    pub only_failed: bool,
+}
+
+impl ClearTaskInstances {
+    /// Request body understood by the `clearTaskInstances` endpoint of both API versions
+    pub fn body(&self, dry_run: bool) -> serde_json::Value {
+        let mut body = serde_json::json!({
+            "dry_run": dry_run,
+            "only_failed": self.only_failed,
+            "reset_dag_runs": true,
+        });
+        let time = |t: &OffsetDateTime| t.format(&Rfc3339).unwrap_or_default();
+        if let Some(start_date) = &self.start_date {
+            body["start_date"] = time(start_date).into();
+        }
+        if let Some(end_date) = &self.end_date {
+            body["end_date"] = time(end_date).into();
+        }
+        body
+    }
+}
+
+/// Related task instances also set to the new state when marking a task instance
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkScope {
+    pub upstream: bool,
+    pub downstream: bool,
+    pub future: bool,
+    pub past: bool,
+}
+
+impl MarkScope {
+    pub fn is_empty(self) -> bool {
+        self == Self::default()
+    }
+
+    /// Add the `include_*` fields understood by the marking endpoints of both API versions
+    pub fn extend(self, mut body: serde_json::Value) -> serde_json::Value {
+        body["include_upstream"] = self.upstream.into();
+        body["include_downstream"] = self.downstream.into();
+        body["include_future"] = self.future.into();
+        body["include_past"] = self.past.into();
+        body
+    }
+}
+
+/// A task instance affected by clearing or marking
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskInstanceReference {
+    pub task_id: String,
+    pub dag_id: String,
+    pub dag_run_id: String,
+    pub logical_date: Option<OffsetDateTime>,
+    /// Not reported by API v1
+    pub state: Option<String>,
+}
+
 // From trait implementations for v1 models
 impl From<v1::model::taskinstance::TaskInstanceResponse> for TaskInstance {
     fn from(value: v1::model::taskinstance::TaskInstanceResponse) -> Self {
@@ -60,6 +132,13 @@ impl From<v1::model::taskinstance::TaskInstanceResponse> for TaskInstance {
             queued_when: value.queued_when,
             pid: value.pid,
             note: value.note,
+            executor: value.executor,
+            executor_config: value.executor_config.and_then(|config| match config {
+                serde_json::Value::Null => None,
+                serde_json::Value::String(config) => Some(config),
+                config => Some(config.to_string()),
+            }),
+            rendered_fields: Some(value.rendered_fields),
         }
     }
 }
@@ -102,6 +181,9 @@ impl From<v2::model::taskinstance::TaskInstance> for TaskInstance {
             queued_when: value.queued_when,
             pid: value.pid,
             note: value.note,
+            executor: value.executor,
+            executor_config: Some(value.executor_config),
+            rendered_fields: Some(value.rendered_fields),
         }
     }
 }
@@ -118,3 +200,27 @@ impl From<v2::model::taskinstance::TaskInstanceList> for TaskInstanceList {
         }
     }
 }
+
+impl From<v1::model::taskinstance::TaskInstanceReference> for TaskInstanceReference {
+    fn from(value: v1::model::taskinstance::TaskInstanceReference) -> Self {
+        TaskInstanceReference {
+            task_id: value.task_id,
+            dag_id: value.dag_id,
+            dag_run_id: value.dag_run_id,
+            logical_date: value.execution_date,
+            state: None,
+        }
+    }
+}
+
+impl From<v2::model::taskinstance::TaskInstanceReference> for TaskInstanceReference {
+    fn from(value: v2::model::taskinstance::TaskInstanceReference) -> Self {
+        TaskInstanceReference {
+            task_id: value.task_id,
+            dag_id: value.dag_id,
+            dag_run_id: value.dag_run_id,
+            logical_date: value.logical_date,
+            state: value.state,
+        }
+    }
+}
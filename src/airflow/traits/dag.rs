@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use anyhow::Result;
 use async_trait::async_trait;
 
@@ -30,4 +32,11 @@ pub trait DagOperations: Send + Sync {
 
     /// Get detailed DAG information including doc_md
     async fn get_dag_details(&self, dag_id: &str) -> Result<Dag>;
+
+    /// Ids of the DAGs pinned as favorites
+    /// (stored on the server in v2, in a local state file per environment in v1)
+    async fn list_favorite_dags(&self) -> Result<HashSet<String>>;
+
+    /// Pin or unpin a DAG
+    async fn set_dag_favorite(&self, dag_id: &str, favorite: bool) -> Result<()>;
 }
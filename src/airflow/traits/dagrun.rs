@@ -16,6 +16,9 @@ pub trait DagRunOperations: Send + Sync {
     #[allow(unused)]
     async fn list_all_dagruns(&self) -> Result<DagRunList>;
 
+    /// List the most recent DAG runs across all DAGs that are in one of `states`
+    async fn list_recent_dagruns_in_states(&self, states: &[&str], limit: i64) -> Result<DagRunList>;
+
     /// List recent DAG runs for multiple DAGs (batch operation)
     /// This uses the POST /dags/~/dagRuns/list endpoint which accepts multiple dag_ids
     async fn list_dagruns_batch(&self, dag_ids: Vec<String>, limit_per_dag: i64) -> Result<DagRunList>;
@@ -26,6 +29,9 @@ pub trait DagRunOperations: Send + Sync {
     /// Clear a DAG run
     async fn clear_dagrun(&self, dag_id: &str, dag_run_id: &str) -> Result<()>;
 
+    /// Replace the note of a DAG run, an empty note clears it
+    async fn set_dag_run_note(&self, dag_id: &str, dag_run_id: &str, note: &str) -> Result<()>;
+
     /// Trigger a new DAG run
     async fn trigger_dag_run(&self, dag_id: &str, logical_date: Option<&str>) -> Result<()>;
 }
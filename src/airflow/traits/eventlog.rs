@@ -0,0 +1,11 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::airflow::model::common::{EventLogList, EventLogQuery};
+
+/// Trait for Event Log operations
+#[async_trait]
+pub trait EventLogOperations: Send + Sync {
+    /// List the most recent event log entries matching the query, newest first
+    async fn list_event_logs(&self, query: &EventLogQuery) -> Result<EventLogList>;
+}
@@ -2,7 +2,9 @@ pub mod connection;
 pub mod dag;
 pub mod dagrun;
 pub mod dagstats;
+pub mod eventlog;
 pub mod log;
+pub mod monitor;
 pub mod task;
 pub mod taskinstance;
 pub mod variable;
@@ -11,11 +13,14 @@ pub use connection::ConnectionOperations;
 pub use dag::DagOperations;
 pub use dagrun::DagRunOperations;
 pub use dagstats::DagStatsOperations;
+pub use eventlog::EventLogOperations;
 pub use log::LogOperations;
+pub use monitor::MonitorOperations;
 pub use task::TaskOperations;
 pub use taskinstance::TaskInstanceOperations;
 pub use variable::VariableOperations;
 
+use crate::airflow::client::oidc::DeviceLoginPrompt;
 use crate::airflow::config::AirflowVersion;
 use crate::airflow::model::common::ImportErrorList;
 use crate::app::worker::OpenItem;
@@ -27,7 +32,7 @@ use async_trait::async_trait;
 /// to provide a consistent interface for interacting with Airflow.
 #[async_trait]
 pub trait AirflowClient:
-    DagOperations + DagRunOperations + TaskInstanceOperations + TaskOperations + LogOperations + DagStatsOperations + VariableOperations + ConnectionOperations
+    DagOperations + DagRunOperations + TaskInstanceOperations + TaskOperations + LogOperations + DagStatsOperations + EventLogOperations + VariableOperations + ConnectionOperations + MonitorOperations
 {
     /// Get the Airflow version this client is configured for
     #[allow(unused)]
@@ -38,6 +43,9 @@ pub trait AirflowClient:
     #[allow(unused)]
     fn build_open_url(&self, item: &OpenItem) -> Result<String>;
     
+    /// The login the user has to approve before requests can be sent, if any
+    fn pending_login(&self) -> Option<DeviceLoginPrompt>;
+
     /// Get the count of import errors
     async fn get_import_error_count(&self) -> Result<usize>;
     
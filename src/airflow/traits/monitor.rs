@@ -0,0 +1,21 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::airflow::model::common::Health;
+
+/// Trait for the operations behind the environment overview
+#[async_trait]
+pub trait MonitorOperations: Send + Sync {
+    /// Get the health of the metadatabase, scheduler, triggerer and DAG processor
+    async fn get_health(&self) -> Result<Health>;
+
+    /// Get the Airflow version running on the server, e.g. `2.10.3`
+    async fn get_server_version(&self) -> Result<String>;
+
+    /// Count DAG runs across all DAGs in a state that ended at or after `since`
+    async fn count_dag_runs(&self, state: &str, since: DateTime<Utc>) -> Result<u64>;
+
+    /// Count task instances across all DAG runs in a state
+    async fn count_task_instances(&self, state: &str) -> Result<u64>;
+}
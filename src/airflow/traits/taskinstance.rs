@@ -1,7 +1,9 @@
 use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::airflow::model::common::TaskInstanceList;
+use crate::airflow::model::common::{
+    ClearTaskInstances, MarkScope, TaskInstanceList, TaskInstanceReference,
+};
 
 /// Trait for Task Instance operations
 #[async_trait]
@@ -17,13 +19,38 @@ pub trait TaskInstanceOperations: Send + Sync {
     #[allow(unused)]
     async fn list_all_taskinstances(&self) -> Result<TaskInstanceList>;
 
-    /// Mark a task instance with a specific status
-    async fn mark_task_instance(
+    /// List task instances across all DAG runs that are in `state`
+    async fn list_task_instances_in_state(&self, state: &str, limit: i64) -> Result<TaskInstanceList>;
+
+    /// List the task instances that marking `task_ids` with `status` would change,
+    /// including the related task instances selected by `scope`
+    async fn preview_mark_task_instances(
         &self,
         dag_id: &str,
         dag_run_id: &str,
-        task_id: &str,
+        task_ids: &[String],
+        status: &str,
+        scope: &MarkScope,
+    ) -> Result<Vec<TaskInstanceReference>>;
+
+    /// Mark task instances of a DAG run with a specific status, together with the
+    /// related task instances selected by `scope`. Returns the result for each task.
+    async fn mark_task_instances(
+        &self,
+        dag_id: &str,
+        dag_run_id: &str,
+        task_ids: &[String],
         status: &str,
+        scope: &MarkScope,
+    ) -> Vec<(String, Result<()>)>;
+
+    /// Replace the note of a task instance, an empty note clears it
+    async fn set_task_instance_note(
+        &self,
+        dag_id: &str,
+        dag_run_id: &str,
+        task_id: &str,
+        note: &str,
     ) -> Result<()>;
 
     /// Clear a task instance
@@ -33,4 +60,13 @@ pub trait TaskInstanceOperations: Send + Sync {
         dag_run_id: &str,
         task_id: &str,
     ) -> Result<()>;
+
+    /// Clear the task instances of a DAG selected by `clear` in one request,
+    /// or only list them when `dry_run` is set
+    async fn clear_task_instances(
+        &self,
+        dag_id: &str,
+        clear: &ClearTaskInstances,
+        dry_run: bool,
+    ) -> Result<Vec<TaskInstanceReference>>;
 }
@@ -0,0 +1,650 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::future::join_all;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::airflow::managed_services::discovery::wildcard_match;
+use crate::airflow::model::common::{Dag, DagRun};
+use crate::airflow::traits::AirflowClient;
+
+pub mod notify;
+pub mod webhook;
+
+use notify::{Notifier, NotifySink};
+use webhook::WebhookConfig;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_mins(1);
+
+/// Number of most recent failed or running runs checked per environment
+const RECENT_RUNS: i64 = 100;
+
+/// Number of task instances up for retry checked per environment
+const RECENT_TASK_INSTANCES: i64 = 100;
+
+/// Settings for the failure watcher, configured in the `[watch]` section.
+///
+/// ```toml
+/// [watch]
+/// interval_secs = 60
+/// tui = true
+/// notify = ["desktop", "bell", "command"]
+/// command = "~/bin/page-me.sh"
+///
+/// [[watch.rules]]
+/// environments = ["prod-*"]
+/// tags = ["critical"]
+/// events = ["failed", "sla_exceeded"]
+/// sla_secs = 3600
+/// webhooks = ["ops"]
+///
+/// [[watch.webhooks]]
+/// name = "ops"
+/// url = "$SLACK_WEBHOOK_URL"
+/// format = "slack"
+/// ```
+///
+/// Without rules, failures and retries of every DAG in every environment are reported.
+/// Paused DAGs are not watched.
+/// A webhook receives the events of the rules naming it, or every event when no rule names it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct WatchConfig {
+    /// Seconds between two polls (default: 60)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+    /// Also run the watcher in the background of the TUI
+    #[serde(default)]
+    pub tui: bool,
+    /// Where notifications are sent (default: desktop)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify: Vec<NotifySink>,
+    /// Shell command run for every notification by the `command` sink
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<WatchRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+impl WatchConfig {
+    pub fn interval(&self) -> Duration {
+        self.interval_secs
+            .map_or(DEFAULT_INTERVAL, Duration::from_secs)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.interval_secs == Some(0) {
+            return Err(anyhow::anyhow!(
+                "watch.interval_secs must be greater than 0"
+            ));
+        }
+        if self.notify.contains(&NotifySink::Command) && self.command.is_none() {
+            return Err(anyhow::anyhow!(
+                "watch.notify contains \"command\" but watch.command is not set"
+            ));
+        }
+        for (idx, rule) in self.rules.iter().enumerate() {
+            if rule.events.contains(&WatchEventKind::SlaExceeded) && rule.sla_secs.is_none() {
+                return Err(anyhow::anyhow!(
+                    "Watch rule #{} watches sla_exceeded but has no sla_secs",
+                    idx + 1
+                ));
+            }
+            if let Some(name) = rule
+                .webhooks
+                .iter()
+                .find(|name| !self.webhooks.iter().any(|hook| &hook.name == *name))
+            {
+                return Err(anyhow::anyhow!(
+                    "Watch rule #{} routes to unknown webhook '{name}'",
+                    idx + 1
+                ));
+            }
+        }
+        for (idx, webhook) in self.webhooks.iter().enumerate() {
+            if webhook.url.is_empty() {
+                return Err(anyhow::anyhow!("Webhook '{}' has no url", webhook.name));
+            }
+            if self.webhooks[..idx]
+                .iter()
+                .any(|hook| hook.name == webhook.name)
+            {
+                return Err(anyhow::anyhow!("Duplicate webhook name '{}'", webhook.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// The configured rules, or a single rule watching everything
+    fn rules(&self) -> Vec<WatchRule> {
+        if self.rules.is_empty() {
+            vec![WatchRule::default()]
+        } else {
+            self.rules.clone()
+        }
+    }
+}
+
+/// Selects DAGs to watch and the events to report for them.
+/// Environment and DAG patterns support `*` wildcards.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct WatchRule {
+    /// Environments to watch (all if empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub environments: Vec<String>,
+    /// DAG ids to watch, DAGs matching either `dags` or `tags` are watched (all if both are empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dags: Vec<String>,
+    /// Watch DAGs carrying any of these tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Events to report (default: `failed` and `up_for_retry`, plus `sla_exceeded` when `sla_secs` is set)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<WatchEventKind>,
+    /// Report runs that are still running after this many seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sla_secs: Option<u64>,
+    /// Names of the webhooks that receive the events of this rule
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<String>,
+}
+
+impl WatchRule {
+    pub fn matches_environment(&self, environment: &str) -> bool {
+        self.environments.is_empty()
+            || self
+                .environments
+                .iter()
+                .any(|pattern| wildcard_match(pattern, environment))
+    }
+
+    pub fn matches_dag(&self, dag: &Dag) -> bool {
+        let tags: Vec<String> = dag.tags.iter().map(|tag| tag.name.clone()).collect();
+        self.matches_dag_id(&dag.dag_id, &tags)
+    }
+
+    fn matches_dag_id(&self, dag_id: &str, tags: &[String]) -> bool {
+        (self.dags.is_empty() && self.tags.is_empty())
+            || self
+                .dags
+                .iter()
+                .any(|pattern| wildcard_match(pattern, dag_id))
+            || tags.iter().any(|tag| self.tags.contains(tag))
+    }
+
+    /// Whether this rule would have reported the event
+    pub fn matches_event(&self, event: &WatchEvent) -> bool {
+        let within_sla = event.kind == WatchEventKind::SlaExceeded
+            && event.running_for.unwrap_or_default()
+                <= Duration::from_secs(self.sla_secs.unwrap_or_default());
+        self.matches_environment(&event.environment)
+            && self.matches_dag_id(&event.dag_id, &event.tags)
+            && self.watches(event.kind)
+            && !within_sla
+    }
+
+    fn watches(&self, kind: WatchEventKind) -> bool {
+        if self.events.is_empty() {
+            kind != WatchEventKind::SlaExceeded || self.sla_secs.is_some()
+        } else {
+            self.events.contains(&kind)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchEventKind {
+    Failed,
+    UpForRetry,
+    SlaExceeded,
+}
+
+impl Display for WatchEventKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatchEventKind::Failed => write!(f, "failed"),
+            WatchEventKind::UpForRetry => write!(f, "up_for_retry"),
+            WatchEventKind::SlaExceeded => write!(f, "sla_exceeded"),
+        }
+    }
+}
+
+/// Something the watcher reports
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchEvent {
+    pub kind: WatchEventKind,
+    pub environment: String,
+    pub dag_id: String,
+    pub dag_run_id: String,
+    pub task_id: Option<String>,
+    /// Attempt of the task that is up for retry
+    pub try_number: Option<i64>,
+    /// How long an overdue run has been running
+    pub running_for: Option<Duration>,
+    /// Tags of the DAG
+    pub tags: Vec<String>,
+}
+
+impl WatchEvent {
+    /// Identifies the event, so it is only reported once
+    fn key(&self) -> String {
+        format!(
+            "{}/{}/{}/{}/{}",
+            self.kind,
+            self.dag_id,
+            self.dag_run_id,
+            self.task_id.as_deref().unwrap_or_default(),
+            self.try_number.unwrap_or_default()
+        )
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self.kind {
+            WatchEventKind::Failed => "DAG run failed",
+            WatchEventKind::UpForRetry => "Task up for retry",
+            WatchEventKind::SlaExceeded => "SLA exceeded",
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let target = format!("{} / {}", self.environment, self.dag_id);
+        match self.kind {
+            WatchEventKind::Failed => format!("{target}: run {} failed", self.dag_run_id),
+            WatchEventKind::UpForRetry => format!(
+                "{target}: task {} of run {} is up for retry (try {})",
+                self.task_id.as_deref().unwrap_or_default(),
+                self.dag_run_id,
+                self.try_number.unwrap_or_default()
+            ),
+            WatchEventKind::SlaExceeded => format!(
+                "{target}: run {} has been running for {} min",
+                self.dag_run_id,
+                self.running_for.unwrap_or_default().as_secs() / 60
+            ),
+        }
+    }
+}
+
+/// Result of one poll over all environments
+#[derive(Debug, Default)]
+pub struct WatchPoll {
+    /// Events that were not reported before
+    pub events: Vec<WatchEvent>,
+    pub errors: Vec<String>,
+}
+
+/// What to look for in the runs of one DAG, combined from all rules matching it
+#[derive(Debug, Default, PartialEq)]
+struct WatchTarget {
+    dag_id: String,
+    tags: Vec<String>,
+    events: HashSet<WatchEventKind>,
+    sla: Option<Duration>,
+}
+
+impl WatchTarget {
+    fn for_dag(dag: &Dag, rules: &[WatchRule]) -> Option<Self> {
+        let mut target = WatchTarget {
+            dag_id: dag.dag_id.clone(),
+            tags: dag.tags.iter().map(|tag| tag.name.clone()).collect(),
+            ..Default::default()
+        };
+        for rule in rules.iter().filter(|rule| rule.matches_dag(dag)) {
+            for kind in [
+                WatchEventKind::Failed,
+                WatchEventKind::UpForRetry,
+                WatchEventKind::SlaExceeded,
+            ] {
+                if rule.watches(kind) {
+                    target.events.insert(kind);
+                }
+            }
+            if let Some(sla) = rule
+                .sla_secs
+                .filter(|_| rule.watches(WatchEventKind::SlaExceeded))
+            {
+                let sla = Duration::from_secs(sla);
+                target.sla = Some(target.sla.map_or(sla, |current| current.min(sla)));
+            }
+        }
+        (!target.events.is_empty()).then_some(target)
+    }
+}
+
+/// Polls recent DAG runs and reports new failures, retries and SLA misses.
+/// The first successful poll of an environment only records what is already there,
+/// so starting the watcher doesn't replay old failures.
+pub struct Watcher {
+    rules: Vec<WatchRule>,
+    /// Keys of the events found in the last successful poll of each environment
+    seen: HashMap<String, HashSet<String>>,
+}
+
+impl Watcher {
+    pub fn new(config: &WatchConfig) -> Self {
+        Self {
+            rules: config.rules(),
+            seen: HashMap::new(),
+        }
+    }
+
+    pub async fn poll(&mut self, environments: &[(String, Arc<dyn AirflowClient>)]) -> WatchPoll {
+        let polls = environments.iter().filter_map(|(name, client)| {
+            let rules: Vec<WatchRule> = self
+                .rules
+                .iter()
+                .filter(|rule| rule.matches_environment(name))
+                .cloned()
+                .collect();
+            (!rules.is_empty()).then_some(async move {
+                (name, poll_environment(name, client.as_ref(), &rules).await)
+            })
+        });
+
+        let mut result = WatchPoll::default();
+        for (name, events) in join_all(polls).await {
+            match events {
+                Ok(events) => result.events.extend(self.record(name, events)),
+                Err(e) => result.errors.push(format!("{name}: {e}")),
+            }
+        }
+        result
+    }
+
+    /// Remember the events currently found in an environment and return the ones not seen before
+    fn record(&mut self, environment: &str, events: Vec<WatchEvent>) -> Vec<WatchEvent> {
+        let keys: HashSet<String> = events.iter().map(WatchEvent::key).collect();
+        let Some(previous) = self.seen.insert(environment.to_string(), keys) else {
+            info!(
+                "Watching {environment}, ignoring {} existing event(s)",
+                events.len()
+            );
+            return vec![];
+        };
+        events
+            .into_iter()
+            .filter(|event| !previous.contains(&event.key()))
+            .collect()
+    }
+}
+
+/// Poll forever, sending notifications for new events and handing every poll to `on_poll`.
+/// Failed notifications are added to the errors of the poll.
+pub async fn watch(
+    config: &WatchConfig,
+    environments: &[(String, Arc<dyn AirflowClient>)],
+    mut on_poll: impl FnMut(&WatchPoll),
+) -> Result<()> {
+    let mut watcher = Watcher::new(config);
+    let notifier = Notifier::new(config)?;
+    let mut interval = tokio::time::interval(config.interval());
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let mut poll = watcher.poll(environments).await;
+        debug!(
+            "Watch poll found {} new event(s) and {} error(s)",
+            poll.events.len(),
+            poll.errors.len()
+        );
+        for event in &poll.events {
+            let failures = notifier.notify(event).await;
+            poll.errors.extend(failures);
+        }
+        on_poll(&poll);
+    }
+}
+
+async fn poll_environment(
+    environment: &str,
+    client: &dyn AirflowClient,
+    rules: &[WatchRule],
+) -> Result<Vec<WatchEvent>> {
+    let targets = watch_targets(&client.list_dags().await?.dags, rules);
+    let watched =
+        |kind: WatchEventKind| targets.values().any(|target| target.events.contains(&kind));
+
+    // Runs and task instances of all DAGs are fetched at once rather than DAG by DAG
+    let now = OffsetDateTime::now_utc();
+    let mut events = Vec::new();
+    if watched(WatchEventKind::Failed) || watched(WatchEventKind::SlaExceeded) {
+        let runs = client
+            .list_recent_dagruns_in_states(&["failed", "running"], RECENT_RUNS)
+            .await?
+            .dag_runs;
+        let mut runs_by_dag: HashMap<String, Vec<DagRun>> = HashMap::new();
+        for run in runs {
+            runs_by_dag.entry(run.dag_id.clone()).or_default().push(run);
+        }
+        for (dag_id, runs) in &runs_by_dag {
+            if let Some(target) = targets.get(dag_id) {
+                events.extend(run_events(environment, target, runs, now));
+            }
+        }
+    }
+
+    if watched(WatchEventKind::UpForRetry) {
+        let task_instances = client
+            .list_task_instances_in_state("up_for_retry", RECENT_TASK_INSTANCES)
+            .await?
+            .task_instances;
+        events.extend(task_instances.into_iter().filter_map(|ti| {
+            let target = targets
+                .get(&ti.dag_id)
+                .filter(|target| target.events.contains(&WatchEventKind::UpForRetry))?;
+            Some(WatchEvent {
+                kind: WatchEventKind::UpForRetry,
+                environment: environment.to_string(),
+                dag_id: ti.dag_id,
+                dag_run_id: ti.dag_run_id,
+                task_id: Some(ti.task_id),
+                try_number: Some(ti.try_number),
+                running_for: None,
+                tags: target.tags.clone(),
+            })
+        }));
+    }
+    Ok(events)
+}
+
+/// The DAGs to watch by id, paused DAGs don't run and are skipped
+fn watch_targets(dags: &[Dag], rules: &[WatchRule]) -> HashMap<String, WatchTarget> {
+    dags.iter()
+        .filter(|dag| !dag.is_paused)
+        .filter_map(|dag| WatchTarget::for_dag(dag, rules))
+        .map(|target| (target.dag_id.clone(), target))
+        .collect()
+}
+
+/// Failed and overdue runs among the recent failed or running runs of a DAG
+fn run_events(
+    environment: &str,
+    target: &WatchTarget,
+    runs: &[DagRun],
+    now: OffsetDateTime,
+) -> Vec<WatchEvent> {
+    let event = |kind, run: &DagRun, running_for| WatchEvent {
+        kind,
+        environment: environment.to_string(),
+        dag_id: target.dag_id.clone(),
+        dag_run_id: run.dag_run_id.clone(),
+        task_id: None,
+        try_number: None,
+        running_for,
+        tags: target.tags.clone(),
+    };
+
+    let mut events = Vec::new();
+    for run in runs {
+        match run.state.as_str() {
+            "failed" if target.events.contains(&WatchEventKind::Failed) => {
+                events.push(event(WatchEventKind::Failed, run, None));
+            }
+            "running" => {
+                let running_for = run
+                    .start_date
+                    .and_then(|start| Duration::try_from(now - start).ok());
+                if let (Some(sla), Some(running_for)) = (target.sla, running_for) {
+                    if running_for > sla {
+                        events.push(event(WatchEventKind::SlaExceeded, run, Some(running_for)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airflow::model::common::dag::Tag;
+
+    fn dag(dag_id: &str, tags: &[&str]) -> Dag {
+        Dag {
+            dag_id: dag_id.to_string(),
+            tags: tags
+                .iter()
+                .map(|name| Tag {
+                    name: (*name).to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn run(dag_run_id: &str, state: &str, start_date: Option<OffsetDateTime>) -> DagRun {
+        DagRun {
+            dag_run_id: dag_run_id.to_string(),
+            state: state.to_string(),
+            start_date,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_watch_config_from_toml() {
+        let config: WatchConfig = toml::from_str(
+            r#"
+            interval_secs = 30
+            notify = ["bell", "command"]
+            command = "echo"
+
+            [[rules]]
+            environments = ["prod-*"]
+            tags = ["critical"]
+            events = ["failed", "sla_exceeded"]
+            sla_secs = 3600
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.interval(), Duration::from_secs(30));
+        assert_eq!(config.notify, vec![NotifySink::Bell, NotifySink::Command]);
+        assert!(config.rules[0].matches_environment("prod-eu"));
+        assert!(!config.rules[0].matches_environment("dev"));
+
+        let without_command = WatchConfig {
+            notify: vec![NotifySink::Command],
+            ..Default::default()
+        };
+        assert!(without_command.validate().is_err());
+    }
+
+    #[test]
+    fn test_watch_target_combines_matching_rules() {
+        let rules = vec![
+            WatchRule {
+                dags: vec!["load_*".to_string()],
+                events: vec![WatchEventKind::Failed],
+                ..Default::default()
+            },
+            WatchRule {
+                tags: vec!["critical".to_string()],
+                sla_secs: Some(600),
+                ..Default::default()
+            },
+        ];
+
+        let target = WatchTarget::for_dag(&dag("load_sales", &[]), &rules).unwrap();
+        assert_eq!(target.events, HashSet::from([WatchEventKind::Failed]));
+        assert_eq!(target.sla, None);
+
+        let target = WatchTarget::for_dag(&dag("export", &["critical"]), &rules).unwrap();
+        assert_eq!(
+            target.events,
+            HashSet::from([
+                WatchEventKind::Failed,
+                WatchEventKind::UpForRetry,
+                WatchEventKind::SlaExceeded
+            ])
+        );
+        assert_eq!(target.sla, Some(Duration::from_mins(10)));
+
+        assert!(WatchTarget::for_dag(&dag("export", &[]), &rules).is_none());
+    }
+
+    #[test]
+    fn test_paused_dags_are_not_watched() {
+        let paused = Dag {
+            is_paused: true,
+            ..dag("archive", &[])
+        };
+        let targets = watch_targets(&[dag("etl", &[]), paused], &[WatchRule::default()]);
+        assert_eq!(targets.len(), 1);
+        assert!(targets.contains_key("etl"));
+    }
+
+    #[test]
+    fn test_run_events() {
+        let now = OffsetDateTime::now_utc();
+        let target = WatchTarget {
+            dag_id: "etl".to_string(),
+            tags: vec![],
+            events: HashSet::from([WatchEventKind::Failed, WatchEventKind::SlaExceeded]),
+            sla: Some(Duration::from_hours(1)),
+        };
+        let runs = vec![
+            run("a", "failed", None),
+            run("b", "success", None),
+            run("c", "running", Some(now - time::Duration::hours(2))),
+            run("d", "running", Some(now - time::Duration::minutes(5))),
+        ];
+        let events = run_events("prod", &target, &runs, now);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].summary(), "prod / etl: run a failed");
+        assert_eq!(events[1].kind, WatchEventKind::SlaExceeded);
+        assert_eq!(events[1].dag_run_id, "c");
+    }
+
+    #[test]
+    fn test_events_are_reported_once_after_the_first_poll() {
+        let mut watcher = Watcher::new(&WatchConfig::default());
+        let target = WatchTarget {
+            dag_id: "etl".to_string(),
+            tags: vec![],
+            events: HashSet::from([WatchEventKind::Failed]),
+            sla: None,
+        };
+        let now = OffsetDateTime::now_utc();
+        let failed = |ids: &[&str]| {
+            let runs: Vec<DagRun> = ids.iter().map(|id| run(id, "failed", None)).collect();
+            run_events("prod", &target, &runs, now)
+        };
+
+        // Failures that exist when the watcher starts are not reported
+        assert!(watcher.record("prod", failed(&["a"])).is_empty());
+        let new = watcher.record("prod", failed(&["a", "b"]));
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].dag_run_id, "b");
+        assert!(watcher.record("prod", failed(&["a", "b"])).is_empty());
+    }
+}
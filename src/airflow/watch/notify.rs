@@ -0,0 +1,241 @@
+use std::io::Write;
+
+use anyhow::Result;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use super::webhook::{WebhookConfig, WEBHOOK_TIMEOUT};
+use super::{WatchConfig, WatchEvent, WatchEventKind, WatchRule};
+
+/// Where watch notifications are sent
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifySink {
+    /// Desktop notification through the freedesktop `notify-send` command
+    Desktop,
+    /// Terminal bell
+    Bell,
+    /// The shell command configured in `watch.command`
+    Command,
+}
+
+pub struct Notifier {
+    sinks: Vec<NotifySink>,
+    command: Option<String>,
+    rules: Vec<WatchRule>,
+    webhooks: Vec<WebhookConfig>,
+    http: reqwest::Client,
+}
+
+impl Notifier {
+    pub fn new(config: &WatchConfig) -> Result<Self> {
+        // Only fall back to desktop notifications when nothing else is configured
+        let sinks = if config.notify.is_empty() && config.webhooks.is_empty() {
+            vec![NotifySink::Desktop]
+        } else {
+            config.notify.clone()
+        };
+        let http = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .use_rustls_tls()
+            .build()?;
+        Ok(Self {
+            sinks,
+            command: config.command.clone(),
+            rules: config.rules(),
+            webhooks: config.webhooks.clone(),
+            http,
+        })
+    }
+
+    /// Webhooks an event is routed to: those named by a rule matching the event,
+    /// plus the webhooks no rule names
+    fn webhooks_for<'a>(
+        &'a self,
+        event: &'a WatchEvent,
+    ) -> impl Iterator<Item = &'a WebhookConfig> {
+        self.webhooks.iter().filter(move |webhook| {
+            let mut routing = self
+                .rules
+                .iter()
+                .filter(|rule| rule.webhooks.contains(&webhook.name))
+                .peekable();
+            routing.peek().is_none() || routing.any(|rule| rule.matches_event(event))
+        })
+    }
+
+    /// Send an event to every sink and the webhooks it is routed to.
+    /// Failing sinks don't stop the others, their errors are logged and returned.
+    pub async fn notify(&self, event: &WatchEvent) -> Vec<String> {
+        self.send(event, self.webhooks_for(event)).await
+    }
+
+    /// Send an event to every sink and every webhook, ignoring the routing of the rules
+    pub async fn notify_all(&self, event: &WatchEvent) -> Vec<String> {
+        self.send(event, self.webhooks.iter()).await
+    }
+
+    async fn send<'a>(
+        &self,
+        event: &WatchEvent,
+        webhooks: impl Iterator<Item = &'a WebhookConfig>,
+    ) -> Vec<String> {
+        let mut failures = Vec::new();
+        for sink in &self.sinks {
+            let result = match sink {
+                NotifySink::Desktop => notify_desktop(event).await,
+                NotifySink::Bell => ring_bell(),
+                NotifySink::Command => match &self.command {
+                    Some(command) => run_hook(command, event).await,
+                    None => Err(anyhow::anyhow!("watch.command is not set")),
+                },
+            };
+            if let Err(e) = result {
+                error!("Failed to send {sink:?} notification: {e}");
+                failures.push(format!("{sink:?} notification failed: {e}"));
+            }
+        }
+        for webhook in webhooks {
+            if let Err(e) = webhook.send(&self.http, event).await {
+                error!("{e:#}");
+                failures.push(format!("{e:#}"));
+            }
+        }
+        failures
+    }
+}
+
+async fn notify_desktop(event: &WatchEvent) -> Result<()> {
+    let urgency = match event.kind {
+        WatchEventKind::Failed => "critical",
+        WatchEventKind::UpForRetry | WatchEventKind::SlaExceeded => "normal",
+    };
+    let status = Command::new("notify-send")
+        .args(["--app-name=flowrs", "--urgency", urgency, event.title()])
+        .arg(event.summary())
+        .status()
+        .await?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("notify-send exited with {status}"));
+    }
+    Ok(())
+}
+
+fn ring_bell() -> Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(b"\x07")?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Run the hook through the shell, passing the event in `FLOWRS_*` environment variables
+async fn run_hook(command: &str, event: &WatchEvent) -> Result<()> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("FLOWRS_EVENT", event.kind.to_string())
+        .env("FLOWRS_ENVIRONMENT", &event.environment)
+        .env("FLOWRS_DAG_ID", &event.dag_id)
+        .env("FLOWRS_DAG_RUN_ID", &event.dag_run_id)
+        .env(
+            "FLOWRS_TASK_ID",
+            event.task_id.as_deref().unwrap_or_default(),
+        )
+        .env("FLOWRS_MESSAGE", event.summary())
+        .stdin(std::process::Stdio::null())
+        .output()
+        .await?;
+    if !output.status.success() {
+        warn!(
+            "Watch hook stderr: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return Err(anyhow::anyhow!("'{command}' exited with {}", output.status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airflow::watch::webhook::WebhookFormat;
+
+    #[tokio::test]
+    async fn test_hook_receives_event_in_environment() {
+        let file = std::env::temp_dir().join(format!("flowrs-watch-hook-{}", std::process::id()));
+        let command = format!(
+            "echo \"$FLOWRS_EVENT $FLOWRS_ENVIRONMENT $FLOWRS_DAG_ID $FLOWRS_TASK_ID\" > {}",
+            file.display()
+        );
+        let event = WatchEvent {
+            kind: WatchEventKind::UpForRetry,
+            environment: "prod".to_string(),
+            dag_id: "etl".to_string(),
+            dag_run_id: "manual__1".to_string(),
+            task_id: Some("load".to_string()),
+            try_number: Some(2),
+            running_for: None,
+            tags: vec![],
+        };
+
+        run_hook(&command, &event).await.unwrap();
+        let written = std::fs::read_to_string(&file).unwrap();
+        assert_eq!(written.trim(), "up_for_retry prod etl load");
+        std::fs::remove_file(file).unwrap();
+
+        assert!(run_hook("exit 3", &event).await.is_err());
+    }
+
+    #[test]
+    fn test_webhooks_are_routed_by_rule() {
+        let webhook = |name: &str| WebhookConfig {
+            name: name.to_string(),
+            url: "http://localhost/hook".to_string(),
+            format: WebhookFormat::Json,
+            template: None,
+        };
+        let config = WatchConfig {
+            rules: vec![
+                WatchRule {
+                    environments: vec!["prod-*".to_string()],
+                    tags: vec!["critical".to_string()],
+                    events: vec![WatchEventKind::Failed],
+                    webhooks: vec!["pager".to_string()],
+                    ..Default::default()
+                },
+                WatchRule::default(),
+            ],
+            webhooks: vec![webhook("pager"), webhook("everything")],
+            ..Default::default()
+        };
+        config.validate().unwrap();
+        let notifier = Notifier::new(&config).unwrap();
+        assert!(notifier.sinks.is_empty());
+
+        let mut event = WatchEvent {
+            kind: WatchEventKind::Failed,
+            environment: "prod-eu".to_string(),
+            dag_id: "etl".to_string(),
+            dag_run_id: "scheduled__1".to_string(),
+            task_id: None,
+            try_number: None,
+            running_for: None,
+            tags: vec!["critical".to_string()],
+        };
+        let routed = |event: &WatchEvent| -> Vec<String> {
+            notifier
+                .webhooks_for(event)
+                .map(|webhook| webhook.name.clone())
+                .collect()
+        };
+        assert_eq!(routed(&event), vec!["pager", "everything"]);
+
+        event.kind = WatchEventKind::UpForRetry;
+        assert_eq!(routed(&event), vec!["everything"]);
+
+        event.kind = WatchEventKind::Failed;
+        event.environment = "dev".to_string();
+        assert_eq!(routed(&event), vec!["everything"]);
+    }
+}
@@ -0,0 +1,208 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{WatchEvent, WatchEventKind};
+use crate::airflow::config::expand_env_vars;
+
+/// Time allowed for a webhook to accept a notification
+pub const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A webhook that receives watch notifications as JSON.
+///
+/// ```toml
+/// [[watch.webhooks]]
+/// name = "ops"
+/// url = "$SLACK_WEBHOOK_URL"
+/// format = "slack"
+/// template = ":red_circle: {summary}"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookConfig {
+    /// Name used to route rules to this webhook
+    pub name: String,
+    /// URL the payload is posted to, environment variables like `$SLACK_WEBHOOK_URL` are expanded
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Message text with `{placeholders}` (default: `{summary}`), see [`render_template`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// Slack incoming webhook, also understood by Mattermost and Rocket.Chat
+    Slack,
+    /// Every field of the event as a flat JSON object
+    #[default]
+    Json,
+    /// Microsoft Teams connector card
+    Teams,
+}
+
+impl WebhookConfig {
+    /// The JSON body posted for an event
+    pub fn payload(&self, event: &WatchEvent) -> Value {
+        let text = render_template(self.template.as_deref().unwrap_or("{summary}"), event);
+        match self.format {
+            WebhookFormat::Slack => json!({
+                "text": format!("*{}*\n{text}", event.title()),
+            }),
+            WebhookFormat::Json => json!({
+                "event": event.kind.to_string(),
+                "title": event.title(),
+                "message": text,
+                "environment": event.environment,
+                "dag_id": event.dag_id,
+                "dag_run_id": event.dag_run_id,
+                "task_id": event.task_id,
+                "try_number": event.try_number,
+                "running_for_secs": event.running_for.map(|d| d.as_secs()),
+                "tags": event.tags,
+            }),
+            WebhookFormat::Teams => {
+                let mut facts = vec![
+                    json!({"name": "Environment", "value": event.environment}),
+                    json!({"name": "DAG", "value": event.dag_id}),
+                    json!({"name": "Run", "value": event.dag_run_id}),
+                ];
+                if let Some(task_id) = &event.task_id {
+                    facts.push(json!({"name": "Task", "value": task_id}));
+                }
+                json!({
+                    "@type": "MessageCard",
+                    "@context": "https://schema.org/extensions",
+                    "themeColor": match event.kind {
+                        WatchEventKind::Failed => "D70000",
+                        WatchEventKind::UpForRetry | WatchEventKind::SlaExceeded => "FFA500",
+                    },
+                    "summary": event.title(),
+                    "title": event.title(),
+                    "text": text,
+                    "sections": [{ "facts": facts }],
+                })
+            }
+        }
+    }
+
+    pub async fn send(&self, client: &reqwest::Client, event: &WatchEvent) -> Result<()> {
+        let url = expand_env_vars(&self.url)?;
+        client
+            .post(&url)
+            .json(&self.payload(event))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            // The URL of Slack and Teams webhooks is the secret, keep it out of errors and logs
+            .map_err(reqwest::Error::without_url)
+            .with_context(|| format!("Webhook '{}' failed", self.name))?;
+        Ok(())
+    }
+}
+
+/// Fill in the placeholders `{event}`, `{title}`, `{summary}`, `{environment}`, `{dag_id}`,
+/// `{dag_run_id}`, `{task_id}`, `{try_number}` and `{tags}`
+pub fn render_template(template: &str, event: &WatchEvent) -> String {
+    let values = [
+        ("{event}", event.kind.to_string()),
+        ("{title}", event.title().to_string()),
+        ("{summary}", event.summary()),
+        ("{environment}", event.environment.clone()),
+        ("{dag_id}", event.dag_id.clone()),
+        ("{dag_run_id}", event.dag_run_id.clone()),
+        ("{task_id}", event.task_id.clone().unwrap_or_default()),
+        (
+            "{try_number}",
+            event.try_number.map(|n| n.to_string()).unwrap_or_default(),
+        ),
+        ("{tags}", event.tags.join(", ")),
+    ];
+    values
+        .iter()
+        .fold(template.to_string(), |text, (placeholder, value)| {
+            text.replace(placeholder, value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> WatchEvent {
+        WatchEvent {
+            kind: WatchEventKind::Failed,
+            environment: "prod".to_string(),
+            dag_id: "etl".to_string(),
+            dag_run_id: "scheduled__1".to_string(),
+            task_id: None,
+            try_number: None,
+            running_for: None,
+            tags: vec!["critical".to_string()],
+        }
+    }
+
+    fn webhook(url: String, format: WebhookFormat) -> WebhookConfig {
+        WebhookConfig {
+            name: "ops".to_string(),
+            url,
+            format,
+            template: Some("{environment}/{dag_id} [{tags}] {event}".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_payload_formats() {
+        let event = event();
+        let slack = webhook(String::new(), WebhookFormat::Slack).payload(&event);
+        assert_eq!(
+            slack,
+            json!({"text": "*DAG run failed*\nprod/etl [critical] failed"})
+        );
+
+        let generic = webhook(String::new(), WebhookFormat::Json).payload(&event);
+        assert_eq!(generic["event"], "failed");
+        assert_eq!(generic["dag_run_id"], "scheduled__1");
+        assert_eq!(generic["task_id"], Value::Null);
+
+        let teams = webhook(String::new(), WebhookFormat::Teams).payload(&event);
+        assert_eq!(teams["@type"], "MessageCard");
+        assert_eq!(teams["themeColor"], "D70000");
+        assert_eq!(teams["sections"][0]["facts"].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_payload() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "event": "failed",
+                "environment": "prod",
+                "message": "prod/etl [critical] failed",
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+        let client = reqwest::Client::new();
+        let hook = webhook(format!("{}/hook", server.url()), WebhookFormat::Json);
+        hook.send(&client, &event()).await.unwrap();
+        mock.assert_async().await;
+
+        let broken = webhook(
+            format!("{}/secret-token", server.url()),
+            WebhookFormat::Json,
+        );
+        server
+            .mock("POST", "/secret-token")
+            .with_status(500)
+            .create_async()
+            .await;
+        let error = broken.send(&client, &event()).await.unwrap_err();
+        assert!(!format!("{error:#}").contains("secret-token"));
+    }
+}
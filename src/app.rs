@@ -4,22 +4,30 @@ use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use events::{custom::FlowrsEvent, generator::EventGenerator};
 use log::debug;
-use model::Model;
 use ratatui::{prelude::Backend, Terminal};
+use session::Session;
 use state::{App, Panel};
-use worker::{Worker, WorkerMessage};
+use model::popup::audit::RecentActionsPopup;
+use model::popup::error::ErrorPopup;
+use worker::{NoteTarget, Worker, WorkerMessage};
 
-use crate::{airflow::client::create_client, ui::draw_ui};
+use crate::{airflow::client::create_client, airflow::watch::watch, ui::draw_ui};
 
+pub mod audit;
 pub mod environment_state;
+pub mod export;
 pub mod events;
 pub mod model;
+pub mod session;
 pub mod state;
 pub mod worker;
 
 // Wait for in-flight event reads to complete before opening editor
 const EVENT_DRAIN_DELAY_MS: u64 = 100;
 
+// Number of watcher events kept for display
+const MAX_WATCH_EVENTS: usize = 100;
+
 pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: Arc<Mutex<App>>) -> Result<()> {
     let mut events = EventGenerator::new(200);
     let ui_app = app.clone();
@@ -60,9 +68,58 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: Arc<Mutex<App>
         }
     }
 
+    // Run the failure watcher in the background when enabled for the TUI
+    {
+        let app_state = app.lock().unwrap();
+        if let Some(watch_config) = app_state.config.watch.clone().filter(|watch| watch.tui) {
+            let environments: Vec<_> = app_state
+                .environment_state
+                .environments
+                .iter()
+                .map(|(name, env)| (name.clone(), env.client.clone()))
+                .collect();
+            let watch_app = app.clone();
+            log::info!("Spawning watcher for {} environment(s)", environments.len());
+            tokio::spawn(async move {
+                let result = watch(&watch_config, &environments, |poll| {
+                    for error in &poll.errors {
+                        log::error!("Watcher: {error}");
+                    }
+                    let mut app = watch_app.lock().unwrap();
+                    app.watch_errors.clone_from(&poll.errors);
+                    app.watch_events.extend(poll.events.iter().cloned());
+                    let excess = app.watch_events.len().saturating_sub(MAX_WATCH_EVENTS);
+                    app.watch_events.drain(..excess);
+                })
+                .await;
+                if let Err(e) = result {
+                    log::error!("Watcher stopped: {e}");
+                }
+            });
+        }
+    }
+
+    // Pick up where the last session ended when enabled
+    let restore = {
+        let mut app = app.lock().unwrap();
+        let session = app
+            .config
+            .restore_session
+            .then(|| Session::load(&Session::default_path()))
+            .flatten();
+        if let Some(session) = &session {
+            log::info!("Restoring session in {:?}", session.environment);
+            session.apply_view_state(&mut app);
+        }
+        session
+    };
+    if let Some(session) = restore {
+        tx_worker.send(WorkerMessage::RestoreSession(session)).await?;
+    }
+
     log::info!("Spawning worker");
     let tx_worker_for_worker = tx_worker.clone();
-    tokio::spawn(async move { Worker::new(worker_app, rx_worker, tx_worker_for_worker).run().await });
+    tokio::spawn(async move { Worker::new(worker_app, tx_worker_for_worker).run(rx_worker).await });
 
     loop {
         terminal.draw(|f| {
@@ -71,20 +128,20 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: Arc<Mutex<App>
         })?;
 
         if let Some(event) = events.next().await {
-            // First handle panel specific events, and send messages to the event channel
-            let (fall_through_event, messages) = {
-                let mut app = app.lock().unwrap();
-                match app.active_panel {
-                    Panel::Config => app.configs.update(&event),
-                    Panel::Dag => app.dags.update(&event),
-                    Panel::DAGRun => app.dagruns.update(&event),
-                    Panel::TaskInstance => app.task_instances.update(&event),
-                    Panel::Logs => app.logs.update(&event),
-                    Panel::VariableDetail => app.variable_detail.update(&event),
-                    Panel::ConnectionDetail => app.connection_detail.update(&event),
-                    Panel::ImportErrorDetail => app.import_error_detail.update(&event),
-                }
+            // Popups shown on top of every panel take the keys while open
+            let overlay_messages = match &event {
+                FlowrsEvent::Key(key) => app.lock().unwrap().overlay_key(key),
+                _ => None,
             };
+            if let Some(messages) = overlay_messages {
+                for message in messages {
+                    tx_worker.send(message).await?;
+                }
+                continue;
+            }
+
+            // First handle panel specific events, and send messages to the event channel
+            let (fall_through_event, messages) = app.lock().unwrap().update_active_panel(&event);
 
             // Process messages and sync cached data immediately
             let mut additional_messages = Vec::new();
@@ -167,11 +224,48 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: Arc<Mutex<App>
                             // If log is not cached, worker will fetch it and sync again
                             // If log is cached, this sync ensures the UI shows the correct log immediately
                         }
+                        WorkerMessage::UpdateOverview => {
+                            // Show every configured environment right away, values fill in as they arrive
+                            let names = app
+                                .config
+                                .servers
+                                .iter()
+                                .flatten()
+                                .map(|server| server.name.clone())
+                                .collect();
+                            app.overview.reset(names);
+                            app.active_panel = Panel::Overview;
+                        }
+                        WorkerMessage::SearchDags => {
+                            let names = app
+                                .config
+                                .servers
+                                .iter()
+                                .flatten()
+                                .map(|server| server.name.clone())
+                                .collect();
+                            app.search.reset(names);
+                            app.active_panel = Panel::Search;
+                        }
+                        WorkerMessage::CompareDag {
+                            dag_id,
+                            environments,
+                        } => {
+                            app.compare.reset(dag_id.clone(), environments.clone());
+                            app.active_panel = Panel::Compare;
+                        }
+                        WorkerMessage::UpdateEventLogs(query) => {
+                            let from = app.active_panel.clone();
+                            app.event_logs.open(query.clone(), &from);
+                            app.active_panel = Panel::EventLog;
+                        }
                         _ => {}
                     }
                 }
             }
 
+            let messages = app.lock().unwrap().protect(messages);
+
             // Now send messages to worker for async processing
             // Special case: OpenInEditor needs terminal access, handle it here
             for message in messages {
@@ -207,6 +301,17 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: Arc<Mutex<App>
                             // Discard buffered events
                         }
                     }
+                    WorkerMessage::EditNote { target, note } => {
+                        if let Some(message) = edit_note(terminal, &mut events, &app, target, note).await {
+                            // Note changes are subject to the environment protection like any other change
+                            let messages = app.lock().unwrap().protect(vec![message]);
+                            for message in messages {
+                                if let Err(e) = tx_worker.send(message).await {
+                                    log::error!("Failed to send message to worker: {e}");
+                                }
+                            }
+                        }
+                    }
                     _ => {
                         // All other messages go to worker
                         if let Err(e) = tx_worker.send(message).await {
@@ -245,20 +350,27 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: Arc<Mutex<App>
             if let Some(FlowrsEvent::Tick) = fall_through_event {
                 app.ticks += 1;
                 app.throbber_state.calc_next();
+                app.update_pending_login();
             }
             if let FlowrsEvent::Key(key) = event {
                 // Handle exit key events
                 if key.modifiers == KeyModifiers::CONTROL {
                     if let KeyCode::Char('c') = key.code {
+                        save_session(&app);
                         return Ok(());
                     }
                 }
                 // Handle other key events
                 match key.code {
                     KeyCode::Char('q') => {
+                        save_session(&app);
                         app.config.write_to_file()?;
                         return Ok(());
                     }
+                    KeyCode::Char('A') => {
+                        let popup = RecentActionsPopup::load(&app.config.timezone_offset);
+                        app.recent_actions = Some(popup);
+                    }
                     KeyCode::Enter | KeyCode::Right => {
                         app.next_panel();
                         app.sync_panel_data();
@@ -273,3 +385,53 @@ pub async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: Arc<Mutex<App>
         }
     }
 }
+
+/// Let the user edit a note in the external editor, which needs the terminal like `OpenInEditor`.
+/// Returns the message saving the note if it was changed.
+async fn edit_note<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut EventGenerator,
+    app: &Arc<Mutex<App>>,
+    target: NoteTarget,
+    note: Option<String>,
+) -> Option<WorkerMessage> {
+    events.pause();
+    tokio::time::sleep(tokio::time::Duration::from_millis(EVENT_DRAIN_DELAY_MS)).await;
+
+    let filepath = std::env::temp_dir().join(target.file_name());
+    let original = note.unwrap_or_default();
+    let edited = crate::editor::edit_text_with_suspend(terminal, &filepath, &original);
+
+    events.resume();
+    while events.rx_event.try_recv().is_ok() {
+        // Discard events meant for the editor
+    }
+
+    match edited {
+        Ok(note) if note != original.trim_end() => Some(WorkerMessage::SetNote { target, note }),
+        Ok(_) => {
+            debug!("Note unchanged, not saving");
+            None
+        }
+        Err(e) => {
+            log::error!("Failed to edit note: {e}");
+            let mut app = app.lock().unwrap();
+            let error = ErrorPopup::from_strings(vec!["Failed to open editor:".into(), e.to_string()]);
+            match target {
+                NoteTarget::DagRun { .. } => app.dagruns.error_popup = Some(error),
+                NoteTarget::TaskInstance { .. } => app.task_instances.error_popup = Some(error),
+            }
+            None
+        }
+    }
+}
+
+/// Save the session for the next startup when `restore_session` is enabled
+fn save_session(app: &App) {
+    if !app.config.restore_session {
+        return;
+    }
+    if let Err(e) = Session::capture(app).save(&Session::default_path()) {
+        log::warn!("Failed to save session: {e}");
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use super::model::popup::taskinstances::mark::MarkRequest;
+use super::worker::{NoteTarget, WorkerMessage};
+use crate::airflow::model::common::ClearTaskInstances;
+
+/// A change made to an Airflow server through flowrs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditAction {
+    pub action: String,
+    pub dag_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dag_run_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, String>,
+}
+
+impl AuditAction {
+    fn new(action: &str, dag_id: &str) -> Self {
+        Self {
+            action: action.to_string(),
+            dag_id: dag_id.to_string(),
+            dag_run_id: None,
+            task_id: None,
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    fn dag_run(mut self, dag_run_id: &str) -> Self {
+        self.dag_run_id = Some(dag_run_id.to_string());
+        self
+    }
+
+    fn task(mut self, task_id: &str) -> Self {
+        self.task_id = Some(task_id.to_string());
+        self
+    }
+
+    fn parameter(mut self, name: &str, value: &impl ToString) -> Self {
+        self.parameters.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// The action a message performs, `None` for messages that don't change anything
+    pub fn for_message(message: &WorkerMessage) -> Option<Self> {
+        let action = match message {
+            WorkerMessage::ToggleDag { dag_id, is_paused } => Self::new(
+                if *is_paused {
+                    "unpause_dag"
+                } else {
+                    "pause_dag"
+                },
+                dag_id,
+            ),
+            WorkerMessage::ToggleFavorite { dag_id, favorite } => {
+                Self::new(if *favorite { "pin_dag" } else { "unpin_dag" }, dag_id)
+            }
+            WorkerMessage::ClearDagRun { dag_run_id, dag_id } => {
+                Self::new("clear_dag_run", dag_id).dag_run(dag_run_id)
+            }
+            WorkerMessage::MarkDagRun {
+                dag_run_id,
+                dag_id,
+                status,
+            } => Self::new("mark_dag_run", dag_id)
+                .dag_run(dag_run_id)
+                .parameter("state", status),
+            WorkerMessage::ClearTaskInstance {
+                task_id,
+                dag_id,
+                dag_run_id,
+            } => Self::new("clear_task_instance", dag_id)
+                .dag_run(dag_run_id)
+                .task(task_id)
+                .parameter("include_downstream", &true),
+            WorkerMessage::TriggerDagRun { dag_id } => Self::new("trigger_dag_run", dag_id),
+            WorkerMessage::SetNote { target, note } => match target {
+                NoteTarget::DagRun { dag_id, dag_run_id } => {
+                    Self::new("set_dag_run_note", dag_id).dag_run(dag_run_id)
+                }
+                NoteTarget::TaskInstance {
+                    dag_id,
+                    dag_run_id,
+                    task_id,
+                } => Self::new("set_task_instance_note", dag_id)
+                    .dag_run(dag_run_id)
+                    .task(task_id),
+            }
+            .parameter("note", note),
+            _ => return None,
+        };
+        Some(action)
+    }
+
+    /// Clearing the task instances of one DAG selected by a repair
+    pub fn for_repair(dag_id: &str, clear: &ClearTaskInstances) -> Self {
+        let time = |t: &OffsetDateTime| t.format(&Rfc3339).unwrap_or_default();
+        let mut action = Self::new("clear_task_instances", dag_id)
+            .parameter("only_failed", &clear.only_failed);
+        if let Some(start_date) = &clear.start_date {
+            action = action.parameter("start_date", &time(start_date));
+        }
+        if let Some(end_date) = &clear.end_date {
+            action = action.parameter("end_date", &time(end_date));
+        }
+        action
+    }
+
+    /// Marking one task instance, with the related task instances included by the request
+    pub fn for_mark(request: &MarkRequest, task_id: &str) -> Self {
+        let scope = [
+            ("include_upstream", request.scope.upstream),
+            ("include_downstream", request.scope.downstream),
+            ("include_past", request.scope.past),
+            ("include_future", request.scope.future),
+        ];
+        scope
+            .into_iter()
+            .filter(|(_, included)| *included)
+            .fold(
+                Self::new("mark_task_instance", &request.dag_id)
+                    .dag_run(&request.dag_run_id)
+                    .task(task_id)
+                    .parameter("state", &request.status),
+                |action, (name, included)| action.parameter(name, &included),
+            )
+    }
+
+    /// The actions a message performs, one per DAG for bulk actions and repairs and one per
+    /// task for marking task instances
+    pub fn all_for_message(message: &WorkerMessage) -> Vec<Self> {
+        match message {
+            WorkerMessage::BulkDagAction { action, dag_ids } => dag_ids
+                .iter()
+                .filter_map(|dag_id| Self::for_message(&action.message(dag_id)))
+                .collect(),
+            WorkerMessage::RepairTaskInstances(request) => request
+                .dag_ids
+                .iter()
+                .map(|dag_id| Self::for_repair(dag_id, &request.clear))
+                .collect(),
+            WorkerMessage::MarkTaskInstances(request) => request
+                .task_ids
+                .iter()
+                .map(|task_id| Self::for_mark(request, task_id))
+                .collect(),
+            _ => Self::for_message(message).into_iter().collect(),
+        }
+    }
+
+    /// The DAG, run and task the action was performed on, separated by slashes
+    pub fn target(&self) -> String {
+        [
+            Some(self.dag_id.as_str()),
+            self.dag_run_id.as_deref(),
+            self.task_id.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("/")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failed,
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    pub environment: String,
+    /// The local user running flowrs
+    pub user: String,
+    #[serde(flatten)]
+    pub action: AuditAction,
+    pub outcome: AuditOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(environment: &str, action: AuditAction, error: Option<String>) -> Self {
+        Self {
+            timestamp: OffsetDateTime::now_utc(),
+            environment: environment.to_string(),
+            user: current_user(),
+            action,
+            outcome: if error.is_some() {
+                AuditOutcome::Failed
+            } else {
+                AuditOutcome::Success
+            },
+            error,
+        }
+    }
+}
+
+fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Append-only JSONL log of the actions performed through flowrs,
+/// kept in `~/.local/state/flowrs/audit.jsonl`
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::with_path(crate::get_state_dir().join("audit.jsonl"))
+    }
+}
+
+impl AuditLog {
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn append(&self, entry: &AuditEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// The last `limit` entries, most recent first. Unreadable lines are skipped.
+    pub fn recent(&self, limit: usize) -> Result<Vec<AuditEntry>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        Ok(content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(limit)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::model::popup::dagruns::mark::MarkState;
+
+    #[test]
+    fn test_audit_log_records_actions() {
+        let path = std::env::temp_dir().join(format!("flowrs-audit-{}.jsonl", std::process::id()));
+        let log = AuditLog::with_path(path.clone());
+        assert!(log.recent(10).unwrap().is_empty());
+
+        let mark = WorkerMessage::MarkDagRun {
+            dag_run_id: "manual__1".to_string(),
+            dag_id: "etl".to_string(),
+            status: MarkState::Failed,
+        };
+        let action = AuditAction::for_message(&mark).unwrap();
+        assert_eq!(action.target(), "etl/manual__1");
+        log.append(&AuditEntry::new("prod", action, None)).unwrap();
+
+        let clear = WorkerMessage::ClearDagRun {
+            dag_run_id: "manual__2".to_string(),
+            dag_id: "etl".to_string(),
+        };
+        let action = AuditAction::for_message(&clear).unwrap();
+        log.append(&AuditEntry::new(
+            "prod",
+            action,
+            Some("403 Forbidden".to_string()),
+        ))
+        .unwrap();
+        assert!(AuditAction::for_message(&WorkerMessage::UpdateDags).is_none());
+
+        let recent = log.recent(10).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].action.action, "clear_dag_run");
+        assert_eq!(recent[0].outcome, AuditOutcome::Failed);
+        assert_eq!(recent[0].error.as_deref(), Some("403 Forbidden"));
+        assert_eq!(recent[1].action.parameters["state"], "failed");
+        assert_eq!(recent[1].outcome, AuditOutcome::Success);
+    }
+}
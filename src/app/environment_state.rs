@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::path::{Path, PathBuf};
 use std::fs;
@@ -145,6 +145,7 @@ pub struct EnvironmentData {
     pub dag_details: HashMap<DagId, Dag>,
     pub task_order: HashMap<DagId, Vec<String>>,
     pub task_dependencies: HashMap<DagId, HashMap<String, Vec<String>>>,
+    pub favorite_dags: HashSet<DagId>,
 }
 
 impl EnvironmentData {
@@ -155,6 +156,7 @@ impl EnvironmentData {
             dag_details: HashMap::new(),
             task_order: HashMap::new(),
             task_dependencies: HashMap::new(),
+            favorite_dags: HashSet::new(),
         }
     }
 
@@ -397,6 +399,13 @@ impl EnvironmentStateContainer {
             .unwrap_or_default()
     }
 
+    /// Get the pinned DAGs of the active environment
+    pub fn get_active_favorite_dags(&self) -> HashSet<DagId> {
+        self.get_active_environment()
+            .map(|env| env.favorite_dags.clone())
+            .unwrap_or_default()
+    }
+
     /// Get all DAG runs for a specific DAG in the active environment
     pub fn get_active_dag_runs(&self, dag_id: &str) -> Vec<DagRun> {
         self.get_active_environment()
@@ -520,7 +529,7 @@ fn get_cache_subdir(subdir: &str) -> Result<PathBuf> {
 }
 
 /// Sanitize a string for use in filenames by replacing problematic characters
-fn sanitize_filename(s: &str) -> String {
+pub(crate) fn sanitize_filename(s: &str) -> String {
     s.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_")
 }
 
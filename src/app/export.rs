@@ -0,0 +1,68 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::app::environment_state::sanitize_filename;
+use crate::app::model::sortable_table::{CustomSort, SortableTable};
+
+/// File format for table exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One row per item, using the visible table columns
+    Csv,
+    /// The full serde model of every item
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Get the directory exports are written to (~/.local/state/flowrs/exports)
+/// Creates the directory if it doesn't exist
+pub fn get_exports_dir() -> Result<PathBuf> {
+    let dir = crate::get_state_dir().join("exports");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Write the currently filtered and sorted rows of a table to disk
+/// Returns the path of the written file, e.g. `exports/dagruns_my_dag_20250101120000.csv`
+pub fn export_table<T>(table: &SortableTable<T>, name: &str, format: ExportFormat) -> Result<PathBuf>
+where
+    T: CustomSort + Serialize,
+{
+    let content = match format {
+        ExportFormat::Csv => table.to_csv(),
+        ExportFormat::Json => serde_json::to_string_pretty(&table.items)?,
+    };
+
+    let filename = format!(
+        "{}_{}.{}",
+        sanitize_filename(name),
+        chrono::Local::now().format("%Y%m%d%H%M%S"),
+        format.extension()
+    );
+    let filepath = get_exports_dir()?.join(filename);
+
+    // Exports can contain variable values and connection details, keep them private
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&filepath)?.write_all(content.as_bytes())?;
+
+    log::info!("Exported {} rows to {}", table.items.len(), filepath.display());
+    Ok(filepath)
+}
@@ -3,13 +3,18 @@ use ratatui::widgets::{ScrollbarState, TableState};
 
 use super::{events::custom::FlowrsEvent, worker::WorkerMessage};
 
+pub mod compare;
 pub mod config;
 pub mod dagruns;
 pub mod dags;
 pub mod detail;
+pub mod eventlogs;
 pub mod filter;
 pub mod logs;
+pub mod note;
+pub mod overview;
 pub mod popup;
+pub mod search;
 pub mod sortable_table;
 pub mod taskinstances;
 
@@ -0,0 +1,850 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, BorderType, Borders, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState,
+    StatefulWidget, Table, Widget, Wrap,
+};
+
+use crate::airflow::model::common::{Dag, DagRun};
+use crate::app::events::custom::FlowrsEvent;
+use crate::app::worker::WorkerMessage;
+use crate::ui::common::convert_to_timezone;
+use crate::ui::constants::{AirflowStateColor, ALTERNATING_ROW_COLOR, DEFAULT_STYLE, HEADER_STYLE};
+use crate::ui::get_time_format;
+
+use super::dagruns::code_to_lines;
+use super::popup::commands_help::CommandPopUp;
+use super::popup::compare::commands::create_compare_command_popup;
+use super::{handle_command_popup_events, handle_vertical_scroll_keys, Model};
+
+/// Lines of unchanged code shown around every change in the diff
+const DIFF_CONTEXT: usize = 3;
+
+/// Above this many compared line pairs the diff gives up on finding common lines
+const MAX_DIFF_CELLS: usize = 25_000_000;
+
+/// The state of one DAG in a single environment
+#[derive(Debug, Clone, Default)]
+pub struct EnvironmentDag {
+    pub name: String,
+    pub loading: bool,
+    pub dag: Option<Dag>,
+    pub latest_run: Option<DagRun>,
+    pub code: Option<String>,
+    pub errors: Vec<String>,
+}
+
+impl EnvironmentDag {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            loading: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Source comparisons still missing after an environment arrived. The worker computes them
+/// without holding the app lock, as diffing and highlighting a large DAG file takes a while.
+pub struct PendingComparison {
+    dag_id: Option<String>,
+    base: String,
+    /// Environments whose source has not been counted against the base yet
+    uncounted: Vec<(String, String)>,
+    /// Diff target with the base and target names and the target source
+    diff: Option<(usize, String, String, String)>,
+}
+
+impl PendingComparison {
+    pub fn compare(self) -> Comparison {
+        Comparison {
+            dag_id: self.dag_id,
+            changes: self
+                .uncounted
+                .iter()
+                .map(|(name, code)| (name.clone(), count_changes(&self.base, code)))
+                .collect(),
+            diff: self.diff.map(|(target, old_name, new_name, code)| {
+                (target, render_diff(&self.base, &code, &old_name, &new_name))
+            }),
+        }
+    }
+}
+
+/// Result of a `PendingComparison`
+pub struct Comparison {
+    dag_id: Option<String>,
+    changes: Vec<(String, (usize, usize))>,
+    diff: Option<(usize, Vec<Line<'static>>)>,
+}
+
+pub struct CompareModel {
+    pub dag_id: Option<String>,
+    /// The first environment is the base the others are compared against
+    pub environments: Vec<EnvironmentDag>,
+    /// Environment whose source is diffed against the base
+    pub diff_target: usize,
+    pub timezone_offset: String,
+    pub commands: Option<CommandPopUp<'static>>,
+    diff: Option<Vec<Line<'static>>>,
+    /// Added and removed lines of each environment's source compared to the base, by name
+    code_changes: HashMap<String, (usize, usize)>,
+    vertical_scroll: usize,
+    vertical_scroll_state: ScrollbarState,
+}
+
+impl CompareModel {
+    pub fn new() -> Self {
+        CompareModel {
+            dag_id: None,
+            environments: vec![],
+            diff_target: 1,
+            timezone_offset: "+00:00".to_string(),
+            commands: None,
+            diff: None,
+            code_changes: HashMap::new(),
+            vertical_scroll: 0,
+            vertical_scroll_state: ScrollbarState::default(),
+        }
+    }
+
+    pub fn reset(&mut self, dag_id: String, environments: Vec<String>) {
+        self.dag_id = Some(dag_id);
+        self.environments = environments.into_iter().map(EnvironmentDag::new).collect();
+        self.diff_target = 1;
+        self.code_changes.clear();
+        self.set_diff(None);
+    }
+
+    /// Store a fetched environment, its comparison with the base is left to `pending_comparison`
+    pub fn update_environment(&mut self, environment: EnvironmentDag) {
+        let Some(idx) = self
+            .environments
+            .iter()
+            .position(|e| e.name == environment.name)
+        else {
+            return;
+        };
+        if idx == 0 {
+            self.code_changes.clear();
+        } else {
+            self.code_changes.remove(&environment.name);
+        }
+        if idx == 0 || idx == self.diff_target {
+            self.set_diff(None);
+        }
+        self.environments[idx] = environment;
+    }
+
+    /// Comparisons with the base source that have not been computed yet
+    pub fn pending_comparison(&self) -> Option<PendingComparison> {
+        let base = self.base()?.code.clone()?;
+        let uncounted: Vec<(String, String)> = self
+            .environments
+            .iter()
+            .skip(1)
+            .filter(|e| !self.code_changes.contains_key(&e.name))
+            .filter_map(|e| Some((e.name.clone(), e.code.clone()?)))
+            .collect();
+        let diff = match (&self.diff, self.target()) {
+            (None, Some(target)) => target.code.clone().map(|code| {
+                let (old_name, new_name) = self.diff_names(target);
+                (self.diff_target, old_name, new_name, code)
+            }),
+            _ => None,
+        };
+        if uncounted.is_empty() && diff.is_none() {
+            return None;
+        }
+        Some(PendingComparison {
+            dag_id: self.dag_id.clone(),
+            base,
+            uncounted,
+            diff,
+        })
+    }
+
+    pub fn apply_comparison(&mut self, comparison: Comparison) {
+        if comparison.dag_id != self.dag_id {
+            return;
+        }
+        self.code_changes.extend(comparison.changes);
+        if let Some((target, diff)) = comparison.diff {
+            if target == self.diff_target && self.diff.is_none() {
+                self.set_diff(Some(diff));
+            }
+        }
+    }
+
+    fn base(&self) -> Option<&EnvironmentDag> {
+        self.environments.first()
+    }
+
+    fn target(&self) -> Option<&EnvironmentDag> {
+        self.environments.get(self.diff_target)
+    }
+
+    fn cycle_target(&mut self, forward: bool) {
+        let others = self.environments.len().saturating_sub(1);
+        if others < 2 {
+            return;
+        }
+        // Targets are 1..=others, the base is never diffed against itself
+        let current = self.diff_target - 1;
+        let next = if forward {
+            (current + 1) % others
+        } else {
+            (current + others - 1) % others
+        };
+        self.diff_target = next + 1;
+        self.refresh_diff();
+    }
+
+    /// Names of the base and target source in the diff header
+    fn diff_names(&self, target: &EnvironmentDag) -> (String, String) {
+        let dag_id = self.dag_id.as_deref().unwrap_or_default();
+        (
+            format!("{}/{dag_id}", self.base().map_or("", |base| &base.name)),
+            format!("{}/{dag_id}", target.name),
+        )
+    }
+
+    /// Render the diff of the current target only, the counts of the others are kept
+    fn refresh_diff(&mut self) {
+        let diff = match (self.base(), self.target()) {
+            (Some(base), Some(target)) => match (&base.code, &target.code) {
+                (Some(old), Some(new)) => {
+                    let (old_name, new_name) = self.diff_names(target);
+                    Some(render_diff(old, new, &old_name, &new_name))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        self.set_diff(diff);
+    }
+
+    fn set_diff(&mut self, diff: Option<Vec<Line<'static>>>) {
+        self.diff = diff;
+        let len = self.diff.as_ref().map_or(0, Vec::len);
+        self.vertical_scroll = self.vertical_scroll.min(len.saturating_sub(1));
+        self.vertical_scroll_state = ScrollbarState::default()
+            .content_length(len)
+            .position(self.vertical_scroll);
+    }
+
+    fn format_date(&self, date: Option<time::OffsetDateTime>) -> String {
+        date.map_or_else(
+            || "-".to_string(),
+            |date| {
+                convert_to_timezone(date, &self.timezone_offset)
+                    .format(get_time_format())
+                    .unwrap_or_else(|_| "Invalid date".to_string())
+            },
+        )
+    }
+
+    /// Value of one compared field in an environment, `None` while it is unknown
+    fn field(&self, environment: &EnvironmentDag, field: Field) -> Option<String> {
+        let dag = environment.dag.as_ref();
+        match field {
+            Field::Paused => {
+                dag.map(|dag| if dag.is_paused { "paused" } else { "active" }.to_string())
+            }
+            Field::Schedule => dag.map(schedule),
+            Field::Timetable => dag.map(|dag| {
+                dag.timetable_description
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string())
+            }),
+            Field::LastParsed => dag.map(|dag| self.format_date(dag.last_parsed_time)),
+            Field::LatestRun => dag.map(|_| {
+                environment.latest_run.as_ref().map_or_else(
+                    || "no runs".to_string(),
+                    |run| {
+                        format!(
+                            "{} ({})",
+                            run.state,
+                            self.format_date(run.logical_date.or(run.start_date))
+                        )
+                    },
+                )
+            }),
+            Field::Code => {
+                if self
+                    .base()
+                    .is_some_and(|base| std::ptr::eq(base, environment))
+                {
+                    return environment.code.as_ref().map(|_| "base".to_string());
+                }
+                let (added, removed) = *self.code_changes.get(&environment.name)?;
+                Some(if added + removed == 0 {
+                    "identical".to_string()
+                } else {
+                    format!("+{added} -{removed}")
+                })
+            }
+        }
+    }
+
+    fn cell(&self, environment: &EnvironmentDag, field: Field) -> Line<'static> {
+        let Some(value) = self.field(environment, field) else {
+            let text = if environment.loading {
+                "loading..."
+            } else {
+                "-"
+            };
+            return Line::from(Span::styled(text, Style::default().fg(Color::DarkGray)));
+        };
+        let style = match field {
+            Field::LatestRun => environment
+                .latest_run
+                .as_ref()
+                .map_or(DEFAULT_STYLE, |run| {
+                    DEFAULT_STYLE.fg(state_color(&run.state))
+                }),
+            Field::Code if value != "base" && value != "identical" => {
+                DEFAULT_STYLE.fg(Color::Yellow)
+            }
+            // Highlight values that differ from the base environment
+            Field::Paused | Field::Schedule | Field::Timetable
+                if self
+                    .base()
+                    .and_then(|base| self.field(base, field))
+                    .is_some_and(|base| base != value) =>
+            {
+                DEFAULT_STYLE.fg(Color::Yellow)
+            }
+            _ => DEFAULT_STYLE,
+        };
+        Line::from(Span::styled(value, style))
+    }
+}
+
+impl Default for CompareModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Paused,
+    Schedule,
+    Timetable,
+    LastParsed,
+    LatestRun,
+    Code,
+}
+
+const FIELDS: [(Field, &str); 6] = [
+    (Field::Paused, "Paused"),
+    (Field::Schedule, "Schedule"),
+    (Field::Timetable, "Timetable"),
+    (Field::LastParsed, "Last parsed"),
+    (Field::LatestRun, "Latest run"),
+    (Field::Code, "Source"),
+];
+
+fn schedule(dag: &Dag) -> String {
+    match &dag.schedule_interval {
+        None | Some(serde_json::Value::Null) => "None".to_string(),
+        Some(serde_json::Value::String(schedule)) => schedule.clone(),
+        // The v1 API wraps cron expressions as {"__type": "CronExpression", "value": "..."}
+        Some(serde_json::Value::Object(schedule)) => match schedule.get("value") {
+            Some(serde_json::Value::String(value)) => value.clone(),
+            _ => serde_json::Value::Object(schedule.clone()).to_string(),
+        },
+        Some(other) => other.to_string(),
+    }
+}
+
+fn state_color(state: &str) -> Color {
+    match state {
+        "success" => AirflowStateColor::Success,
+        "failed" => AirflowStateColor::Failed,
+        "running" => AirflowStateColor::Running,
+        "queued" => AirflowStateColor::Queued,
+        _ => AirflowStateColor::None,
+    }
+    .into()
+}
+
+/// One step of a line based diff, holding the line indices in the old and/or new text
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffOp {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Line diff based on the longest common subsequence.
+/// The common prefix and suffix are skipped first, so the quadratic part only covers the changed region.
+fn diff_ops(old: &[&str], new: &[&str]) -> Vec<DiffOp> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<DiffOp> = (0..prefix).map(|i| DiffOp::Equal(i, i)).collect();
+    let (n, m) = (old_mid.len(), new_mid.len());
+    if n.saturating_mul(m) > MAX_DIFF_CELLS {
+        ops.extend((0..n).map(|i| DiffOp::Delete(prefix + i)));
+        ops.extend((0..m).map(|j| DiffOp::Insert(prefix + j)));
+    } else {
+        // lcs[i][j] is the length of the longest common subsequence of old_mid[i..] and new_mid[j..]
+        let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+        let idx = |i: usize, j: usize| i * (m + 1) + j;
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[idx(i, j)] = if old_mid[i] == new_mid[j] {
+                    lcs[idx(i + 1, j + 1)] + 1
+                } else {
+                    lcs[idx(i + 1, j)].max(lcs[idx(i, j + 1)])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_mid[i] == new_mid[j] {
+                ops.push(DiffOp::Equal(prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || lcs[idx(i + 1, j)] >= lcs[idx(i, j + 1)]) {
+                // Prefer deletions so removed lines are listed before the lines replacing them
+                ops.push(DiffOp::Delete(prefix + i));
+                i += 1;
+            } else {
+                ops.push(DiffOp::Insert(prefix + j));
+                j += 1;
+            }
+        }
+    }
+
+    let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+    ops.extend((0..suffix).map(|k| DiffOp::Equal(old_end + k, new_end + k)));
+    ops
+}
+
+/// Number of added and removed lines going from `old` to `new`
+fn count_changes(old: &str, new: &str) -> (usize, usize) {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    diff_ops(&old, &new)
+        .iter()
+        .fold((0, 0), |(added, removed), op| match op {
+            DiffOp::Insert(_) => (added + 1, removed),
+            DiffOp::Delete(_) => (added, removed + 1),
+            DiffOp::Equal(..) => (added, removed),
+        })
+}
+
+/// A line of a unified diff
+#[derive(Debug, PartialEq)]
+enum DiffLine {
+    Hunk(String),
+    Context(usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Group the changes into hunks with `DIFF_CONTEXT` unchanged lines around them
+fn unified_diff(ops: &[DiffOp]) -> Vec<DiffLine> {
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, DiffOp::Equal(..)))
+        .map(|(idx, _)| idx)
+        .collect();
+
+    // Merge changes whose context overlaps into one range of ops
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for idx in changes {
+        let start = idx.saturating_sub(DIFF_CONTEXT);
+        let end = (idx + DIFF_CONTEXT + 1).min(ops.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    // Line numbers in the old and new text before each op
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for op in ops {
+        positions.push((old_pos, new_pos));
+        match op {
+            DiffOp::Equal(..) => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+            DiffOp::Delete(_) => old_pos += 1,
+            DiffOp::Insert(_) => new_pos += 1,
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (start, end) in ranges {
+        let hunk = &ops[start..end];
+        let old_len = hunk
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Insert(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Delete(_)))
+            .count();
+        let (old_start, new_start) = positions[start];
+        // Unified diffs number lines from 1, an empty side starts at the line before
+        let header_start = |start: usize, len: usize| if len == 0 { start } else { start + 1 };
+        lines.push(DiffLine::Hunk(format!(
+            "@@ -{},{old_len} +{},{new_len} @@",
+            header_start(old_start, old_len),
+            header_start(new_start, new_len)
+        )));
+        lines.extend(hunk.iter().map(|op| match *op {
+            DiffOp::Equal(old, _) => DiffLine::Context(old),
+            DiffOp::Delete(old) => DiffLine::Removed(old),
+            DiffOp::Insert(new) => DiffLine::Added(new),
+        }));
+    }
+    lines
+}
+
+/// Unified diff of two DAG sources, syntax highlighted like the DAG code view
+fn render_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> Vec<Line<'static>> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let diff = unified_diff(&diff_ops(&old_lines, &new_lines));
+    if diff.is_empty() {
+        return vec![Line::from(Span::styled(
+            "Source code is identical",
+            DEFAULT_STYLE.fg(Color::Green),
+        ))];
+    }
+
+    let old_highlighted = code_to_lines(old);
+    let new_highlighted = code_to_lines(new);
+    let highlighted = |lines: &[Line<'static>], idx: usize, marker: &'static str, style: Style| {
+        let mut spans = vec![Span::styled(marker, style.add_modifier(Modifier::BOLD))];
+        if let Some(line) = lines.get(idx) {
+            spans.extend(line.spans.iter().map(|span| {
+                let content = span.content.trim_end_matches(['\n', '\r']).to_string();
+                Span::styled(content, span.style.patch(style))
+            }));
+        }
+        Line::from(spans)
+    };
+
+    let removed_style = Style::default().bg(Color::Rgb(64, 16, 16));
+    let added_style = Style::default().bg(Color::Rgb(16, 56, 16));
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("--- {old_name}"),
+            DEFAULT_STYLE.fg(Color::Red),
+        )),
+        Line::from(Span::styled(
+            format!("+++ {new_name}"),
+            DEFAULT_STYLE.fg(Color::Green),
+        )),
+    ];
+    lines.extend(diff.into_iter().map(|line| match line {
+        DiffLine::Hunk(header) => Line::from(Span::styled(header, DEFAULT_STYLE.fg(Color::Cyan))),
+        DiffLine::Context(idx) => highlighted(&old_highlighted, idx, " ", Style::default()),
+        DiffLine::Removed(idx) => highlighted(&old_highlighted, idx, "-", removed_style),
+        DiffLine::Added(idx) => highlighted(&new_highlighted, idx, "+", added_style),
+    }));
+    lines
+}
+
+impl Model for CompareModel {
+    fn update(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
+        match event {
+            FlowrsEvent::Tick => (Some(FlowrsEvent::Tick), vec![]),
+            FlowrsEvent::Key(key_event) => {
+                if self.commands.is_some() {
+                    return handle_command_popup_events(&mut self.commands, key_event);
+                }
+                let max_lines = self.diff.as_ref().map(Vec::len);
+                if handle_vertical_scroll_keys(
+                    &mut self.vertical_scroll,
+                    &mut self.vertical_scroll_state,
+                    key_event,
+                    max_lines,
+                ) {
+                    return (None, vec![]);
+                }
+                match key_event.code {
+                    KeyCode::Tab | KeyCode::Char('l') => {
+                        self.cycle_target(true);
+                        (None, vec![])
+                    }
+                    KeyCode::BackTab | KeyCode::Char('h') => {
+                        self.cycle_target(false);
+                        (None, vec![])
+                    }
+                    KeyCode::Char('r') if key_event.modifiers == KeyModifiers::NONE => {
+                        match &self.dag_id {
+                            Some(dag_id) => (
+                                None,
+                                vec![WorkerMessage::CompareDag {
+                                    dag_id: dag_id.clone(),
+                                    environments: self
+                                        .environments
+                                        .iter()
+                                        .map(|e| e.name.clone())
+                                        .collect(),
+                                }],
+                            ),
+                            None => (None, vec![]),
+                        }
+                    }
+                    KeyCode::Char('?') => {
+                        self.commands = Some(create_compare_command_popup());
+                        (None, vec![])
+                    }
+                    KeyCode::Enter | KeyCode::Right => (None, vec![]),
+                    _ => (Some(event.clone()), vec![]),
+                }
+            }
+            FlowrsEvent::Mouse => (Some(event.clone()), vec![]),
+        }
+    }
+}
+
+impl Widget for &mut CompareModel {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let errors: Vec<Line> = self
+            .environments
+            .iter()
+            .flat_map(|e| {
+                e.errors
+                    .iter()
+                    .map(move |err| Line::from(format!("{}: {err}", e.name)))
+            })
+            .collect();
+        #[allow(clippy::cast_possible_truncation)]
+        let error_height = if errors.is_empty() {
+            0
+        } else {
+            (errors.len() as u16 + 2).min(6)
+        };
+        #[allow(clippy::cast_possible_truncation)]
+        let table_height = FIELDS.len() as u16 + 3;
+        let [table_area, error_area, diff_area] = Layout::vertical([
+            Constraint::Length(table_height),
+            Constraint::Length(error_height),
+            Constraint::Min(0),
+        ])
+        .areas(area);
+
+        let header = Row::new(
+            std::iter::once(Line::from(""))
+                .chain(self.environments.iter().map(|e| Line::from(e.name.clone())))
+                .collect::<Vec<_>>(),
+        )
+        .style(HEADER_STYLE);
+        let rows = FIELDS.iter().enumerate().map(|(idx, (field, label))| {
+            let cells = std::iter::once(Line::from(Span::styled(
+                *label,
+                Style::default().add_modifier(Modifier::BOLD),
+            )))
+            .chain(self.environments.iter().map(|e| self.cell(e, *field)));
+            Row::new(cells.collect::<Vec<_>>()).style(if (idx % 2) == 0 {
+                DEFAULT_STYLE
+            } else {
+                DEFAULT_STYLE.bg(ALTERNATING_ROW_COLOR)
+            })
+        });
+        let widths = std::iter::once(Constraint::Length(12))
+            .chain(self.environments.iter().map(|_| Constraint::Fill(1)))
+            .collect::<Vec<_>>();
+        Widget::render(
+            Table::new(rows, widths)
+                .header(header)
+                .style(DEFAULT_STYLE)
+                .block(
+                    Block::default()
+                        .border_type(BorderType::Rounded)
+                        .borders(Borders::ALL)
+                        .title(format!(
+                            " Compare {} - press <?> for help ",
+                            self.dag_id.as_deref().unwrap_or_default()
+                        )),
+                ),
+            table_area,
+            buf,
+        );
+
+        if !errors.is_empty() {
+            Paragraph::new(errors)
+                .wrap(Wrap { trim: true })
+                .style(DEFAULT_STYLE)
+                .block(
+                    Block::default()
+                        .border_type(BorderType::Rounded)
+                        .borders(Borders::ALL)
+                        .title(" Errors "),
+                )
+                .render(error_area, buf);
+        }
+
+        let diff_title = match (self.base(), self.target()) {
+            (Some(base), Some(target)) => format!(" Source diff {} → {} ", base.name, target.name),
+            _ => " Source diff ".to_string(),
+        };
+        let diff_block = Block::default()
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL)
+            .title(diff_title)
+            .title_bottom("[h/l] Environment  [j/k] Scroll");
+        let diff = match &self.diff {
+            Some(lines) => lines.clone(),
+            None if self.environments.len() < 2 => vec![Line::from(
+                "Select the DAG in more than one environment to compare its source",
+            )],
+            None => vec![Line::from("Waiting for the source of both environments...")],
+        };
+        #[allow(clippy::cast_possible_truncation)]
+        Paragraph::new(diff)
+            .block(diff_block)
+            .style(DEFAULT_STYLE)
+            .scroll((self.vertical_scroll as u16, 0))
+            .render(diff_area, buf);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"));
+        StatefulWidget::render(scrollbar, diff_area, buf, &mut self.vertical_scroll_state);
+
+        if let Some(commands) = &mut self.commands {
+            commands.render(area, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unified(old: &str, new: &str) -> Vec<DiffLine> {
+        let old: Vec<&str> = old.lines().collect();
+        let new: Vec<&str> = new.lines().collect();
+        unified_diff(&diff_ops(&old, &new))
+    }
+
+    #[test]
+    fn test_identical_sources_have_no_diff() {
+        assert!(unified("a\nb\n", "a\nb\n").is_empty());
+        assert_eq!(count_changes("a\nb\n", "a\nb\n"), (0, 0));
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n10\n11\n12\n13\n";
+        assert_eq!(
+            unified(old, new),
+            vec![
+                DiffLine::Hunk("@@ -2,7 +2,7 @@".to_string()),
+                DiffLine::Context(1),
+                DiffLine::Context(2),
+                DiffLine::Context(3),
+                DiffLine::Removed(4),
+                DiffLine::Added(4),
+                DiffLine::Context(5),
+                DiffLine::Context(6),
+                DiffLine::Context(7),
+                DiffLine::Hunk("@@ -10,3 +10,4 @@".to_string()),
+                DiffLine::Context(9),
+                DiffLine::Context(10),
+                DiffLine::Context(11),
+                DiffLine::Added(12),
+            ]
+        );
+        assert_eq!(count_changes(old, new), (2, 1));
+    }
+
+    #[test]
+    fn test_diff_finds_common_lines_in_the_middle() {
+        let old: Vec<&str> = vec!["a", "b", "c", "d"];
+        let new: Vec<&str> = vec!["x", "b", "c", "y"];
+        assert_eq!(
+            diff_ops(&old, &new),
+            vec![
+                DiffOp::Delete(0),
+                DiffOp::Insert(0),
+                DiffOp::Equal(1, 1),
+                DiffOp::Equal(2, 2),
+                DiffOp::Delete(3),
+                DiffOp::Insert(3),
+            ]
+        );
+        // Changes at the start of an empty file
+        assert_eq!(
+            unified("", "a\n"),
+            vec![
+                DiffLine::Hunk("@@ -0,0 +1,1 @@".to_string()),
+                DiffLine::Added(0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycle_target_skips_the_base() {
+        let mut model = CompareModel::new();
+        model.reset(
+            "etl".to_string(),
+            vec!["prod".to_string(), "acc".to_string(), "dev".to_string()],
+        );
+        assert_eq!(model.diff_target, 1);
+        model.cycle_target(true);
+        assert_eq!(model.diff_target, 2);
+        model.cycle_target(true);
+        assert_eq!(model.diff_target, 1);
+        model.cycle_target(false);
+        assert_eq!(model.diff_target, 2);
+    }
+
+    #[test]
+    fn test_changes_are_counted_once_per_environment() {
+        let mut model = CompareModel::new();
+        model.reset(
+            "etl".to_string(),
+            vec!["prod".to_string(), "acc".to_string(), "dev".to_string()],
+        );
+        let environment = |name: &str, code: &str| EnvironmentDag {
+            name: name.to_string(),
+            code: Some(code.to_string()),
+            ..Default::default()
+        };
+
+        // Nothing can be compared before the base source arrives
+        model.update_environment(environment("acc", "a\nb\n"));
+        assert!(model.pending_comparison().is_none());
+
+        model.update_environment(environment("prod", "a\n"));
+        let pending = model.pending_comparison().unwrap();
+        model.apply_comparison(pending.compare());
+        assert_eq!(model.code_changes.get("acc"), Some(&(1, 0)));
+        assert!(model.diff.is_some());
+        assert!(model.pending_comparison().is_none());
+
+        // Only the newly arrived environment is counted
+        model.update_environment(environment("dev", "a\n"));
+        let pending = model.pending_comparison().unwrap();
+        assert_eq!(pending.uncounted.len(), 1);
+        assert!(pending.diff.is_none());
+        model.apply_comparison(pending.compare());
+        assert_eq!(model.code_changes.get("dev"), Some(&(0, 0)));
+    }
+}
@@ -14,6 +14,7 @@ use crate::ui::constants::{ALTERNATING_ROW_COLOR, DEFAULT_STYLE, HEADER_STYLE, R
 use super::popup::commands_help::CommandPopUp;
 use super::popup::config::commands::create_config_command_popup;
 use super::popup::error::ErrorPopup;
+use super::popup::toast::{tick_toast, Toast};
 use super::sortable_table::{CustomSort, SortableTable};
 use super::{filter::Filter, handle_command_popup_events, Model, HALF_PAGE_SIZE};
 
@@ -38,19 +39,21 @@ pub struct ConfigModel {
     pub filter: Filter,
     pub commands: Option<CommandPopUp<'static>>,
     pub error_popup: Option<ErrorPopup>,
+    pub toast: Option<Toast>,
 }
 
 impl ConfigModel {
     pub fn new(configs: Vec<AirflowConfig>) -> Self {
         let headers = ["Version", "Name", "Endpoint"];
-        // Reserved keys: j/k (scroll), o (open), ? (help), / (filter), q (quit)
-        let reserved = &['j', 'k', 'o', '?', '/', 'q'];
+        // Reserved keys: j/k (scroll), o/O (open, overview), S (search), r (rediscover), ? (help), / (filter), q (quit)
+        let reserved = &['j', 'k', 'o', 'r', '?', '/', 'q'];
         ConfigModel {
             all: configs.clone(),
             filtered: SortableTable::new(&headers, configs, reserved),
             filter: Filter::new(),
             commands: None,
             error_popup: None,
+            toast: None,
         }
     }
 
@@ -62,16 +65,24 @@ impl ConfigModel {
         };
 
         let headers = ["Version", "Name", "Endpoint"];
-        let reserved = &['j', 'k', 'o', '?', '/', 'q'];
+        let reserved = &['j', 'k', 'o', 'r', '?', '/', 'q'];
         ConfigModel {
             all: configs.clone(),
             filtered: SortableTable::new(&headers, configs, reserved),
             filter: Filter::new(),
             commands: None,
             error_popup,
+            toast: None,
         }
     }
 
+    /// Replace the listed configs, e.g. after rediscovering managed services
+    pub fn set_configs(&mut self, configs: Vec<AirflowConfig>) {
+        self.all = configs;
+        self.filter_configs();
+        self.filtered.ensure_valid_selection();
+    }
+
     pub fn filter_configs(&mut self) {
         let prefix = &self.filter.prefix;
         let dags = &self.all;
@@ -93,7 +104,10 @@ impl ConfigModel {
 impl Model for ConfigModel {
     fn update(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
         match event {
-            FlowrsEvent::Tick => (Some(FlowrsEvent::Tick), vec![]),
+            FlowrsEvent::Tick => {
+                tick_toast(&mut self.toast);
+                (Some(FlowrsEvent::Tick), vec![])
+            }
             FlowrsEvent::Key(key_event) => {
                 if self.filter.enabled {
                     self.filter.update(key_event);
@@ -156,6 +170,19 @@ impl Model for ConfigModel {
                                         vec![WorkerMessage::OpenItem(OpenItem::Config(endpoint))],
                                     );
                                 }
+                                'O' => {
+                                    return (None, vec![WorkerMessage::UpdateOverview]);
+                                }
+                                'S' => {
+                                    return (None, vec![WorkerMessage::SearchDags]);
+                                }
+                                'r' => {
+                                    self.toast = Some(Toast::new("Rediscovering managed environments..."));
+                                    return (
+                                        Some(event.clone()),
+                                        vec![WorkerMessage::RediscoverEnvironments],
+                                    );
+                                }
                                 '?' => {
                                     self.commands = Some(create_config_command_popup());
                                 }
@@ -248,6 +275,10 @@ impl Widget for &mut ConfigModel {
             commands.render(area, buf);
         }
 
+        if let Some(toast) = &self.toast {
+            toast.render(area, buf);
+        }
+
         if let Some(error_popup) = &self.error_popup {
             error_popup.render(area, buf);
         }
@@ -13,22 +13,25 @@ use syntect::parsing::SyntaxSet;
 use syntect::util::LinesWithEndings;
 use syntect_tui::into_span;
 
-use crate::airflow::model::common::DagRun;
+use crate::airflow::model::common::{DagRun, EventLogQuery};
 use crate::app::events::custom::FlowrsEvent;
 use crate::ui::common::{convert_to_timezone, format_duration_with_now};
-use crate::ui::constants::{AirflowStateColor, ALTERNATING_ROW_COLOR, DEFAULT_STATE_ICON, DEFAULT_STYLE, HEADER_STYLE, MARKED_COLOR, RED, RUNNING_STATE_ICON};
+use crate::ui::constants::{AirflowStateColor, ALTERNATING_ROW_COLOR, DEFAULT_STATE_ICON, DEFAULT_STYLE, HEADER_STYLE, MARKED_COLOR, RED, RUNNING_STATE_ICON, YELLOW};
 use crate::ui::get_time_format;
 
+use super::note::{render_note, split_note_area, visible_note, NOTE_ICON};
 use super::popup::commands_help::CommandPopUp;
 use super::popup::dagruns::commands::create_dagrun_command_popup;
 use super::popup::dagruns::trigger::TriggerDagRunPopUp;
 use super::popup::dagruns::DagRunPopUp;
 use super::popup::error::ErrorPopup;
 use super::popup::popup_area;
+use super::popup::toast::{tick_toast, Toast};
 use super::popup::{dagruns::clear::ClearDagRunPopup, dagruns::mark::MarkDagRunPopup};
 use super::sortable_table::{CustomSort, SortableTable};
 use super::{filter::Filter, handle_command_popup_events, handle_vertical_scroll_keys, Model, HALF_PAGE_SIZE};
-use crate::app::worker::{OpenItem, WorkerMessage};
+use crate::app::export::{export_table, ExportFormat};
+use crate::app::worker::{NoteTarget, OpenItem, WorkerMessage};
 use std::cmp::Ordering;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -209,6 +212,9 @@ pub struct DagRunModel {
     pub popup: Option<DagRunPopUp>,
     pub commands: Option<CommandPopUp<'static>>,
     pub error_popup: Option<ErrorPopup>,
+    pub toast: Option<Toast>,
+    /// Disables the actions that change the environment
+    pub read_only: bool,
     pub current_page: usize,
     pub page_size: usize,
     pub total_entries: i64,  // Total DAG runs available from API
@@ -220,8 +226,8 @@ pub struct DagRunModel {
 impl DagRunModel {
     pub fn new() -> Self {
         let headers = ["State", "DAG Run ID", "Logical Date", "Duration"];
-        // Reserved keys: j/k (scroll), g/G (jump), K/J (focus), m (mark), c (clear), t (trigger), o (open), ? (help), / (filter)
-        let reserved = &['j', 'k', 'g', 'G', 'K', 'J', 'm', 'c', 't', 'o', '?', '/'];
+        // Reserved keys: j/k (scroll), g/G (jump), K/J (focus), m (mark), c (clear), t (trigger), n (note), o (open), x (export), ? (help), / (filter)
+        let reserved = &['j', 'k', 'g', 'G', 'K', 'J', 'm', 'c', 't', 'n', 'o', 'x', '?', '/'];
         let mut filtered = SortableTable::new(&headers, vec![], reserved);
         // Set default sort to Logical Date (column index 2) descending
         filtered.set_default_sort(2, super::sortable_table::SortDirection::Descending);
@@ -238,6 +244,8 @@ impl DagRunModel {
             popup: None,
             commands: None,
             error_popup: None,
+            toast: None,
+            read_only: false,
             current_page: 0,
             page_size: 20,
             total_entries: 0,
@@ -296,6 +304,14 @@ impl DagRunModel {
         });
     }
 
+    pub fn set_note(&mut self, dag_run_id: &str, note: Option<&str>) {
+        self.all
+            .iter_mut()
+            .chain(self.filtered.items.iter_mut())
+            .filter(|dag_run| dag_run.dag_run_id == dag_run_id)
+            .for_each(|dag_run| dag_run.note = note.map(str::to_string));
+    }
+
     pub fn init_info_scroll(&mut self) {
         if let Some(dag) = &self.dag_details {
             self.dag_info.set_info(dag);
@@ -336,6 +352,22 @@ impl DagRunModel {
         }
     }
 
+    /// Export all filtered DAG runs (not only the current page) and report the file path in a toast
+    fn export_dag_runs(&mut self, format: ExportFormat) {
+        let name = format!("dagruns_{}", self.dag_id.as_deref().unwrap_or_default());
+        match export_table(&self.filtered, &name, format) {
+            Ok(filepath) => {
+                self.toast = Some(Toast::new(format!("Exported to {}", filepath.display())));
+            }
+            Err(e) => {
+                self.error_popup = Some(ErrorPopup::from_strings(vec![
+                    "Failed to export DAG runs:".into(),
+                    e.to_string(),
+                ]));
+            }
+        }
+    }
+
     /// Get the range of items being displayed (e.g., "1-20")
     pub fn current_range(&self) -> (usize, usize) {
         if self.filtered.items.is_empty() {
@@ -359,6 +391,7 @@ impl Model for DagRunModel {
         match event {
             FlowrsEvent::Tick => {
                 self.ticks += 1;
+                tick_toast(&mut self.toast);
                 
                 // Check if we need to open DAG code in editor
                 if self.dag_code.pending_editor_open {
@@ -632,12 +665,12 @@ impl Model for DagRunModel {
                                 self.event_buffer.push(FlowrsEvent::Key(*key_event));
                             }
                         }
-                        KeyCode::Char('t') => {
+                        KeyCode::Char('t') if !self.read_only => {
                             self.popup = Some(DagRunPopUp::Trigger(TriggerDagRunPopUp::new(
                                 self.dag_id.clone().unwrap(),
                             )));
                         }
-                        KeyCode::Char('m') => {
+                        KeyCode::Char('m') if !self.read_only => {
                             if let Some(index) = self.filtered.state.selected() {
                                 let actual_idx = self.current_page * self.page_size + index;
                                 self.marked.push(actual_idx);
@@ -651,7 +684,7 @@ impl Model for DagRunModel {
                                 )));
                             }
                         }
-                        KeyCode::Char('M') => {
+                        KeyCode::Char('M') if !self.read_only => {
                             if let Some(index) = self.filtered.state.selected() {
                                 let actual_idx = self.current_page * self.page_size + index;
                                 if self.marked.contains(&actual_idx) {
@@ -661,8 +694,14 @@ impl Model for DagRunModel {
                                 }
                             }
                         }
+                        KeyCode::Char('x') => {
+                            self.export_dag_runs(ExportFormat::Csv);
+                        }
+                        KeyCode::Char('X') => {
+                            self.export_dag_runs(ExportFormat::Json);
+                        }
                         KeyCode::Char('?') => {
-                            self.commands = Some(create_dagrun_command_popup());
+                            self.commands = Some(create_dagrun_command_popup(self.read_only));
                         }
                         KeyCode::Char('/') => {
                             self.filter.toggle();
@@ -704,7 +743,7 @@ impl Model for DagRunModel {
                                 );
                             }
                         }
-                        KeyCode::Char('c') => {
+                        KeyCode::Char('c') if !self.read_only => {
                             if let (Some(dag_run), Some(dag_id)) = (self.current(), &self.dag_id) {
                                 self.popup = Some(DagRunPopUp::Clear(ClearDagRunPopup::new(
                                     dag_run.dag_run_id.clone(),
@@ -724,6 +763,30 @@ impl Model for DagRunModel {
                                 );
                             }
                         }
+                        KeyCode::Char('E') => {
+                            let query = match (&self.dag_id, self.current()) {
+                                (_, Some(dag_run)) => {
+                                    EventLogQuery::for_dag_run(&dag_run.dag_id, &dag_run.dag_run_id)
+                                }
+                                (Some(dag_id), None) => EventLogQuery::for_dag(dag_id),
+                                (None, None) => EventLogQuery::default(),
+                            };
+                            return (None, vec![WorkerMessage::UpdateEventLogs(query)]);
+                        }
+                        KeyCode::Char('n') if !self.read_only => {
+                            if let Some(dag_run) = self.current() {
+                                return (
+                                    None,
+                                    vec![WorkerMessage::EditNote {
+                                        target: NoteTarget::DagRun {
+                                            dag_id: dag_run.dag_id.clone(),
+                                            dag_run_id: dag_run.dag_run_id.clone(),
+                                        },
+                                        note: dag_run.note.clone(),
+                                    }],
+                                );
+                            }
+                        }
                         KeyCode::Char('o') => {
                             if let (Some(dag_id), Some(dag_run)) = (&self.dag_id, &self.current()) {
                                 return (
@@ -827,6 +890,13 @@ impl Widget for &mut DagRunModel {
             scrollbar.render(info_area, buf, &mut self.dag_info.vertical_scroll_state);
         }
 
+        // The note of the selected run is shown below the table
+        let note = self.current().and_then(|dag_run| visible_note(dag_run.note.as_ref())).map(str::to_string);
+        let (dagruns_area, note_area) = split_note_area(dagruns_area, note.as_deref());
+        if let (Some(note), Some(note_area)) = (&note, note_area) {
+            render_note(note, note_area, buf);
+        }
+
         // Render DAGRuns table
         let dagruns_border_style = if self.focused_section == DagRunFocusedSection::DagRunsTable {
             DEFAULT_STYLE.fg(Color::Cyan) // Highlight when focused
@@ -858,10 +928,13 @@ impl Widget for &mut DagRunModel {
                     }
                     _ => Span::styled(DEFAULT_STATE_ICON, DEFAULT_STYLE.fg(AirflowStateColor::None.into())),
                 }),
-                Line::from(Span::styled(
-                    item.dag_run_id.as_str(),
-                    Style::default().add_modifier(Modifier::BOLD),
-                )),
+                Line::from(vec![
+                    Span::styled(item.dag_run_id.as_str(), Style::default().add_modifier(Modifier::BOLD)),
+                    Span::styled(
+                        if visible_note(item.note.as_ref()).is_some() { format!(" {NOTE_ICON}") } else { String::new() },
+                        DEFAULT_STYLE.fg(YELLOW),
+                    ),
+                ]),
                 Line::from(if let Some(date) = item.logical_date {
                     // Convert from UTC to configured timezone and format
                     let local_date = convert_to_timezone(date, &self.timezone_offset);
@@ -951,6 +1024,10 @@ impl Widget for &mut DagRunModel {
             commands.render(area, buf);
         }
 
+        if let Some(toast) = &self.toast {
+            toast.render(area, buf);
+        }
+
         if let Some(error_popup) = &self.error_popup {
             error_popup.render(area, buf);
         }
@@ -983,7 +1060,7 @@ fn format_dag_info(dag: &crate::airflow::model::common::Dag) -> Vec<Line<'static
     lines
 }
 
-fn code_to_lines(dag_code: &str) -> Vec<Line<'static>> {
+pub(crate) fn code_to_lines(dag_code: &str) -> Vec<Line<'static>> {
     let ps = SyntaxSet::load_defaults_newlines();
     let ts = ThemeSet::load_defaults();
 
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use crossterm::event::{KeyCode, KeyModifiers};
 use log::debug;
@@ -11,15 +11,21 @@ use ratatui::widgets::{Block, BorderType, Borders, Row, StatefulWidget, Table, W
 use regex::Regex;
 use time::OffsetDateTime;
 
-use crate::airflow::model::common::{Connection, Dag, DagRun, ImportError, Variable};
+use crate::airflow::model::common::{Connection, Dag, DagRun, EventLogQuery, ImportError, Variable};
 use crate::app::events::custom::FlowrsEvent;
+use crate::app::model::popup::dags::bulk::BulkDagPopup;
 use crate::app::model::popup::dags::commands::create_dag_command_popup;
+use crate::app::model::popup::dags::repair::RepairPopup;
+use crate::app::model::popup::dags::tags::TagSelectPopup;
+use crate::app::model::popup::dags::DagPopUp;
 use crate::ui::common::{format_and_highlight_json, get_state_icon, hash_to_color, highlight_search_text};
-use crate::ui::constants::{ALTERNATING_ROW_COLOR, DEFAULT_STYLE, HEADER_STYLE, RED};
+use crate::ui::constants::{ALTERNATING_ROW_COLOR, DEFAULT_STYLE, HEADER_STYLE, MARKED_COLOR, RED};
 
 use super::popup::commands_help::CommandPopUp;
 use super::popup::error::ErrorPopup;
+use super::popup::toast::{tick_toast, Toast};
 use super::sortable_table::{CustomSort, SortableTable};
+use crate::app::export::{export_table, ExportFormat};
 use super::{filter::Filter, handle_command_popup_events, Model, HALF_PAGE_SIZE};
 use crate::app::worker::{OpenItem, WorkerMessage};
 use std::cmp::Ordering;
@@ -108,6 +114,10 @@ impl CustomSort for Dag {
             _ => None,
         }
     }
+
+    fn pinned(&self) -> bool {
+        self.is_favorite
+    }
 }
 
 impl CustomSort for Variable {
@@ -172,6 +182,11 @@ pub struct DagModel {
     pub filtered: SortableTable<Dag>,
     pub filter: Filter,
     pub show_paused: bool,
+    /// Ids of the pinned DAGs, shown at the top of the table
+    pub favorites: HashSet<String>,
+    pub favorites_only: bool,
+    /// Ids of the DAGs marked for a bulk action
+    pub marked: BTreeSet<String>,
     pub import_error_list: Vec<ImportError>,
     
     // Variables tab data
@@ -198,7 +213,11 @@ pub struct DagModel {
     
     // Shared UI state
     commands: Option<CommandPopUp<'static>>,
+    pub popup: Option<DagPopUp>,
     pub error_popup: Option<ErrorPopup>,
+    pub toast: Option<Toast>,
+    /// Disables the actions that change the environment
+    pub read_only: bool,
     pub loading_status: LoadingStatus,
     ticks: u32,
     event_buffer: Vec<FlowrsEvent>,
@@ -207,8 +226,8 @@ pub struct DagModel {
 impl DagModel {
     pub fn new() -> Self {
         // Reserved keys across all DAG panel tabs: j/k (scroll), g/G (jump), h/l (tab nav), 
-        // p (pause toggle), o (open), r (refresh), ? (help), / (filter)
-        let reserved = &['j', 'k', 'g', 'G', 'h', 'l', 'p', 'o', 'r', '?', '/'];
+        // p (pause toggle), o (open), r (refresh), x (export), f/F (favorites), ? (help), / (filter)
+        let reserved = &['j', 'k', 'g', 'G', 'h', 'l', 'p', 'o', 'r', 'x', 'f', 'F', '?', '/'];
         
         let dag_headers = ["State", "Name", "Schedule", "Next Run", "Tags"];
         let var_headers = ["Key", "Value"];
@@ -222,6 +241,9 @@ impl DagModel {
             filtered: SortableTable::new(&dag_headers, vec![], reserved),
             filter: Filter::new(),
             show_paused: true,
+            favorites: HashSet::new(),
+            favorites_only: false,
+            marked: BTreeSet::new(),
             import_error_list: vec![],
             all_variables: vec![],
             filtered_variables: SortableTable::new(&var_headers, vec![], reserved),
@@ -237,12 +259,36 @@ impl DagModel {
             saved_import_error_selection: None,
             loading_status: LoadingStatus::NotStarted,
             commands: None,
+            popup: None,
             error_popup: None,
+            toast: None,
+            read_only: false,
             ticks: 0,
             event_buffer: vec![],
         }
     }
 
+    /// Export the rows of the active tab and report the file path in a toast
+    fn export_active_tab(&mut self, format: ExportFormat) {
+        let result = match self.active_tab {
+            DagPanelTab::Dags => export_table(&self.filtered, "dags", format),
+            DagPanelTab::Variables => export_table(&self.filtered_variables, "variables", format),
+            DagPanelTab::Connections => export_table(&self.filtered_connections, "connections", format),
+            DagPanelTab::ImportErrors => export_table(&self.filtered_import_errors, "import_errors", format),
+        };
+        match result {
+            Ok(filepath) => {
+                self.toast = Some(Toast::new(format!("Exported to {}", filepath.display())));
+            }
+            Err(e) => {
+                self.error_popup = Some(ErrorPopup::from_strings(vec![
+                    "Failed to export table:".into(),
+                    e.to_string(),
+                ]));
+            }
+        }
+    }
+
     pub fn filter_dags(&mut self) {
         let prefix = &self.filter.prefix;
         
@@ -263,13 +309,17 @@ impl DagModel {
             None => self.all.iter().filter(|dag| dag.is_active.unwrap_or(false)).cloned().collect(),
         };
         
-        // Step 2: Filter by pause state
+        // Step 2: Filter by pause state and favorites
         if !self.show_paused {
             filtered_dags.retain(|dag| !dag.is_paused);
         }
+        if self.favorites_only {
+            filtered_dags.retain(|dag| self.favorites.contains(&dag.dag_id));
+        }
         
-        // Step 3: Compute state priority and schedule frequency for each DAG (for sorting)
+        // Step 3: Compute favorites, state priority and schedule frequency for each DAG (for sorting)
         for dag in &mut filtered_dags {
+            dag.is_favorite = self.favorites.contains(&dag.dag_id);
             dag.computed_state_priority = Some(self.compute_state_priority(dag));
             dag.computed_schedule_frequency = Some(Self::compute_schedule_frequency(dag));
         }
@@ -283,6 +333,19 @@ impl DagModel {
         self.filtered.reapply_sort();
     }
 
+    pub fn set_favorite(&mut self, dag_id: &str, favorite: bool) {
+        if favorite {
+            self.favorites.insert(dag_id.to_string());
+        } else {
+            self.favorites.remove(dag_id);
+        }
+        self.filter_dags();
+        // Keep the DAG selected while it moves in or out of the pinned rows
+        if let Some(idx) = self.filtered.items.iter().position(|dag| dag.dag_id == dag_id) {
+            self.filtered.state.select(Some(idx));
+        }
+    }
+
     pub fn filter_variables(&mut self) {
         let prefix = &self.filter.prefix;
         
@@ -376,6 +439,129 @@ impl DagModel {
         self.filtered_import_errors.reapply_sort();
     }
 
+    /// Mark or unmark the selected DAG for a bulk action
+    fn toggle_mark(&mut self) {
+        if let Some(dag_id) = self.current().map(|dag| dag.dag_id.clone()) {
+            if !self.marked.remove(&dag_id) {
+                self.marked.insert(dag_id);
+            }
+        }
+    }
+
+    /// The tags of the shown DAGs with the number of DAGs carrying each
+    fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts = BTreeMap::new();
+        for tag in self.filtered.items.iter().flat_map(|dag| &dag.tags) {
+            *counts.entry(tag.name.clone()).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+
+    /// Mark all shown DAGs carrying `tag`
+    fn mark_tag(&mut self, tag: &str) {
+        let dag_ids = self
+            .filtered
+            .items
+            .iter()
+            .filter(|dag| dag.tags.iter().any(|t| t.name == tag))
+            .map(|dag| dag.dag_id.clone());
+        self.marked.extend(dag_ids);
+    }
+
+    /// The marked DAGs, or the selected DAG when none are marked
+    fn bulk_dag_ids(&mut self) -> Vec<String> {
+        if self.marked.is_empty() {
+            self.current().map(|dag| vec![dag.dag_id.clone()]).unwrap_or_default()
+        } else {
+            self.marked.iter().cloned().collect()
+        }
+    }
+
+    fn open_tag_popup(&mut self) {
+        let tags = self.tag_counts();
+        if tags.is_empty() {
+            self.error_popup = Some(ErrorPopup::from_strings(vec![
+                "None of the shown DAGs have tags".to_string(),
+            ]));
+        } else {
+            self.popup = Some(DagPopUp::Tags(TagSelectPopup::new(tags)));
+        }
+    }
+
+    /// Pause, unpause or trigger the marked DAGs, or the selected one
+    fn open_bulk_popup(&mut self) {
+        let dag_ids = self.bulk_dag_ids();
+        if dag_ids.is_empty() {
+            self.error_popup = Some(ErrorPopup::from_strings(vec![
+                "No DAGs marked or selected".to_string(),
+            ]));
+        } else {
+            self.popup = Some(DagPopUp::Bulk(BulkDagPopup::new(dag_ids)));
+        }
+    }
+
+    /// Clear failed task instances of the selected DAG, or of all DAGs with a tag
+    fn open_repair_popup(&mut self) {
+        let target = self.current().map(|dag| dag.dag_id.clone()).unwrap_or_default();
+        let mut dags_by_tag: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for dag in self.all.iter().filter(|dag| dag.is_active.unwrap_or(false)) {
+            for tag in &dag.tags {
+                dags_by_tag.entry(tag.name.clone()).or_default().push(dag.dag_id.clone());
+            }
+        }
+        self.popup = Some(DagPopUp::Repair(RepairPopup::new(target, dags_by_tag)));
+    }
+
+    /// Keys for the tag, bulk action and progress popups. Choosing a tag marks its DAGs,
+    /// sending a bulk action clears the marks.
+    fn update_popup(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
+        if let Some(DagPopUp::Repair(popup)) = &mut self.popup {
+            // The repair popup handles Enter and Esc itself, returning the event once it is done
+            let (key_event, messages) = popup.update(event);
+            if key_event.is_some() {
+                self.popup = None;
+            }
+            return (None, messages);
+        }
+        let (key_event, messages) = match &mut self.popup {
+            Some(DagPopUp::Tags(popup)) => popup.update(event),
+            Some(DagPopUp::Bulk(popup)) => popup.update(event),
+            Some(DagPopUp::Progress(_) | DagPopUp::Repair(_)) | None => (Some(event.clone()), vec![]),
+        };
+        if let Some(FlowrsEvent::Key(key_event)) = &key_event {
+            match key_event.code {
+                KeyCode::Enter => {
+                    if let Some(DagPopUp::Tags(popup)) = &self.popup {
+                        if let Some(tag) = popup.selected().map(str::to_string) {
+                            self.mark_tag(&tag);
+                        }
+                    }
+                    if !messages.is_empty() {
+                        self.marked.clear();
+                    }
+                    self.popup = None;
+                }
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.popup = None;
+                }
+                _ => {}
+            }
+        }
+        (None, messages)
+    }
+
+    /// Show a DAG as paused or unpaused after it was changed on the server
+    pub fn set_paused(&mut self, dag_id: &str, is_paused: bool) {
+        for dag in self
+            .all
+            .iter_mut()
+            .chain(self.filtered.items.iter_mut())
+            .filter(|dag| dag.dag_id == dag_id)
+        {
+            dag.is_paused = is_paused;
+        }
+    }
+
     pub fn current(&mut self) -> Option<&mut Dag> {
         self.filtered
             .state
@@ -579,6 +765,7 @@ impl Model for DagModel {
         match event {
             FlowrsEvent::Tick => {
                 self.ticks += 1;
+                tick_toast(&mut self.toast);
                 
                 match &self.loading_status {
                     LoadingStatus::NotStarted => {
@@ -606,6 +793,9 @@ impl Model for DagModel {
                 (Some(FlowrsEvent::Tick), vec![])
             }
             FlowrsEvent::Key(key_event) => {
+                if self.popup.is_some() {
+                    return self.update_popup(event);
+                }
                 // Handle Escape key with multi-stage behavior
                 if key_event.code == KeyCode::Esc {
                     if self.filter.is_enabled() {
@@ -628,8 +818,12 @@ impl Model for DagModel {
                             DagPanelTab::ImportErrors => self.filter_import_errors(),
                         }
                         return (None, vec![]);
+                    } else if !self.marked.is_empty() {
+                        // No filter applied: clear the DAGs marked for a bulk action
+                        self.marked.clear();
+                        return (None, vec![]);
                     }
-                    // else: no filter active, fall through to go back to environment page
+                    // else: no filter or marks, fall through to go back to environment page
                 }
                 
                 if self.filter.is_enabled() {
@@ -808,7 +1002,7 @@ impl Model for DagModel {
                             self.filter_dags();
                             // No WorkerMessage - purely frontend filtering!
                         }
-                        KeyCode::Char('P') => {
+                        KeyCode::Char('P') if !self.read_only => {
                             // Pause/unpause the selected DAG (Shift+P)
                             match self.current() {
                                 Some(dag) => {
@@ -829,6 +1023,40 @@ impl Model for DagModel {
                                 }
                             }
                         }
+                        KeyCode::Char('M') if self.active_tab == DagPanelTab::Dags => {
+                            self.toggle_mark();
+                        }
+                        KeyCode::Char('T') if self.active_tab == DagPanelTab::Dags => {
+                            self.open_tag_popup();
+                        }
+                        KeyCode::Char('B') if self.active_tab == DagPanelTab::Dags && !self.read_only => {
+                            self.open_bulk_popup();
+                        }
+                        KeyCode::Char('R') if self.active_tab == DagPanelTab::Dags && !self.read_only => {
+                            self.open_repair_popup();
+                        }
+                        KeyCode::Char('f') => {
+                            // Pin/unpin the selected DAG, updated right away and reverted if the request fails
+                            if let Some(dag_id) = self.current().map(|dag| dag.dag_id.clone()) {
+                                let favorite = !self.favorites.contains(&dag_id);
+                                self.set_favorite(&dag_id, favorite);
+                                return (None, vec![WorkerMessage::ToggleFavorite { dag_id, favorite }]);
+                            }
+                            self.error_popup = Some(ErrorPopup::from_strings(vec![
+                                "No DAG selected to pin".to_string(),
+                            ]));
+                        }
+                        KeyCode::Char('F') => {
+                            self.favorites_only = !self.favorites_only;
+                            self.filter_dags();
+                        }
+                        KeyCode::Char('E') => {
+                            // Event log of the selected DAG, or of every DAG when none is selected
+                            let query = self.current().map_or_else(EventLogQuery::default, |dag| {
+                                EventLogQuery::for_dag(&dag.dag_id)
+                            });
+                            return (None, vec![WorkerMessage::UpdateEventLogs(query)]);
+                        }
                         KeyCode::Char('/') => {
                             self.filter.toggle();
                             // Apply filter based on active tab
@@ -839,8 +1067,14 @@ impl Model for DagModel {
                                 DagPanelTab::ImportErrors => self.filter_import_errors(),
                             }
                         }
+                        KeyCode::Char('x') => {
+                            self.export_active_tab(ExportFormat::Csv);
+                        }
+                        KeyCode::Char('X') => {
+                            self.export_active_tab(ExportFormat::Json);
+                        }
                         KeyCode::Char('?') => {
-                            self.commands = Some(create_dag_command_popup());
+                            self.commands = Some(create_dag_command_popup(self.read_only));
                         }
                         KeyCode::Enter => {
                             match self.active_tab {
@@ -1066,7 +1300,17 @@ impl DagModel {
             LoadingStatus::Complete | LoadingStatus::NotStarted => String::new(),
         };
         
-        let count_text = format!("(showing {} of {}){}", showing_count, total_count, status_text);
+        let favorites_text = if self.favorites_only && self.active_tab == DagPanelTab::Dags {
+            " ★ favorites only"
+        } else {
+            ""
+        };
+        let marked_text = if self.marked.is_empty() || self.active_tab != DagPanelTab::Dags {
+            String::new()
+        } else {
+            format!(" {} marked", self.marked.len())
+        };
+        let count_text = format!("(showing {} of {}){}{}{}", showing_count, total_count, favorites_text, marked_text, status_text);
         
         // Render appropriate table based on active tab
         match self.active_tab {
@@ -1084,9 +1328,16 @@ impl DagModel {
                             Color::Reset
                         };
                         
+                        let mut name = if item.is_favorite {
+                            vec![Span::styled("★ ", DEFAULT_STYLE.fg(Color::Yellow))]
+                        } else {
+                            vec![]
+                        };
+                        name.extend(highlight_search_text(&item.dag_id, search_term, text_color));
+                        
                         Row::new(vec![
                             Line::from(Span::styled(icon, DEFAULT_STYLE.fg(color))),
-                            Line::from(highlight_search_text(&item.dag_id, search_term, text_color)),
+                            Line::from(name),
                             {
                                 let schedule = item.timetable_description.as_deref().unwrap_or("None");
                                 let schedule_text = if schedule.starts_with("Never") {
@@ -1125,7 +1376,9 @@ impl DagModel {
                             },
                         ])
                         .style({
-                            let base_style = if (idx % 2) == 0 {
+                            let base_style = if self.marked.contains(&item.dag_id) {
+                                DEFAULT_STYLE.bg(MARKED_COLOR)
+                            } else if (idx % 2) == 0 {
                                 DEFAULT_STYLE
                             } else {
                                 DEFAULT_STYLE.bg(ALTERNATING_ROW_COLOR)
@@ -1360,10 +1613,22 @@ impl Widget for &mut DagModel {
         self.render_tabbed_container(main_area, buf);
 
         // Render popups (they float over everything)
+        match &mut self.popup {
+            Some(DagPopUp::Tags(popup)) => popup.render(area, buf),
+            Some(DagPopUp::Bulk(popup)) => popup.render(area, buf),
+            Some(DagPopUp::Progress(popup)) => popup.render(area, buf),
+            Some(DagPopUp::Repair(popup)) => popup.render(area, buf),
+            None => {}
+        }
+
         if let Some(commands) = &mut self.commands {
             commands.render(area, buf);
         }
 
+        if let Some(toast) = &self.toast {
+            toast.render(area, buf);
+        }
+
         if let Some(error_popup) = &self.error_popup {
             error_popup.render(area, buf);
         }
@@ -1569,6 +1834,60 @@ fn parse_timetable_description(description: Option<&str>) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::airflow::model::common::dag::Tag;
+    use crossterm::event::KeyEvent;
+
+    fn dag(dag_id: &str, tags: &[&str]) -> Dag {
+        Dag {
+            dag_id: dag_id.to_string(),
+            is_active: Some(true),
+            tags: tags
+                .iter()
+                .map(|name| Tag {
+                    name: (*name).to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn key(code: KeyCode) -> FlowrsEvent {
+        FlowrsEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_mark_dags_by_selection_and_tag() {
+        let mut model = DagModel::new();
+        model.all = vec![
+            dag("billing", &["finance"]),
+            dag("etl", &["core", "finance"]),
+            dag("report", &["core"]),
+        ];
+        model.filter_dags();
+        model.filtered.state.select(Some(2));
+
+        model.update(&key(KeyCode::Char('M')));
+        assert_eq!(model.tag_counts()[1], ("finance".to_string(), 2));
+        model.update(&key(KeyCode::Char('T')));
+        // Tags are sorted, select "finance" below "core"
+        model.update(&key(KeyCode::Char('j')));
+        model.update(&key(KeyCode::Enter));
+        assert!(model.popup.is_none());
+        assert_eq!(model.bulk_dag_ids(), vec!["billing", "etl", "report"]);
+
+        model.update(&key(KeyCode::Char('B')));
+        let (_, messages) = model.update(&key(KeyCode::Enter));
+        assert!(matches!(
+            messages.as_slice(),
+            [WorkerMessage::BulkDagAction { dag_ids, .. }] if dag_ids.len() == 3
+        ));
+        // Marks are cleared once the action is sent, leaving the selected DAG
+        assert_eq!(model.bulk_dag_ids(), vec!["report"]);
+
+        model.read_only = true;
+        model.update(&key(KeyCode::Char('B')));
+        assert!(model.popup.is_none());
+    }
 
     #[test]
     // TODO: This is poor test... should make it deterministic
@@ -1,7 +1,9 @@
 pub mod connection;
 pub mod importerror;
+pub mod taskinstance;
 pub mod variable;
 
 pub use connection::ConnectionDetailModel;
 pub use importerror::ImportErrorDetailModel;
+pub use taskinstance::TaskInstanceDetailModel;
 pub use variable::VariableDetailModel;
@@ -0,0 +1,387 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        StatefulWidget, Widget, Wrap,
+    },
+};
+use time::OffsetDateTime;
+
+use crate::{
+    airflow::model::common::{Task, TaskInstance},
+    app::{
+        events::custom::FlowrsEvent,
+        model::{handle_vertical_scroll_keys, Model},
+        worker::WorkerMessage,
+    },
+    ui::{
+        common::{convert_to_timezone, format_and_highlight_json, format_duration_seconds},
+        constants::{DEFAULT_STYLE, GREEN, RED},
+        get_time_format,
+    },
+};
+
+/// All fields of a task instance, its rendered template fields and executor config, and the
+/// definition and documentation of its task
+pub struct TaskInstanceDetailModel {
+    pub task_instance: Option<TaskInstance>,
+    /// The task definition, `None` while it is being fetched
+    pub task: Option<Result<Task, String>>,
+    pub timezone_offset: String,
+    vertical_scroll: usize,
+    vertical_scroll_state: ScrollbarState,
+}
+
+impl TaskInstanceDetailModel {
+    pub fn new() -> Self {
+        TaskInstanceDetailModel {
+            task_instance: None,
+            task: None,
+            timezone_offset: "+00:00".to_string(),
+            vertical_scroll: 0,
+            vertical_scroll_state: ScrollbarState::default(),
+        }
+    }
+
+    pub fn set_task_instance(&mut self, task_instance: TaskInstance) {
+        self.task_instance = Some(task_instance);
+        self.task = None;
+        self.vertical_scroll = 0;
+        self.vertical_scroll_state = ScrollbarState::default();
+    }
+
+    /// Store the fetched task definition, unless another task instance was opened meanwhile
+    pub fn set_task(&mut self, dag_id: &str, task_id: &str, task: Result<Task, String>) {
+        if self
+            .task_instance
+            .as_ref()
+            .is_some_and(|ti| ti.dag_id == dag_id && ti.task_id == task_id)
+        {
+            self.task = Some(task);
+        }
+    }
+
+    fn format_date(&self, date: Option<OffsetDateTime>) -> String {
+        date.map_or_else(
+            || "-".to_string(),
+            |date| {
+                convert_to_timezone(date, &self.timezone_offset)
+                    .format(get_time_format())
+                    .unwrap_or_else(|_| "Invalid date".to_string())
+            },
+        )
+    }
+
+    fn format_task_instance(&self) -> Vec<Line<'static>> {
+        let Some(ti) = &self.task_instance else {
+            return vec![Line::from(Span::styled(
+                "No task instance data available",
+                Style::default().fg(Color::DarkGray),
+            ))];
+        };
+        let optional = |value: Option<&str>| value.unwrap_or("-").to_string();
+        let mut fields = vec![
+            ("Task ID", ti.task_id.clone()),
+            ("DAG ID", ti.dag_id.clone()),
+            ("Run ID", ti.dag_run_id.clone()),
+            ("Map index", ti.map_index.to_string()),
+            ("State", optional(ti.state.as_deref())),
+            ("Try", format!("{} of {}", ti.try_number, ti.max_tries + 1)),
+            ("Logical date", self.format_date(ti.logical_date)),
+            ("Queued at", self.format_date(ti.queued_when)),
+            ("Started", self.format_date(ti.start_date)),
+            ("Ended", self.format_date(ti.end_date)),
+            ("Duration", format_duration_seconds(ti.duration)),
+            ("Operator", optional(ti.operator.as_deref())),
+            ("Hostname", optional(ti.hostname.as_deref())),
+            ("Unix user", optional(ti.unixname.as_deref())),
+            ("PID", ti.pid.map_or("-".to_string(), |pid| pid.to_string())),
+            ("Pool", format!("{} ({} slots)", ti.pool, ti.pool_slots)),
+            ("Queue", optional(ti.queue.as_deref())),
+            (
+                "Priority",
+                ti.priority_weight
+                    .map_or("-".to_string(), |weight| weight.to_string()),
+            ),
+            ("Executor", optional(ti.executor.as_deref())),
+            ("Note", optional(ti.note.as_deref())),
+        ];
+        if ti.map_index < 0 {
+            fields.retain(|(name, _)| *name != "Map index");
+        }
+
+        let mut lines = field_lines(fields);
+
+        lines.push(Line::from(""));
+        lines.push(section("Rendered template fields"));
+        match ti
+            .rendered_fields
+            .as_ref()
+            .and_then(|fields| fields.as_object())
+        {
+            Some(fields) if !fields.is_empty() => {
+                for (name, value) in fields {
+                    lines.push(Line::from(Span::styled(
+                        format!("{name}:"),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )));
+                    // Show rendered strings such as SQL as they are, not as JSON strings
+                    let value = match value {
+                        serde_json::Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    };
+                    lines.extend(format_and_highlight_json(&value, false, None).0);
+                }
+            }
+            _ => lines.push(unavailable("No rendered fields")),
+        }
+
+        lines.push(Line::from(""));
+        lines.push(section("Executor config"));
+        match ti.executor_config.as_deref() {
+            Some(config) if !matches!(config, "" | "{}" | "null") => {
+                lines.extend(format_and_highlight_json(config, false, None).0);
+            }
+            _ => lines.push(unavailable("No executor config")),
+        }
+
+        lines.push(Line::from(""));
+        lines.push(section("Task"));
+        match &self.task {
+            None => lines.push(unavailable("Loading task definition…")),
+            Some(Err(error)) => lines.push(Line::from(Span::styled(
+                format!("Failed to fetch task: {error}"),
+                Style::default().fg(RED),
+            ))),
+            Some(Ok(task)) => {
+                let join = |values: &[String]| {
+                    if values.is_empty() {
+                        "-".to_string()
+                    } else {
+                        values.join(", ")
+                    }
+                };
+                lines.extend(field_lines(vec![
+                    ("Operator", optional(task.operator.as_deref())),
+                    ("Owner", optional(task.owner.as_deref())),
+                    ("Trigger rule", optional(task.trigger_rule.as_deref())),
+                    (
+                        "Retries",
+                        task.retries.map_or("-".to_string(), |r| r.to_string()),
+                    ),
+                    ("Pool", optional(task.pool.as_deref())),
+                    ("Queue", optional(task.queue.as_deref())),
+                    ("Templated", join(&task.template_fields)),
+                    ("Downstream", join(&task.downstream_task_ids)),
+                ]));
+                lines.push(Line::from(""));
+                lines.push(section("Documentation"));
+                match task.doc_md.as_deref() {
+                    Some(doc) if !doc.trim().is_empty() => {
+                        lines.extend(doc.lines().map(|line| Line::from(line.to_string())));
+                    }
+                    _ => lines.push(unavailable("No documentation")),
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// Bold, aligned field names followed by their values
+fn field_lines(fields: Vec<(&'static str, String)>) -> Vec<Line<'static>> {
+    let width = fields.iter().map(|(name, _)| name.len()).max().unwrap_or(0) + 2;
+    fields
+        .into_iter()
+        .map(|(name, value)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<width$}", format!("{name}:")),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(value),
+            ])
+        })
+        .collect()
+}
+
+fn section(title: &'static str) -> Line<'static> {
+    Line::from(Span::styled(
+        title,
+        Style::default()
+            .fg(GREEN)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+    ))
+}
+
+fn unavailable(text: &'static str) -> Line<'static> {
+    Line::from(Span::styled(text, Style::default().fg(Color::DarkGray)))
+}
+
+impl Default for TaskInstanceDetailModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Model for TaskInstanceDetailModel {
+    fn update(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
+        match event {
+            FlowrsEvent::Tick => (Some(FlowrsEvent::Tick), vec![]),
+            FlowrsEvent::Key(key) => {
+                // Handle standard scrolling keybinds
+                if handle_vertical_scroll_keys(
+                    &mut self.vertical_scroll,
+                    &mut self.vertical_scroll_state,
+                    key,
+                    None,
+                ) {
+                    return (None, vec![]);
+                }
+
+                match key.code {
+                    KeyCode::Char('g') => {
+                        // Jump to top
+                        self.vertical_scroll = 0;
+                        self.vertical_scroll_state = self.vertical_scroll_state.position(0);
+                        (None, vec![])
+                    }
+                    KeyCode::Char('G') => {
+                        // Jump to bottom
+                        let lines = self.format_task_instance();
+                        self.vertical_scroll = lines.len().saturating_sub(1);
+                        self.vertical_scroll_state =
+                            self.vertical_scroll_state.position(self.vertical_scroll);
+                        (None, vec![])
+                    }
+                    KeyCode::Enter => {
+                        // Continue to the logs, like Enter in the task instance panel
+                        let Some(ti) = &self.task_instance else {
+                            return (None, vec![]);
+                        };
+                        (
+                            Some(FlowrsEvent::Key(*key)),
+                            vec![WorkerMessage::UpdateTaskLogs {
+                                dag_id: ti.dag_id.clone(),
+                                dag_run_id: ti.dag_run_id.clone(),
+                                task_id: ti.task_id.clone(),
+                                #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+                                task_try: ti.try_number as u16,
+                                clear: true,
+                            }],
+                        )
+                    }
+                    _ => (Some(FlowrsEvent::Key(*key)), vec![]),
+                }
+            }
+            FlowrsEvent::Mouse => (Some(event.clone()), vec![]),
+        }
+    }
+}
+
+impl Widget for &mut TaskInstanceDetailModel {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = if let Some(ti) = &self.task_instance {
+            Line::from(vec![
+                Span::styled("Task instance: ", Style::default().fg(GREEN)),
+                Span::raw(format!("{} ({})", ti.task_id, ti.dag_run_id)),
+            ])
+        } else {
+            Line::from(Span::styled("Task instance", Style::default().fg(GREEN)))
+        };
+
+        let lines = self.format_task_instance();
+        self.vertical_scroll_state = self
+            .vertical_scroll_state
+            .content_length(lines.len())
+            .position(self.vertical_scroll);
+
+        let block = Block::default()
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL)
+            .title(title)
+            .title_bottom(Line::from(vec![
+                Span::styled(
+                    "Press Esc/h/← to go back",
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(" | "),
+                Span::styled("Enter for logs", Style::default().fg(Color::DarkGray)),
+            ]))
+            .border_style(DEFAULT_STYLE.fg(Color::Cyan))
+            .style(DEFAULT_STYLE);
+
+        #[allow(clippy::cast_possible_truncation)]
+        Paragraph::new(lines)
+            .block(block)
+            .style(DEFAULT_STYLE)
+            .wrap(Wrap { trim: false })
+            .scroll((self.vertical_scroll as u16, 0))
+            .render(area, buf);
+
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"));
+        let mut scrollbar_state = self.vertical_scroll_state;
+        scrollbar.render(area, buf, &mut scrollbar_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(lines: &[Line]) -> String {
+        lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_rendered_fields_and_task_docs() {
+        let mut detail = TaskInstanceDetailModel::new();
+        detail.set_task_instance(TaskInstance {
+            task_id: "load".to_string(),
+            dag_id: "etl".to_string(),
+            dag_run_id: "manual__1".to_string(),
+            map_index: -1,
+            hostname: Some("worker-1".to_string()),
+            rendered_fields: Some(serde_json::json!({
+                "sql": "SELECT *\nFROM orders\nWHERE ds = '2024-03-01'",
+                "parameters": {"limit": 10},
+            })),
+            executor_config: Some("{}".to_string()),
+            ..Default::default()
+        });
+        let lines = text(&detail.format_task_instance());
+        assert!(lines.contains("worker-1"));
+        assert!(!lines.contains("Map index"));
+        // Rendered SQL keeps its lines instead of showing an escaped JSON string
+        assert!(lines.contains("\nFROM orders\n"));
+        assert!(lines.contains("\"limit\": 10"));
+        assert!(lines.contains("No executor config"));
+        assert!(lines.contains("Loading task definition"));
+
+        // The definition of another task is ignored
+        let task = Task {
+            task_id: "load".to_string(),
+            doc_md: Some("Loads the orders".to_string()),
+            ..Default::default()
+        };
+        detail.set_task("etl", "extract", Ok(task.clone()));
+        assert!(detail.task.is_none());
+        detail.set_task("etl", "load", Ok(task));
+        assert!(text(&detail.format_task_instance()).contains("Loads the orders"));
+    }
+}
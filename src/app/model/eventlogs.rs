@@ -0,0 +1,340 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Row, StatefulWidget, Table, Widget};
+use time::OffsetDateTime;
+
+use crate::airflow::model::common::{EventLog, EventLogList, EventLogQuery};
+use crate::app::events::custom::FlowrsEvent;
+use crate::app::state::Panel;
+use crate::app::worker::WorkerMessage;
+use crate::ui::common::convert_to_timezone;
+use crate::ui::constants::{ALTERNATING_ROW_COLOR, DEFAULT_STYLE, HEADER_STYLE, SELECTED_STYLE};
+use crate::ui::get_time_format;
+
+use super::filter::Filter;
+use super::popup::commands_help::CommandPopUp;
+use super::popup::eventlogs::commands::create_event_log_command_popup;
+use super::{handle_command_popup_events, handle_table_scroll_keys, Model, StatefulTable};
+
+/// The Airflow event log, showing who paused, triggered, cleared or marked what and when
+pub struct EventLogModel {
+    /// The filters of the entries currently shown or being fetched
+    pub query: EventLogQuery,
+    /// The panel the event log was opened from, returned to on Esc
+    pub return_panel: Panel,
+    pub event_logs: StatefulTable<EventLog>,
+    pub total_entries: i64,
+    pub filter: Filter,
+    pub loading: bool,
+    pub error: Option<String>,
+    pub commands: Option<CommandPopUp<'static>>,
+    pub timezone_offset: String,
+}
+
+impl EventLogModel {
+    pub fn new() -> Self {
+        EventLogModel {
+            query: EventLogQuery::default(),
+            return_panel: Panel::Dag,
+            event_logs: StatefulTable::new(vec![]),
+            total_entries: 0,
+            filter: Filter::new(),
+            loading: false,
+            error: None,
+            commands: None,
+            timezone_offset: "+00:00".to_string(),
+        }
+    }
+
+    /// Start fetching the entries matching `query`. Opening the event log from
+    /// another panel remembers that panel to return to.
+    pub fn open(&mut self, query: EventLogQuery, from: &Panel) {
+        if *from != Panel::EventLog {
+            self.return_panel = from.clone();
+        }
+        self.query = query;
+        self.loading = true;
+        self.error = None;
+    }
+
+    /// Show the fetched entries, unless the query was changed in the meantime
+    pub fn set_event_logs(&mut self, query: &EventLogQuery, event_logs: Result<EventLogList>) {
+        if *query != self.query {
+            return;
+        }
+        self.loading = false;
+        match event_logs {
+            Ok(list) => {
+                self.total_entries = list.total_entries;
+                self.event_logs.items = list.event_logs;
+                self.event_logs.ensure_valid_selection();
+            }
+            Err(e) => {
+                self.event_logs.items.clear();
+                self.event_logs.ensure_valid_selection();
+                self.total_entries = 0;
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Open the filter input prefilled with the current query
+    fn edit_filter(&mut self) {
+        let text = self.query.to_filter_text();
+        self.filter.prefix = (!text.is_empty()).then_some(text);
+        self.filter.enabled = true;
+    }
+
+    /// Query the entries matching the filter once the input is closed
+    fn apply_filter(&mut self) -> Vec<WorkerMessage> {
+        let text = self.filter.prefix().cloned().unwrap_or_default();
+        match EventLogQuery::parse(&text, OffsetDateTime::now_utc()) {
+            Ok(query) => vec![WorkerMessage::UpdateEventLogs(query)],
+            Err(e) => {
+                self.error = Some(e.to_string());
+                vec![]
+            }
+        }
+    }
+
+    fn title(&self) -> String {
+        let filter = self.query.to_filter_text();
+        let filter = if filter.is_empty() {
+            "all events".to_string()
+        } else {
+            filter
+        };
+        let count = if self.loading {
+            "loading...".to_string()
+        } else {
+            format!("{} of {}", self.event_logs.items.len(), self.total_entries)
+        };
+        format!(" Event Log - {filter} - {count} - press <?> for help ")
+    }
+
+    fn time(&self, time: Option<OffsetDateTime>) -> String {
+        time.and_then(|time| {
+            convert_to_timezone(time, &self.timezone_offset)
+                .format(get_time_format())
+                .ok()
+        })
+        .unwrap_or_default()
+    }
+}
+
+impl Default for EventLogModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Model for EventLogModel {
+    fn update(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
+        match event {
+            FlowrsEvent::Tick => (Some(FlowrsEvent::Tick), vec![]),
+            FlowrsEvent::Key(key_event) => {
+                if self.filter.is_enabled() {
+                    self.filter.update(key_event);
+                    if self.filter.is_enabled() {
+                        return (None, vec![]);
+                    }
+                    if key_event.code == KeyCode::Esc {
+                        self.filter.prefix = None;
+                        return (None, vec![]);
+                    }
+                    return (None, self.apply_filter());
+                }
+                if self.commands.is_some() {
+                    return handle_command_popup_events(&mut self.commands, key_event);
+                }
+                if handle_table_scroll_keys(&mut self.event_logs, key_event) {
+                    return (None, vec![]);
+                }
+                match key_event.code {
+                    KeyCode::Char('g') => {
+                        self.event_logs.state.select_first();
+                        (None, vec![])
+                    }
+                    KeyCode::Char('G') => {
+                        self.event_logs.state.select_last();
+                        (None, vec![])
+                    }
+                    KeyCode::Char('/') => {
+                        self.error = None;
+                        self.edit_filter();
+                        (None, vec![])
+                    }
+                    KeyCode::Char('r') => (
+                        None,
+                        vec![WorkerMessage::UpdateEventLogs(self.query.clone())],
+                    ),
+                    KeyCode::Char('?') => {
+                        self.commands = Some(create_event_log_command_popup());
+                        (None, vec![])
+                    }
+                    // Esc and Left return to the panel the event log was opened from
+                    _ => (Some(event.clone()), vec![]),
+                }
+            }
+            FlowrsEvent::Mouse => (Some(event.clone()), vec![]),
+        }
+    }
+}
+
+impl Widget for &mut EventLogModel {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let error_height = if self.error.is_some() { 3 } else { 0 };
+        let filter_height = if self.filter.is_enabled() { 3 } else { 0 };
+        let [table_area, error_area, filter_area] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(error_height),
+            Constraint::Length(filter_height),
+        ])
+        .areas(area);
+
+        let header =
+            Row::new(["When", "Event", "Owner", "DAG", "Run", "Task", "Extra"]).style(HEADER_STYLE);
+        let rows = self
+            .event_logs
+            .items
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| {
+                let task = match (&entry.task_id, entry.map_index) {
+                    (Some(task_id), Some(map_index)) if map_index >= 0 => {
+                        format!("{task_id}[{map_index}]")
+                    }
+                    (task_id, _) => task_id.clone().unwrap_or_default(),
+                };
+                Row::new(vec![
+                    Line::from(self.time(entry.when)),
+                    Line::from(Span::styled(
+                        entry.event.as_str(),
+                        Style::default().fg(Color::Cyan),
+                    )),
+                    Line::from(entry.owner.clone().unwrap_or_default()),
+                    Line::from(entry.dag_id.clone().unwrap_or_default()),
+                    Line::from(entry.run_id.clone().unwrap_or_default()),
+                    Line::from(task),
+                    Line::from(entry.extra.clone().unwrap_or_default()),
+                ])
+                .style(if idx % 2 == 0 {
+                    DEFAULT_STYLE
+                } else {
+                    DEFAULT_STYLE.bg(ALTERNATING_ROW_COLOR)
+                })
+            });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(19),
+                Constraint::Length(20),
+                Constraint::Length(14),
+                Constraint::Fill(2),
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Fill(2),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .border_type(BorderType::Rounded)
+                .borders(Borders::ALL)
+                .title(self.title()),
+        )
+        .style(DEFAULT_STYLE)
+        .row_highlight_style(SELECTED_STYLE);
+        StatefulWidget::render(table, table_area, buf, &mut self.event_logs.state);
+
+        if let Some(error) = &self.error {
+            Paragraph::new(error.as_str())
+                .style(DEFAULT_STYLE.fg(Color::Red))
+                .block(
+                    Block::default()
+                        .border_type(BorderType::Rounded)
+                        .borders(Borders::ALL)
+                        .title(" Error "),
+                )
+                .render(error_area, buf);
+        }
+
+        if self.filter.is_enabled() {
+            self.filter.render(filter_area, buf);
+        }
+
+        if let Some(commands) = &mut self.commands {
+            commands.render(area, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: i64, event: &str) -> EventLog {
+        EventLog {
+            id,
+            event: event.to_string(),
+            dag_id: Some("etl".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_results_of_a_previous_query_are_ignored() {
+        let mut model = EventLogModel::new();
+        let dag_query = EventLogQuery::for_dag("etl");
+        let run_query = EventLogQuery::for_dag_run("etl", "manual__1");
+        model.open(dag_query.clone(), &Panel::DAGRun);
+        model.open(run_query.clone(), &Panel::EventLog);
+        assert!(model.return_panel == Panel::DAGRun);
+
+        model.set_event_logs(
+            &dag_query,
+            Ok(EventLogList {
+                event_logs: vec![event(1, "paused")],
+                total_entries: 1,
+            }),
+        );
+        assert!(model.loading);
+        assert!(model.event_logs.items.is_empty());
+
+        model.set_event_logs(
+            &run_query,
+            Ok(EventLogList {
+                event_logs: vec![event(2, "clear"), event(3, "trigger")],
+                total_entries: 2,
+            }),
+        );
+        assert!(!model.loading);
+        assert_eq!(model.event_logs.items.len(), 2);
+        assert_eq!(model.event_logs.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_filter_is_applied_as_query() {
+        let mut model = EventLogModel::new();
+        model.open(EventLogQuery::for_dag("etl"), &Panel::Dag);
+        model.edit_filter();
+        assert_eq!(model.filter.prefix.as_deref(), Some("dag:etl"));
+
+        model.filter.prefix = Some("dag:etl owner:alice".to_string());
+        let messages = model.apply_filter();
+        assert!(matches!(
+            messages.as_slice(),
+            [WorkerMessage::UpdateEventLogs(query)]
+                if query.owner.as_deref() == Some("alice") && query.dag_id.as_deref() == Some("etl")
+        ));
+
+        model.filter.prefix = Some("alice".to_string());
+        assert!(model.apply_filter().is_empty());
+        assert!(model.error.is_some());
+    }
+}
@@ -0,0 +1,53 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph, Widget, Wrap},
+};
+
+use crate::ui::constants::{DEFAULT_STYLE, YELLOW};
+
+/// Lines of a note shown below a table, longer notes are cut off
+const MAX_NOTE_LINES: usize = 4;
+
+/// Marker shown next to runs and tasks that have a note
+pub const NOTE_ICON: &str = "✎";
+
+/// The note text, `None` for a missing or blank note
+pub fn visible_note(note: Option<&String>) -> Option<&str> {
+    note.map(String::as_str)
+        .filter(|note| !note.trim().is_empty())
+}
+
+/// Split the area below a table off for the note of the selected row
+pub fn split_note_area(area: Rect, note: Option<&str>) -> (Rect, Option<Rect>) {
+    let Some(note) = note else {
+        return (area, None);
+    };
+    #[allow(clippy::cast_possible_truncation)]
+    let height = note.lines().count().clamp(1, MAX_NOTE_LINES) as u16 + 2;
+    let [table, note] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(height)]).areas(area);
+    (table, Some(note))
+}
+
+pub fn render_note(note: &str, area: Rect, buf: &mut Buffer) {
+    let block = Block::default()
+        .border_type(BorderType::Rounded)
+        .borders(Borders::ALL)
+        .title(Line::from(vec![
+            Span::styled(format!("{NOTE_ICON} "), DEFAULT_STYLE.fg(YELLOW)),
+            Span::styled("Note", DEFAULT_STYLE.add_modifier(Modifier::BOLD)),
+        ]))
+        .title_bottom(Line::from(Span::styled(
+            "[n] Edit",
+            DEFAULT_STYLE.fg(Color::DarkGray),
+        )))
+        .style(DEFAULT_STYLE);
+    Paragraph::new(note.trim_end().to_string())
+        .block(block)
+        .style(DEFAULT_STYLE)
+        .wrap(Wrap { trim: false })
+        .render(area, buf);
+}
@@ -0,0 +1,370 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, BorderType, Borders, Paragraph, Row, StatefulWidget, Table, Widget, Wrap,
+};
+
+use crate::airflow::model::common::Health;
+use crate::app::events::custom::FlowrsEvent;
+use crate::app::worker::WorkerMessage;
+use crate::ui::constants::{ALTERNATING_ROW_COLOR, DEFAULT_STYLE, HEADER_STYLE, RED};
+
+use super::popup::commands_help::CommandPopUp;
+use super::popup::overview::commands::create_overview_command_popup;
+use super::sortable_table::{CustomSort, SortableTable};
+use super::{handle_command_popup_events, Model, HALF_PAGE_SIZE};
+
+/// Status of a single environment on the overview dashboard.
+/// Every metric is fetched separately, so one failing endpoint doesn't hide the others.
+#[derive(Debug, Clone, Default)]
+pub struct EnvironmentOverview {
+    pub name: String,
+    pub loading: bool,
+    pub health: Option<Health>,
+    pub airflow_version: Option<String>,
+    pub import_errors: Option<usize>,
+    pub failed_runs: Option<u64>,
+    pub running_tasks: Option<u64>,
+    pub queued_tasks: Option<u64>,
+    pub errors: Vec<String>,
+}
+
+impl EnvironmentOverview {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            loading: true,
+            ..Default::default()
+        }
+    }
+
+    /// Short health summary and whether it needs attention
+    fn health_status(&self) -> (String, bool) {
+        match &self.health {
+            _ if self.loading => ("loading...".to_string(), false),
+            Some(health) if health.is_healthy() => ("healthy".to_string(), false),
+            Some(health) => (
+                format!("unhealthy: {}", health.unhealthy_components().join(", ")),
+                true,
+            ),
+            None => ("unreachable".to_string(), true),
+        }
+    }
+
+    /// Environments that are unhealthy or had failures sort first
+    fn severity(&self) -> u64 {
+        let (_, alert) = self.health_status();
+        u64::from(alert) * 1_000_000
+            + self.failed_runs.unwrap_or_default()
+            + self.import_errors.unwrap_or_default() as u64
+    }
+}
+
+fn count_cell<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+impl CustomSort for EnvironmentOverview {
+    fn comparator(column_index: usize) -> Option<fn(&Self, &Self) -> std::cmp::Ordering> {
+        match column_index {
+            1 => Some(|a, b| a.severity().cmp(&b.severity())),
+            3 => Some(|a, b| a.import_errors.cmp(&b.import_errors)),
+            4 => Some(|a, b| a.failed_runs.cmp(&b.failed_runs)),
+            5 => Some(|a, b| a.running_tasks.cmp(&b.running_tasks)),
+            6 => Some(|a, b| a.queued_tasks.cmp(&b.queued_tasks)),
+            _ => None,
+        }
+    }
+
+    fn column_value(&self, column_index: usize) -> String {
+        match column_index {
+            0 => self.name.clone(),
+            1 => self.health_status().0,
+            2 => self.airflow_version.clone().unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+}
+
+pub struct OverviewModel {
+    pub environments: SortableTable<EnvironmentOverview>,
+    pub commands: Option<CommandPopUp<'static>>,
+}
+
+impl OverviewModel {
+    pub fn new() -> Self {
+        let headers = [
+            "Name",
+            "Health",
+            "Version",
+            "Import Errors",
+            "Failed (24h)",
+            "Running",
+            "Queued",
+        ];
+        // Reserved keys: j/k (scroll), r (refresh), ? (help), q (quit)
+        let reserved = &['j', 'k', 'r', '?', 'q'];
+        OverviewModel {
+            environments: SortableTable::new(&headers, vec![], reserved),
+            commands: None,
+        }
+    }
+
+    /// Start a refresh of the given environments, keeping the last known values visible
+    pub fn reset(&mut self, names: Vec<String>) {
+        let previous = std::mem::take(&mut self.environments.items);
+        self.environments.items = names
+            .into_iter()
+            .map(|name| {
+                let mut environment = previous
+                    .iter()
+                    .find(|e| e.name == name)
+                    .cloned()
+                    .unwrap_or_else(|| EnvironmentOverview::new(name));
+                environment.loading = true;
+                environment
+            })
+            .collect();
+        self.environments.reapply_sort();
+    }
+
+    pub fn update_environment(&mut self, overview: EnvironmentOverview) {
+        if let Some(environment) = self
+            .environments
+            .items
+            .iter_mut()
+            .find(|e| e.name == overview.name)
+        {
+            *environment = overview;
+        }
+        self.environments.reapply_sort();
+    }
+
+    fn selected(&self) -> Option<&EnvironmentOverview> {
+        self.environments
+            .state
+            .selected()
+            .and_then(|idx| self.environments.items.get(idx))
+    }
+}
+
+impl Default for OverviewModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Model for OverviewModel {
+    fn update(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
+        match event {
+            FlowrsEvent::Tick => (Some(FlowrsEvent::Tick), vec![]),
+            FlowrsEvent::Key(key_event) => {
+                if self.commands.is_some() {
+                    return handle_command_popup_events(&mut self.commands, key_event);
+                }
+                if key_event.modifiers == KeyModifiers::CONTROL {
+                    match key_event.code {
+                        KeyCode::Char('d') => {
+                            self.environments.scroll_by(HALF_PAGE_SIZE.cast_signed());
+                            return (None, vec![]);
+                        }
+                        KeyCode::Char('u') => {
+                            self.environments.scroll_by(-HALF_PAGE_SIZE.cast_signed());
+                            return (None, vec![]);
+                        }
+                        _ => {}
+                    }
+                }
+                match key_event.code {
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        self.environments.scroll_by(1);
+                        (None, vec![])
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        self.environments.scroll_by(-1);
+                        (None, vec![])
+                    }
+                    KeyCode::Char('r') => (None, vec![WorkerMessage::UpdateOverview]),
+                    KeyCode::Char('?') => {
+                        self.commands = Some(create_overview_command_popup());
+                        (None, vec![])
+                    }
+                    KeyCode::Char(c)
+                        if key_event.modifiers == KeyModifiers::NONE
+                            && self.environments.handle_key(c) =>
+                    {
+                        (None, vec![])
+                    }
+                    // Enter has no next panel here, only Esc/Left and q fall through
+                    KeyCode::Enter | KeyCode::Right => (None, vec![]),
+                    _ => (Some(event.clone()), vec![]),
+                }
+            }
+            FlowrsEvent::Mouse => (Some(event.clone()), vec![]),
+        }
+    }
+}
+
+impl Widget for &mut OverviewModel {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let errors: Vec<Line> = self
+            .selected()
+            .map(|e| e.errors.iter().map(|err| Line::from(err.clone())).collect())
+            .unwrap_or_default();
+        #[allow(clippy::cast_possible_truncation)]
+        let error_height = if errors.is_empty() {
+            0
+        } else {
+            (errors.len() as u16 + 2).min(8)
+        };
+        let [table_area, error_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(error_height)]).areas(area);
+
+        let header_row = self.environments.render_headers(HEADER_STYLE, RED);
+        let header = Row::new(header_row).style(HEADER_STYLE);
+
+        let alert_style = Style::default().fg(Color::Red);
+        let ok_style = Style::default().fg(Color::Green);
+        let rows = self
+            .environments
+            .items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                let (health, alert) = item.health_status();
+                let health_style = if item.loading {
+                    DEFAULT_STYLE
+                } else if alert {
+                    alert_style
+                } else {
+                    ok_style
+                };
+                let failed_style = if item.failed_runs.unwrap_or_default() > 0 {
+                    alert_style
+                } else {
+                    DEFAULT_STYLE
+                };
+                let import_error_style = if item.import_errors.unwrap_or_default() > 0 {
+                    alert_style
+                } else {
+                    DEFAULT_STYLE
+                };
+                Row::new(vec![
+                    Line::from(item.name.as_str()),
+                    Line::from(Span::styled(health, health_style)),
+                    Line::from(
+                        item.airflow_version
+                            .clone()
+                            .unwrap_or_else(|| "-".to_string()),
+                    ),
+                    Line::from(Span::styled(
+                        count_cell(item.import_errors),
+                        import_error_style,
+                    )),
+                    Line::from(Span::styled(count_cell(item.failed_runs), failed_style)),
+                    Line::from(count_cell(item.running_tasks)),
+                    Line::from(count_cell(item.queued_tasks)),
+                ])
+                .style(if (idx % 2) == 0 {
+                    DEFAULT_STYLE
+                } else {
+                    DEFAULT_STYLE.bg(ALTERNATING_ROW_COLOR)
+                })
+            });
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Percentage(25),
+                Constraint::Percentage(25),
+                Constraint::Length(10),
+                Constraint::Length(14),
+                Constraint::Length(13),
+                Constraint::Length(8),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .border_type(BorderType::Rounded)
+                .borders(Borders::ALL)
+                .title(" Overview - press <r> to refresh, <?> for help "),
+        )
+        .style(DEFAULT_STYLE)
+        .row_highlight_style(crate::ui::constants::SELECTED_STYLE);
+        StatefulWidget::render(table, table_area, buf, &mut self.environments.state);
+
+        if !errors.is_empty() {
+            Paragraph::new(errors)
+                .wrap(Wrap { trim: true })
+                .style(DEFAULT_STYLE)
+                .block(
+                    Block::default()
+                        .border_type(BorderType::Rounded)
+                        .borders(Borders::ALL)
+                        .title(" Errors "),
+                )
+                .render(error_area, buf);
+        }
+
+        if let Some(commands) = &mut self.commands {
+            commands.render(area, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airflow::model::common::monitor::ComponentHealth;
+
+    fn healthy() -> Health {
+        Health {
+            components: vec![ComponentHealth {
+                name: "scheduler".to_string(),
+                status: Some("healthy".to_string()),
+                latest_heartbeat: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_reset_keeps_previous_values_while_loading() {
+        let mut model = OverviewModel::new();
+        model.reset(vec!["a".to_string(), "b".to_string()]);
+        model.update_environment(EnvironmentOverview {
+            name: "a".to_string(),
+            health: Some(healthy()),
+            failed_runs: Some(2),
+            ..Default::default()
+        });
+        assert!(!model.environments.items[0].loading);
+
+        model.reset(vec!["a".to_string(), "c".to_string()]);
+        let names: Vec<&str> = model
+            .environments
+            .items
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["a", "c"]);
+        assert!(model.environments.items[0].loading);
+        assert_eq!(model.environments.items[0].failed_runs, Some(2));
+    }
+
+    #[test]
+    fn test_health_status() {
+        let mut overview = EnvironmentOverview::new("a".to_string());
+        assert_eq!(overview.health_status(), ("loading...".to_string(), false));
+
+        overview.loading = false;
+        assert_eq!(overview.health_status(), ("unreachable".to_string(), true));
+
+        overview.health = Some(healthy());
+        assert_eq!(overview.health_status(), ("healthy".to_string(), false));
+    }
+}
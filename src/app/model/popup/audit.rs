@@ -0,0 +1,131 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Row, StatefulWidget, Table, Widget},
+};
+
+use super::popup_area;
+use crate::app::audit::{AuditEntry, AuditLog, AuditOutcome};
+use crate::app::model::{handle_table_scroll_keys, StatefulTable};
+use crate::ui::common::convert_to_timezone;
+use crate::ui::constants::{
+    AirflowStateColor, ALTERNATING_ROW_COLOR, DEFAULT_STYLE, HEADER_STYLE, SELECTED_STYLE,
+};
+use crate::ui::get_time_format;
+
+/// Number of audit log entries shown in the popup
+const RECENT_ACTIONS: usize = 200;
+
+/// The most recent actions from the audit log
+pub struct RecentActionsPopup {
+    actions: StatefulTable<AuditEntry>,
+    error: Option<String>,
+    timezone_offset: String,
+}
+
+impl RecentActionsPopup {
+    pub fn load(timezone_offset: &str) -> Self {
+        let (actions, error) = match AuditLog::default().recent(RECENT_ACTIONS) {
+            Ok(actions) => (actions, None),
+            Err(e) => (vec![], Some(e.to_string())),
+        };
+        Self {
+            actions: StatefulTable::new(actions),
+            error,
+            timezone_offset: timezone_offset.to_string(),
+        }
+    }
+
+    /// Returns false once the popup should be closed
+    pub fn update(&mut self, key_event: &KeyEvent) -> bool {
+        if handle_table_scroll_keys(&mut self.actions, key_event) {
+            return true;
+        }
+        !matches!(
+            key_event.code,
+            KeyCode::Esc | KeyCode::Char('q' | 'A') | KeyCode::Enter
+        )
+    }
+}
+
+impl Widget for &mut RecentActionsPopup {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = popup_area(area, 90, 70);
+        let header = Row::new(["Time", "Environment", "User", "Action", "Target", "Details"])
+            .style(HEADER_STYLE);
+        let rows = self.actions.items.iter().enumerate().map(|(idx, entry)| {
+            let time = convert_to_timezone(entry.timestamp, &self.timezone_offset)
+                .format(get_time_format())
+                .unwrap_or_default();
+            let (color, details) = match entry.outcome {
+                AuditOutcome::Success => (
+                    AirflowStateColor::Success,
+                    entry
+                        .action
+                        .parameters
+                        .iter()
+                        .map(|(name, value)| format!("{name}={value}"))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                AuditOutcome::Failed => (
+                    AirflowStateColor::Failed,
+                    entry.error.clone().unwrap_or_default(),
+                ),
+            };
+            Row::new(vec![
+                Line::from(time),
+                Line::from(entry.environment.as_str()),
+                Line::from(entry.user.as_str()),
+                Line::from(Span::styled(
+                    entry.action.action.as_str(),
+                    DEFAULT_STYLE.fg(color.into()),
+                )),
+                Line::from(entry.action.target()),
+                Line::from(details),
+            ])
+            .style(if idx % 2 == 0 {
+                DEFAULT_STYLE
+            } else {
+                DEFAULT_STYLE.bg(ALTERNATING_ROW_COLOR)
+            })
+        });
+
+        let title = match &self.error {
+            Some(error) => format!("Recent actions - {error}"),
+            None if self.actions.items.is_empty() => "Recent actions - none yet".to_string(),
+            None => format!("Recent actions ({})", self.actions.items.len()),
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(19),
+                Constraint::Length(16),
+                Constraint::Length(12),
+                Constraint::Length(22),
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .border_type(BorderType::Rounded)
+                .borders(Borders::ALL)
+                .title(title)
+                .title_style(DEFAULT_STYLE.add_modifier(Modifier::BOLD))
+                .title_bottom(Line::from(Span::styled(
+                    "[j/k] Scroll  [q/Esc] Close",
+                    DEFAULT_STYLE.fg(Color::DarkGray),
+                )))
+                .style(DEFAULT_STYLE),
+        )
+        .row_highlight_style(SELECTED_STYLE);
+
+        Clear.render(area, buf);
+        StatefulWidget::render(table, area, buf, &mut self.actions.state);
+    }
+}
@@ -40,6 +40,14 @@ impl<'a> CommandPopUp<'a> {
         popup
     }
 
+    /// Leave out the commands with the given key bindings, e.g. the ones that change a read-only environment
+    pub fn without(mut self, key_bindings: &[&str]) -> Self {
+        self.all_commands
+            .retain(|cmd| !key_bindings.contains(&cmd.key_binding));
+        self.filter_commands();
+        self
+    }
+
     pub fn filter_commands(&mut self) {
         let prefix = &self.filter.prefix;
         let filtered = match prefix {
@@ -180,6 +188,11 @@ impl DefaultCommands {
                 key_binding: "l / Right",
                 description: "Move to the next tab",
             },
+            Command {
+                name: "Recent actions",
+                key_binding: "Shift+A",
+                description: "Show the actions performed through flowrs from the audit log",
+            },
             Command {
                 name: "Help",
                 key_binding: "?",
@@ -0,0 +1,37 @@
+use std::sync::LazyLock;
+
+use crate::app::model::popup::commands_help::{Command, CommandPopUp};
+
+pub static COMPARE_COMMANDS: LazyLock<Vec<Command<'static>>> = LazyLock::new(|| {
+    vec![
+        Command {
+            name: "Next environment",
+            key_binding: "l / Tab",
+            description: "Diff the source of the next environment against the first one",
+        },
+        Command {
+            name: "Previous environment",
+            key_binding: "h / Shift+Tab",
+            description: "Diff the source of the previous environment against the first one",
+        },
+        Command {
+            name: "Scroll",
+            key_binding: "j / k",
+            description: "Scroll the source diff",
+        },
+        Command {
+            name: "Refresh",
+            key_binding: "r",
+            description: "Fetch the DAG from all compared environments again",
+        },
+        Command {
+            name: "Back",
+            key_binding: "Esc / Left",
+            description: "Return to the search results",
+        },
+    ]
+});
+
+pub fn create_compare_command_popup() -> CommandPopUp<'static> {
+    CommandPopUp::new("Compare Commands".into(), COMPARE_COMMANDS.clone())
+}
@@ -3,11 +3,28 @@ use std::sync::LazyLock;
 use crate::app::model::popup::commands_help::{Command, CommandPopUp, DefaultCommands};
 
 pub static CONFIG_COMMANDS: LazyLock<Vec<Command<'static>>> = LazyLock::new(|| {
-    let mut commands = vec![Command {
-        name: "Open",
-        key_binding: "o",
-        description: "Open Airflow Web UI",
-    }];
+    let mut commands = vec![
+        Command {
+            name: "Open",
+            key_binding: "o",
+            description: "Open Airflow Web UI",
+        },
+        Command {
+            name: "Overview",
+            key_binding: "O",
+            description: "Show health and activity of all environments",
+        },
+        Command {
+            name: "Search",
+            key_binding: "S",
+            description: "Search the DAGs of all environments",
+        },
+        Command {
+            name: "Rediscover",
+            key_binding: "r",
+            description: "Discover managed service environments again, bypassing the cache",
+        },
+    ];
     commands.append(&mut DefaultCommands::new().0);
     commands
 });
@@ -0,0 +1,139 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use super::popup_area;
+use crate::app::audit::AuditAction;
+use crate::app::worker::WorkerMessage;
+use crate::ui::constants::{DEFAULT_STYLE, GREEN, RED};
+
+/// Number of actions listed before the rest are summarized, e.g. for bulk actions
+const MAX_LISTED_ACTIONS: usize = 10;
+
+/// Holds back changes to an environment with `protection = "confirm"`
+/// until its name has been typed
+pub struct ConfirmEnvironmentPopup {
+    pub environment: String,
+    input: String,
+    pub messages: Vec<WorkerMessage>,
+}
+
+impl ConfirmEnvironmentPopup {
+    pub fn new(environment: &str, messages: Vec<WorkerMessage>) -> Self {
+        Self {
+            environment: environment.to_string(),
+            input: String::new(),
+            messages,
+        }
+    }
+
+    fn is_confirmed(&self) -> bool {
+        self.input == self.environment
+    }
+
+    /// `Some(true)` once the typed name is confirmed with Enter, `Some(false)` when cancelled
+    pub fn update(&mut self, key_event: &KeyEvent) -> Option<bool> {
+        match key_event.code {
+            KeyCode::Esc => return Some(false),
+            KeyCode::Enter if self.is_confirmed() => return Some(true),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) => self.input.push(c),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl Widget for &mut ConfirmEnvironmentPopup {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = popup_area(area, 50, 40);
+        let mut lines = vec![Line::from("This will change:"), Line::from("")];
+        let actions: Vec<AuditAction> = self
+            .messages
+            .iter()
+            .flat_map(AuditAction::all_for_message)
+            .collect();
+        lines.extend(actions.iter().take(MAX_LISTED_ACTIONS).map(|action| {
+            Line::from(vec![
+                Span::styled(
+                    format!("  {} ", action.action),
+                    DEFAULT_STYLE.add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(action.target()),
+            ])
+        }));
+        if actions.len() > MAX_LISTED_ACTIONS {
+            lines.push(Line::from(format!(
+                "  … and {} more",
+                actions.len() - MAX_LISTED_ACTIONS
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw("Type "),
+            Span::styled(
+                self.environment.as_str(),
+                DEFAULT_STYLE.fg(RED).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" to confirm:"),
+        ]));
+        let input_color = if self.is_confirmed() { GREEN } else { RED };
+        lines.push(Line::from(Span::styled(
+            format!("> {}", self.input),
+            DEFAULT_STYLE.fg(input_color),
+        )));
+
+        let block = Block::default()
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL)
+            .border_style(DEFAULT_STYLE.fg(RED))
+            .title(format!(" Protected environment: {} ", self.environment))
+            .title_style(DEFAULT_STYLE.add_modifier(Modifier::BOLD))
+            .title_bottom(Line::from(Span::styled(
+                "[Enter] Confirm  [Esc] Cancel",
+                DEFAULT_STYLE.fg(Color::DarkGray),
+            )))
+            .style(DEFAULT_STYLE);
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_environment_name_must_be_typed() {
+        let trigger = WorkerMessage::TriggerDagRun {
+            dag_id: "etl".to_string(),
+        };
+        let mut popup = ConfirmEnvironmentPopup::new("prod", vec![trigger]);
+        for c in "pro".chars() {
+            assert_eq!(popup.update(&key(KeyCode::Char(c))), None);
+        }
+        // Enter does nothing until the whole name is typed
+        assert_eq!(popup.update(&key(KeyCode::Enter)), None);
+        popup.update(&key(KeyCode::Char('d')));
+        assert_eq!(popup.update(&key(KeyCode::Enter)), Some(true));
+
+        let mut popup = ConfirmEnvironmentPopup::new("prod", vec![]);
+        assert_eq!(popup.update(&key(KeyCode::Esc)), Some(false));
+    }
+}
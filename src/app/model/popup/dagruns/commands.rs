@@ -34,6 +34,16 @@ pub static DAGRUN_COMMANDS: LazyLock<Vec<Command<'static>>> = LazyLock::new(|| {
             key_binding: "t",
             description: "Trigger a DAG run",
         },
+        Command {
+            name: "Note",
+            key_binding: "n",
+            description: "Edit the note of a DAG run in the external editor",
+        },
+        Command {
+            name: "Event log",
+            key_binding: "Shift+E",
+            description: "Show the event log of the selected DAG run",
+        },
         Command {
             name: "Focus Info",
             key_binding: "Shift+K",
@@ -54,11 +64,29 @@ pub static DAGRUN_COMMANDS: LazyLock<Vec<Command<'static>>> = LazyLock::new(|| {
             key_binding: "[[",
             description: "Navigate to previous page",
         },
+        Command {
+            name: "Export CSV",
+            key_binding: "x",
+            description: "Export all filtered DAG runs to CSV",
+        },
+        Command {
+            name: "Export JSON",
+            key_binding: "X",
+            description: "Export all filtered DAG runs to JSON",
+        },
     ];
     commands.append(&mut DefaultCommands::new().0);
     commands
 });
 
-pub fn create_dagrun_command_popup() -> CommandPopUp<'static> {
-    CommandPopUp::new("DAG Run Commands".into(), DAGRUN_COMMANDS.clone())
+/// Key bindings that change the environment, hidden when it is read-only
+const MUTATING_KEY_BINDINGS: [&str; 5] = ["c", "m", "M", "t", "n"];
+
+pub fn create_dagrun_command_popup(read_only: bool) -> CommandPopUp<'static> {
+    let popup = CommandPopUp::new("DAG Run Commands".into(), DAGRUN_COMMANDS.clone());
+    if read_only {
+        popup.without(&MUTATING_KEY_BINDINGS)
+    } else {
+        popup
+    }
 }
@@ -0,0 +1,312 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
+};
+use strum::Display;
+
+use crate::{
+    app::{
+        events::custom::FlowrsEvent,
+        model::{popup::popup_area, Model},
+        worker::WorkerMessage,
+    },
+    ui::constants::{DEFAULT_STYLE, GREEN, RED},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Display)]
+pub enum BulkDagAction {
+    #[strum(to_string = "Pause")]
+    Pause,
+    #[strum(to_string = "Unpause")]
+    Unpause,
+    #[strum(to_string = "Trigger")]
+    Trigger,
+}
+
+impl BulkDagAction {
+    /// The single-DAG message performing this action on `dag_id`
+    pub fn message(self, dag_id: &str) -> WorkerMessage {
+        let dag_id = dag_id.to_string();
+        match self {
+            // `is_paused` is the current state, which toggling flips
+            BulkDagAction::Pause => WorkerMessage::ToggleDag {
+                dag_id,
+                is_paused: false,
+            },
+            BulkDagAction::Unpause => WorkerMessage::ToggleDag {
+                dag_id,
+                is_paused: true,
+            },
+            BulkDagAction::Trigger => WorkerMessage::TriggerDagRun { dag_id },
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            BulkDagAction::Pause => BulkDagAction::Unpause,
+            BulkDagAction::Unpause => BulkDagAction::Trigger,
+            BulkDagAction::Trigger => BulkDagAction::Pause,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            BulkDagAction::Pause => BulkDagAction::Trigger,
+            BulkDagAction::Unpause => BulkDagAction::Pause,
+            BulkDagAction::Trigger => BulkDagAction::Unpause,
+        }
+    }
+}
+
+/// Choose whether to pause, unpause or trigger the marked DAGs
+pub struct BulkDagPopup {
+    pub dag_ids: Vec<String>,
+    pub action: BulkDagAction,
+}
+
+impl BulkDagPopup {
+    pub fn new(dag_ids: Vec<String>) -> Self {
+        BulkDagPopup {
+            dag_ids,
+            action: BulkDagAction::Pause,
+        }
+    }
+}
+
+impl Model for BulkDagPopup {
+    fn update(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
+        if let FlowrsEvent::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Enter => {
+                    // On Enter, we always return the key event, so the parent can close the popup
+                    return (
+                        Some(FlowrsEvent::Key(*key_event)),
+                        vec![WorkerMessage::BulkDagAction {
+                            action: self.action,
+                            dag_ids: self.dag_ids.clone(),
+                        }],
+                    );
+                }
+                KeyCode::Char('j' | 'h') | KeyCode::Down | KeyCode::Left => {
+                    self.action = self.action.previous();
+                    return (None, vec![]);
+                }
+                KeyCode::Char('k' | 'l') | KeyCode::Up | KeyCode::Right => {
+                    self.action = self.action.next();
+                    return (None, vec![]);
+                }
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    return (Some(FlowrsEvent::Key(*key_event)), vec![]);
+                }
+                _ => {}
+            }
+        }
+        (Some(event.clone()), vec![])
+    }
+}
+
+impl Widget for &mut BulkDagPopup {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = popup_area(area, 50, 50);
+
+        let [_, header, options, _] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(1),
+        ])
+        .flex(Flex::Center)
+        .areas(area);
+
+        let popup_block = Block::default()
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL)
+            .title("Bulk DAG Action")
+            .border_style(DEFAULT_STYLE)
+            .style(DEFAULT_STYLE)
+            .title_style(DEFAULT_STYLE.add_modifier(Modifier::BOLD));
+
+        let count = self.dag_ids.len();
+        let text = Paragraph::new(format!(
+            "Select the action to perform on {count} DAG{}:",
+            if count == 1 { "" } else { "s" }
+        ))
+        .style(DEFAULT_STYLE)
+        .centered()
+        .wrap(Wrap { trim: true });
+
+        let [_, pause, _, unpause, _, trigger, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(11),
+            Constraint::Percentage(5),
+            Constraint::Length(11),
+            Constraint::Percentage(5),
+            Constraint::Length(11),
+            Constraint::Fill(1),
+        ])
+        .areas(options);
+
+        Clear.render(area, buffer); //this clears out the background
+        popup_block.render(area, buffer);
+        text.render(header, buffer);
+        for (action, option_area) in [
+            (BulkDagAction::Pause, pause),
+            (BulkDagAction::Unpause, unpause),
+            (BulkDagAction::Trigger, trigger),
+        ] {
+            Paragraph::new(action.to_string())
+                .style(if self.action == action {
+                    DEFAULT_STYLE.reversed()
+                } else {
+                    DEFAULT_STYLE
+                })
+                .centered()
+                .block(
+                    Block::default()
+                        .border_type(BorderType::Rounded)
+                        .borders(Borders::ALL),
+                )
+                .render(option_area, buffer);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BulkDagStatus {
+    Pending,
+    Done,
+    Failed(String),
+}
+
+/// Per-DAG results of a bulk action, filled in by the worker as the requests complete
+pub struct BulkProgressPopup {
+    pub action: BulkDagAction,
+    pub results: Vec<(String, BulkDagStatus)>,
+}
+
+impl BulkProgressPopup {
+    pub fn new(action: BulkDagAction, dag_ids: &[String]) -> Self {
+        BulkProgressPopup {
+            action,
+            results: dag_ids
+                .iter()
+                .map(|dag_id| (dag_id.clone(), BulkDagStatus::Pending))
+                .collect(),
+        }
+    }
+
+    pub fn set_result(&mut self, dag_id: &str, result: &Result<()>) {
+        if let Some((_, status)) = self.results.iter_mut().find(|(id, _)| id == dag_id) {
+            *status = match result {
+                Ok(()) => BulkDagStatus::Done,
+                Err(e) => BulkDagStatus::Failed(e.to_string()),
+            };
+        }
+    }
+
+    /// The number of pending, done and failed DAGs
+    pub fn counts(&self) -> (usize, usize, usize) {
+        self.results.iter().fold(
+            (0, 0, 0),
+            |(pending, done, failed), (_, status)| match status {
+                BulkDagStatus::Pending => (pending + 1, done, failed),
+                BulkDagStatus::Done => (pending, done + 1, failed),
+                BulkDagStatus::Failed(_) => (pending, done, failed + 1),
+            },
+        )
+    }
+}
+
+impl Widget for &mut BulkProgressPopup {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = popup_area(area, 60, 60);
+        let (pending, done, failed) = self.counts();
+
+        // Failures first, so they stay visible when there are more DAGs than lines
+        let mut results: Vec<&(String, BulkDagStatus)> = self.results.iter().collect();
+        results.sort_by_key(|(_, status)| match status {
+            BulkDagStatus::Failed(_) => 0,
+            BulkDagStatus::Pending => 1,
+            BulkDagStatus::Done => 2,
+        });
+        let lines: Vec<Line> = results
+            .into_iter()
+            .map(|(dag_id, status)| match status {
+                BulkDagStatus::Pending => Line::from(vec![
+                    Span::styled("  … ", DEFAULT_STYLE.fg(Color::DarkGray)),
+                    Span::raw(dag_id.as_str()),
+                ]),
+                BulkDagStatus::Done => Line::from(vec![
+                    Span::styled("  ✓ ", DEFAULT_STYLE.fg(GREEN)),
+                    Span::raw(dag_id.as_str()),
+                ]),
+                BulkDagStatus::Failed(error) => Line::from(vec![
+                    Span::styled("  ✗ ", DEFAULT_STYLE.fg(RED)),
+                    Span::raw(dag_id.as_str()),
+                    Span::styled(format!(": {error}"), DEFAULT_STYLE.fg(RED)),
+                ]),
+            })
+            .collect();
+
+        let summary = if pending > 0 {
+            format!(" {done} done, {failed} failed, {pending} pending ")
+        } else {
+            format!(" {done} done, {failed} failed ")
+        };
+        let block = Block::default()
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL)
+            .border_style(DEFAULT_STYLE.fg(if failed > 0 { RED } else { GREEN }))
+            .title(format!(
+                " {} {} DAGs -{summary}",
+                self.action,
+                self.results.len()
+            ))
+            .title_style(DEFAULT_STYLE.add_modifier(Modifier::BOLD))
+            .title_bottom(Line::from(Span::styled(
+                "[Esc] Close",
+                DEFAULT_STYLE.fg(Color::DarkGray),
+            )))
+            .style(DEFAULT_STYLE);
+
+        Clear.render(area, buffer);
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(area, buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bulk_progress() {
+        let dag_ids = vec!["etl".to_string(), "report".to_string(), "sync".to_string()];
+        let mut progress = BulkProgressPopup::new(BulkDagAction::Pause, &dag_ids);
+        assert_eq!(progress.counts(), (3, 0, 0));
+
+        progress.set_result("report", &Ok(()));
+        progress.set_result("sync", &Err(anyhow::anyhow!("403 Forbidden")));
+        assert_eq!(progress.counts(), (1, 1, 1));
+        assert_eq!(
+            progress.results[2].1,
+            BulkDagStatus::Failed("403 Forbidden".to_string())
+        );
+
+        // Pausing toggles DAGs that are currently unpaused
+        assert!(matches!(
+            BulkDagAction::Pause.message("etl"),
+            WorkerMessage::ToggleDag {
+                is_paused: false,
+                ..
+            }
+        ));
+    }
+}
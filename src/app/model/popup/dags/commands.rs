@@ -14,6 +14,41 @@ pub static DAG_COMMANDS: LazyLock<Vec<Command<'static>>> = LazyLock::new(|| {
             key_binding: "Shift+P",
             description: "Pause or unpause selected DAG",
         },
+        Command {
+            name: "Mark",
+            key_binding: "Shift+M",
+            description: "Mark or unmark the selected DAG for a bulk action",
+        },
+        Command {
+            name: "Mark by tag",
+            key_binding: "Shift+T",
+            description: "Mark all shown DAGs with a tag",
+        },
+        Command {
+            name: "Bulk action",
+            key_binding: "Shift+B",
+            description: "Pause, unpause or trigger the marked DAGs (Esc clears marks)",
+        },
+        Command {
+            name: "Repair",
+            key_binding: "Shift+R",
+            description: "Preview and clear failed task instances of a DAG or tag over a date range",
+        },
+        Command {
+            name: "Pin/Unpin",
+            key_binding: "f",
+            description: "Pin or unpin the selected DAG as a favorite",
+        },
+        Command {
+            name: "Favorites only",
+            key_binding: "Shift+F",
+            description: "Toggle showing only pinned DAGs",
+        },
+        Command {
+            name: "Event log",
+            key_binding: "Shift+E",
+            description: "Show who paused, triggered or cleared the selected DAG, and when",
+        },
         Command {
             name: "Focus Import Errors",
             key_binding: "Shift+K",
@@ -24,11 +59,29 @@ pub static DAG_COMMANDS: LazyLock<Vec<Command<'static>>> = LazyLock::new(|| {
             key_binding: "Shift+J",
             description: "Switch focus to DAG table",
         },
+        Command {
+            name: "Export CSV",
+            key_binding: "x",
+            description: "Export the rows of the active tab to CSV",
+        },
+        Command {
+            name: "Export JSON",
+            key_binding: "X",
+            description: "Export the rows of the active tab to JSON",
+        },
     ];
     commands.append(&mut DefaultCommands::new().0);
     commands
 });
 
-pub fn create_dag_command_popup() -> CommandPopUp<'static> {
-    CommandPopUp::new("DAG Commands".into(), DAG_COMMANDS.clone())
+/// Key bindings that change the environment, hidden when it is read-only
+const MUTATING_KEY_BINDINGS: [&str; 3] = ["Shift+P", "Shift+B", "Shift+R"];
+
+pub fn create_dag_command_popup(read_only: bool) -> CommandPopUp<'static> {
+    let popup = CommandPopUp::new("DAG Commands".into(), DAG_COMMANDS.clone());
+    if read_only {
+        popup.without(&MUTATING_KEY_BINDINGS)
+    } else {
+        popup
+    }
 }
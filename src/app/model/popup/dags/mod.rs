@@ -1 +1,15 @@
+use bulk::{BulkDagPopup, BulkProgressPopup};
+use repair::RepairPopup;
+use tags::TagSelectPopup;
+
+pub mod bulk;
 pub mod commands;
+pub mod repair;
+pub mod tags;
+
+pub enum DagPopUp {
+    Tags(TagSelectPopup),
+    Bulk(BulkDagPopup),
+    Progress(BulkProgressPopup),
+    Repair(RepairPopup),
+}
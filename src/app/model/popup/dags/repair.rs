@@ -0,0 +1,492 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
+};
+use time::OffsetDateTime;
+
+use crate::{
+    airflow::model::common::{eventlog::parse_time, ClearTaskInstances, TaskInstanceReference},
+    app::{
+        events::custom::FlowrsEvent,
+        model::{popup::popup_area, Model},
+        worker::WorkerMessage,
+    },
+    ui::constants::{DEFAULT_STYLE, GREEN, RED, YELLOW},
+};
+
+/// The DAGs to repair and which of their task instances to clear
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepairRequest {
+    pub dag_ids: Vec<String>,
+    pub clear: ClearTaskInstances,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RepairStatus {
+    Pending,
+    /// The task instances the dry run matched
+    Matched(Vec<TaskInstanceReference>),
+    /// The number of cleared task instances
+    Cleared(usize),
+    Failed(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RepairStep {
+    Edit,
+    Preview,
+    Clear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RepairField {
+    Target,
+    Since,
+    Until,
+    States,
+}
+
+/// Clear the failed task instances of a DAG, or of all DAGs with a tag, over a range of
+/// logical dates. The matching task instances are previewed with a dry run before clearing.
+pub struct RepairPopup {
+    pub step: RepairStep,
+    target: String,
+    since: String,
+    until: String,
+    only_failed: bool,
+    field: RepairField,
+    /// Ids of the DAGs carrying each tag, to resolve a `tag:<name>` target
+    dags_by_tag: BTreeMap<String, Vec<String>>,
+    /// The request being previewed or cleared
+    pub request: Option<RepairRequest>,
+    /// Per-DAG results of the dry run, then of clearing
+    pub results: BTreeMap<String, RepairStatus>,
+    error: Option<String>,
+    scroll: u16,
+}
+
+impl RepairPopup {
+    pub fn new(target: String, dags_by_tag: BTreeMap<String, Vec<String>>) -> Self {
+        RepairPopup {
+            step: RepairStep::Edit,
+            target,
+            since: "24h".to_string(),
+            until: String::new(),
+            only_failed: true,
+            field: RepairField::Target,
+            dags_by_tag,
+            request: None,
+            results: BTreeMap::new(),
+            error: None,
+            scroll: 0,
+        }
+    }
+
+    /// The request described by the form, relative times are resolved against `now`
+    fn parse_request(&self, now: OffsetDateTime) -> Result<RepairRequest> {
+        let target = self.target.trim();
+        let dag_ids = if let Some(tag) = target.strip_prefix("tag:") {
+            match self.dags_by_tag.get(tag) {
+                Some(dag_ids) => dag_ids.clone(),
+                None => bail!("No DAG has the tag '{tag}'"),
+            }
+        } else if target.is_empty() {
+            bail!("Enter a DAG id, or tag:<name> for all DAGs with a tag");
+        } else {
+            vec![target.to_string()]
+        };
+        let time = |value: &str| -> Result<Option<OffsetDateTime>> {
+            let value = value.trim();
+            if value.is_empty() {
+                Ok(None)
+            } else {
+                parse_time(value, now).map(Some)
+            }
+        };
+        let clear = ClearTaskInstances {
+            start_date: time(&self.since)?,
+            end_date: time(&self.until)?,
+            only_failed: self.only_failed,
+        };
+        match (clear.start_date, clear.end_date) {
+            (Some(start), Some(end)) if start > end => bail!("'since' must be before 'until'"),
+            (Some(_), Some(_)) => {}
+            // Without bounds, any state would clear the whole history of the DAGs
+            _ if !clear.only_failed => {
+                bail!("Clearing task instances in any state needs both 'since' and 'until'");
+            }
+            _ => {}
+        }
+        Ok(RepairRequest { dag_ids, clear })
+    }
+
+    fn start(&mut self, request: RepairRequest, step: RepairStep) {
+        self.results = request
+            .dag_ids
+            .iter()
+            .map(|dag_id| (dag_id.clone(), RepairStatus::Pending))
+            .collect();
+        self.request = Some(request);
+        self.step = step;
+        self.error = None;
+        self.scroll = 0;
+    }
+
+    /// Show the cleared task instances instead of the preview, once the worker starts clearing
+    pub fn start_clearing(&mut self, request: &RepairRequest) {
+        self.start(request.clone(), RepairStep::Clear);
+    }
+
+    /// Record the result for one DAG, unless the request was changed in the meantime
+    pub fn set_result(&mut self, request: &RepairRequest, dag_id: &str, status: RepairStatus) {
+        if self.request.as_ref() == Some(request) {
+            self.results.insert(dag_id.to_string(), status);
+        }
+    }
+
+    fn is_pending(&self) -> bool {
+        self.results
+            .values()
+            .any(|status| *status == RepairStatus::Pending)
+    }
+
+    /// The previewed DAGs with at least one matching task instance
+    fn matched_dag_ids(&self) -> Vec<String> {
+        self.results
+            .iter()
+            .filter(|(_, status)| matches!(status, RepairStatus::Matched(tis) if !tis.is_empty()))
+            .map(|(dag_id, _)| dag_id.clone())
+            .collect()
+    }
+
+    fn field_mut(&mut self) -> Option<&mut String> {
+        match self.field {
+            RepairField::Target => Some(&mut self.target),
+            RepairField::Since => Some(&mut self.since),
+            RepairField::Until => Some(&mut self.until),
+            RepairField::States => None,
+        }
+    }
+
+    fn next_field(&mut self) {
+        self.field = match self.field {
+            RepairField::Target => RepairField::Since,
+            RepairField::Since => RepairField::Until,
+            RepairField::Until => RepairField::States,
+            RepairField::States => RepairField::Target,
+        };
+    }
+
+    fn previous_field(&mut self) {
+        self.field = match self.field {
+            RepairField::Target => RepairField::States,
+            RepairField::Since => RepairField::Target,
+            RepairField::Until => RepairField::Since,
+            RepairField::States => RepairField::Until,
+        };
+    }
+
+    fn update_form(&mut self, code: KeyCode) -> Vec<WorkerMessage> {
+        match code {
+            KeyCode::Enter => match self.parse_request(OffsetDateTime::now_utc()) {
+                Ok(request) => {
+                    self.start(request.clone(), RepairStep::Preview);
+                    return vec![WorkerMessage::PreviewRepair(request)];
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            },
+            KeyCode::Tab | KeyCode::Down => self.next_field(),
+            KeyCode::BackTab | KeyCode::Up => self.previous_field(),
+            KeyCode::Char(' ') if self.field == RepairField::States => {
+                self.only_failed = !self.only_failed;
+            }
+            KeyCode::Backspace => {
+                if let Some(value) = self.field_mut() {
+                    value.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(value) = self.field_mut() {
+                    value.push(c);
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    fn form_lines(&self) -> Vec<Line<'static>> {
+        let states = if self.only_failed {
+            "failed, upstream_failed"
+        } else {
+            "any state"
+        };
+        let field = |name: &'static str, field: RepairField, value: String| {
+            let style = if self.field == field {
+                DEFAULT_STYLE.add_modifier(Modifier::REVERSED)
+            } else {
+                DEFAULT_STYLE
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{name:>8}: "),
+                    DEFAULT_STYLE.add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(value, style),
+            ])
+        };
+        let mut lines = vec![
+            field("DAG", RepairField::Target, format!("{} ", self.target)),
+            field("Since", RepairField::Since, format!("{} ", self.since)),
+            field("Until", RepairField::Until, format!("{} ", self.until)),
+            field("States", RepairField::States, format!("[{states}]")),
+            Line::from(""),
+            Line::from(Span::styled(
+                "DAG id or tag:<name>. Times: 30m, 24h, 7d, a date or RFC 3339, empty for no bound.",
+                DEFAULT_STYLE.fg(Color::DarkGray),
+            )),
+            Line::from(Span::styled(
+                "Space toggles between failed and all task instances, which needs both times.",
+                DEFAULT_STYLE.fg(Color::DarkGray),
+            )),
+        ];
+        if let Some(error) = &self.error {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                DEFAULT_STYLE.fg(RED),
+            )));
+        }
+        lines
+    }
+
+    fn result_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![];
+        for (dag_id, status) in &self.results {
+            match status {
+                RepairStatus::Pending => lines.push(Line::from(vec![
+                    Span::styled("  … ", DEFAULT_STYLE.fg(Color::DarkGray)),
+                    Span::raw(dag_id.clone()),
+                ])),
+                RepairStatus::Matched(task_instances) => {
+                    lines.push(Line::from(vec![
+                        Span::styled(dag_id.clone(), DEFAULT_STYLE.add_modifier(Modifier::BOLD)),
+                        Span::styled(
+                            format!(" ({} task instances)", task_instances.len()),
+                            DEFAULT_STYLE.fg(Color::DarkGray),
+                        ),
+                    ]));
+                    lines.extend(task_instances.iter().map(|ti| {
+                        Line::from(vec![
+                            Span::raw(format!("  {} ", ti.dag_run_id)),
+                            Span::raw(ti.task_id.clone()),
+                            Span::styled(
+                                ti.state
+                                    .as_ref()
+                                    .map(|state| format!(" {state}"))
+                                    .unwrap_or_default(),
+                                DEFAULT_STYLE.fg(YELLOW),
+                            ),
+                        ])
+                    }));
+                }
+                RepairStatus::Cleared(count) => lines.push(Line::from(vec![
+                    Span::styled("  ✓ ", DEFAULT_STYLE.fg(GREEN)),
+                    Span::raw(format!("{dag_id}: {count} task instances cleared")),
+                ])),
+                RepairStatus::Failed(error) => lines.push(Line::from(vec![
+                    Span::styled("  ✗ ", DEFAULT_STYLE.fg(RED)),
+                    Span::raw(dag_id.clone()),
+                    Span::styled(format!(": {error}"), DEFAULT_STYLE.fg(RED)),
+                ])),
+            }
+        }
+        lines
+    }
+
+    fn title(&self) -> String {
+        let count = |status: &RepairStatus| match status {
+            RepairStatus::Matched(task_instances) => task_instances.len(),
+            RepairStatus::Cleared(count) => *count,
+            RepairStatus::Pending | RepairStatus::Failed(_) => 0,
+        };
+        let total: usize = self.results.values().map(count).sum();
+        match (self.step, self.is_pending()) {
+            (RepairStep::Edit, _) => " Repair failed task instances ".to_string(),
+            (RepairStep::Preview, true) => " Repair - looking for task instances... ".to_string(),
+            (RepairStep::Preview, false) => format!(" Repair - {total} task instances to clear "),
+            (RepairStep::Clear, true) => " Repair - clearing... ".to_string(),
+            (RepairStep::Clear, false) => format!(" Repair - {total} task instances cleared "),
+        }
+    }
+}
+
+impl Model for RepairPopup {
+    /// Returns the key event when the popup should be closed
+    fn update(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
+        let FlowrsEvent::Key(key_event) = event else {
+            return (None, vec![]);
+        };
+        match (self.step, key_event.code) {
+            (RepairStep::Edit, KeyCode::Esc) => return (Some(event.clone()), vec![]),
+            (RepairStep::Edit, code) => return (None, self.update_form(code)),
+            (RepairStep::Preview, KeyCode::Esc) => {
+                // Back to the form to adjust the selection
+                self.step = RepairStep::Edit;
+                self.request = None;
+                self.results.clear();
+            }
+            (RepairStep::Preview, KeyCode::Enter) if !self.is_pending() => {
+                let dag_ids = self.matched_dag_ids();
+                if let (false, Some(request)) = (dag_ids.is_empty(), &self.request) {
+                    let request = RepairRequest {
+                        dag_ids,
+                        clear: request.clear.clone(),
+                    };
+                    return (None, vec![WorkerMessage::RepairTaskInstances(request)]);
+                }
+            }
+            (RepairStep::Clear, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) => {
+                return (Some(event.clone()), vec![]);
+            }
+            (_, KeyCode::Char('j') | KeyCode::Down) => self.scroll = self.scroll.saturating_add(1),
+            (_, KeyCode::Char('k') | KeyCode::Up) => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+        (None, vec![])
+    }
+}
+
+impl Widget for &mut RepairPopup {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = popup_area(area, 70, 70);
+        let (lines, keys) = match self.step {
+            RepairStep::Edit => (
+                self.form_lines(),
+                "[Enter] Preview  [Tab] Next field  [Esc] Cancel",
+            ),
+            RepairStep::Preview => (
+                self.result_lines(),
+                "[Enter] Clear all  [j/k] Scroll  [Esc] Back",
+            ),
+            RepairStep::Clear => (self.result_lines(), "[j/k] Scroll  [Esc] Close"),
+        };
+        let block = Block::default()
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL)
+            .title(self.title())
+            .title_style(DEFAULT_STYLE.add_modifier(Modifier::BOLD))
+            .title_bottom(Line::from(Span::styled(
+                keys,
+                DEFAULT_STYLE.fg(Color::DarkGray),
+            )))
+            .style(DEFAULT_STYLE);
+
+        Clear.render(area, buffer);
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .render(area, buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+    use time::macros::datetime;
+
+    fn key(code: KeyCode) -> FlowrsEvent {
+        FlowrsEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn task_instance(dag_id: &str, task_id: &str) -> TaskInstanceReference {
+        TaskInstanceReference {
+            task_id: task_id.to_string(),
+            dag_id: dag_id.to_string(),
+            dag_run_id: "scheduled__2024-03-02".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_request() {
+        let now = datetime!(2024-03-10 12:00 UTC);
+        let dags_by_tag = BTreeMap::from([(
+            "finance".to_string(),
+            vec!["billing".to_string(), "etl".to_string()],
+        )]);
+        let mut popup = RepairPopup::new("tag:finance".to_string(), dags_by_tag);
+        popup.until = "2024-03-10".to_string();
+        let request = popup.parse_request(now).unwrap();
+        assert_eq!(request.dag_ids, vec!["billing", "etl"]);
+        assert_eq!(
+            request.clear.start_date,
+            Some(datetime!(2024-03-09 12:00 UTC))
+        );
+        assert_eq!(
+            request.clear.end_date,
+            Some(datetime!(2024-03-10 00:00 UTC))
+        );
+        assert!(request.clear.only_failed);
+
+        popup.target = "tag:marketing".to_string();
+        assert!(popup.parse_request(now).is_err());
+        popup.target = "etl".to_string();
+        popup.since = "7d".to_string();
+        popup.until = "8d".to_string();
+        assert!(popup.parse_request(now).is_err());
+
+        // Task instances in any state are only cleared within a date range
+        popup.only_failed = false;
+        popup.until = String::new();
+        assert!(popup.parse_request(now).is_err());
+        popup.until = "1d".to_string();
+        assert!(!popup.parse_request(now).unwrap().clear.only_failed);
+    }
+
+    #[test]
+    fn test_only_matched_dags_are_cleared() {
+        let mut popup = RepairPopup::new(
+            "tag:finance".to_string(),
+            BTreeMap::from([(
+                "finance".to_string(),
+                vec!["billing".to_string(), "etl".to_string()],
+            )]),
+        );
+        let (_, messages) = popup.update(&key(KeyCode::Enter));
+        let [WorkerMessage::PreviewRepair(request)] = messages.as_slice() else {
+            panic!("expected a preview, got {messages:?}");
+        };
+        assert_eq!(popup.step, RepairStep::Preview);
+
+        popup.set_result(request, "billing", RepairStatus::Matched(vec![]));
+        // Clearing waits for the dry run of every DAG
+        assert!(popup.update(&key(KeyCode::Enter)).1.is_empty());
+        popup.set_result(
+            request,
+            "etl",
+            RepairStatus::Matched(vec![task_instance("etl", "load")]),
+        );
+        let (_, messages) = popup.update(&key(KeyCode::Enter));
+        assert!(matches!(
+            messages.as_slice(),
+            [WorkerMessage::RepairTaskInstances(RepairRequest { dag_ids, .. })] if dag_ids == &["etl"]
+        ));
+
+        // Results of an earlier request are ignored
+        popup.update(&key(KeyCode::Esc));
+        popup.target = "etl".to_string();
+        popup.update(&key(KeyCode::Enter));
+        popup.set_result(request, "etl", RepairStatus::Cleared(1));
+        assert!(popup.is_pending());
+    }
+}
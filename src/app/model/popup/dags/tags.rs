@@ -0,0 +1,95 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListState, StatefulWidget, Widget},
+};
+
+use crate::{
+    app::{
+        events::custom::FlowrsEvent,
+        model::{popup::popup_area, Model},
+        worker::WorkerMessage,
+    },
+    ui::{
+        common::hash_to_color,
+        constants::{DEFAULT_STYLE, SELECTED_STYLE},
+    },
+};
+
+/// Pick a tag to mark all DAGs carrying it
+pub struct TagSelectPopup {
+    /// Tag names with the number of DAGs carrying them
+    pub tags: Vec<(String, usize)>,
+    state: ListState,
+}
+
+impl TagSelectPopup {
+    pub fn new(tags: Vec<(String, usize)>) -> Self {
+        TagSelectPopup {
+            tags,
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.state
+            .selected()
+            .and_then(|i| self.tags.get(i))
+            .map(|(tag, _)| tag.as_str())
+    }
+}
+
+impl Model for TagSelectPopup {
+    fn update(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
+        if let FlowrsEvent::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.state.select_next();
+                    return (None, vec![]);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.state.select_previous();
+                    return (None, vec![]);
+                }
+                // On Enter, Esc and q the parent reads the selection and closes the popup
+                KeyCode::Enter | KeyCode::Char('q') | KeyCode::Esc => {
+                    return (Some(FlowrsEvent::Key(*key_event)), vec![]);
+                }
+                _ => {}
+            }
+        }
+        (None, vec![])
+    }
+}
+
+impl Widget for &mut TagSelectPopup {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = popup_area(area, 40, 60);
+        let items = self.tags.iter().map(|(tag, count)| {
+            Line::from(vec![
+                Span::styled(tag.as_str(), DEFAULT_STYLE.fg(hash_to_color(tag))),
+                Span::styled(format!(" ({count})"), DEFAULT_STYLE.fg(Color::DarkGray)),
+            ])
+        });
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .border_type(BorderType::Rounded)
+                    .borders(Borders::ALL)
+                    .title(" Mark DAGs by tag ")
+                    .title_style(DEFAULT_STYLE.add_modifier(Modifier::BOLD))
+                    .title_bottom(Line::from(Span::styled(
+                        "[Enter] Mark  [Esc] Cancel",
+                        DEFAULT_STYLE.fg(Color::DarkGray),
+                    )))
+                    .style(DEFAULT_STYLE),
+            )
+            .highlight_style(SELECTED_STYLE);
+
+        Clear.render(area, buffer);
+        StatefulWidget::render(list, area, buffer, &mut self.state);
+    }
+}
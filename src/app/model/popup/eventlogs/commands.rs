@@ -0,0 +1,42 @@
+use std::sync::LazyLock;
+
+use crate::app::model::popup::commands_help::{Command, CommandPopUp};
+
+pub static EVENT_LOG_COMMANDS: LazyLock<Vec<Command<'static>>> = LazyLock::new(|| {
+    vec![
+        Command {
+            name: "Filter",
+            key_binding: "/",
+            description: "Filter with dag: run: task: event: owner: since: until:, e.g. event:clear since:24h",
+        },
+        Command {
+            name: "Refresh",
+            key_binding: "r",
+            description: "Fetch the event log again",
+        },
+        Command {
+            name: "Next",
+            key_binding: "j / Down",
+            description: "Move to the next event",
+        },
+        Command {
+            name: "Previous",
+            key_binding: "k / Up",
+            description: "Move to the previous event",
+        },
+        Command {
+            name: "Top / Bottom",
+            key_binding: "g / G",
+            description: "Jump to the newest or oldest event",
+        },
+        Command {
+            name: "Back",
+            key_binding: "Esc / Left",
+            description: "Return to the panel the event log was opened from",
+        },
+    ]
+});
+
+pub fn create_event_log_command_popup() -> CommandPopUp<'static> {
+    CommandPopUp::new("Event Log Commands".into(), EVENT_LOG_COMMANDS.clone())
+}
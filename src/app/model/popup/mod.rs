@@ -1,10 +1,17 @@
+pub mod audit;
 pub mod commands_help;
+pub mod compare;
 pub mod config;
+pub mod confirm;
 pub mod dags;
 pub mod dagruns;
 pub mod error;
+pub mod eventlogs;
 pub mod logs;
+pub mod overview;
+pub mod search;
 pub mod taskinstances;
+pub mod toast;
 
 use ratatui::layout::{Constraint, Flex, Layout, Rect};
 
@@ -0,0 +1,37 @@
+use std::sync::LazyLock;
+
+use crate::app::model::popup::commands_help::{Command, CommandPopUp};
+
+pub static OVERVIEW_COMMANDS: LazyLock<Vec<Command<'static>>> = LazyLock::new(|| {
+    vec![
+        Command {
+            name: "Refresh",
+            key_binding: "r",
+            description: "Refresh the status of all environments",
+        },
+        Command {
+            name: "Sort",
+            key_binding: "highlighted letter",
+            description: "Sort by a column, e.g. health to show failing environments first",
+        },
+        Command {
+            name: "Next",
+            key_binding: "j / Down",
+            description: "Move to the next environment",
+        },
+        Command {
+            name: "Previous",
+            key_binding: "k / Up",
+            description: "Move to the previous environment",
+        },
+        Command {
+            name: "Back",
+            key_binding: "Esc / Left",
+            description: "Return to the environment list",
+        },
+    ]
+});
+
+pub fn create_overview_command_popup() -> CommandPopUp<'static> {
+    CommandPopUp::new("Overview Commands".into(), OVERVIEW_COMMANDS.clone())
+}
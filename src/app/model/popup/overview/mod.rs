@@ -0,0 +1 @@
+pub mod commands;
\ No newline at end of file
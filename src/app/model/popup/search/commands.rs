@@ -0,0 +1,57 @@
+use std::sync::LazyLock;
+
+use crate::app::model::popup::commands_help::{Command, CommandPopUp};
+
+pub static SEARCH_COMMANDS: LazyLock<Vec<Command<'static>>> = LazyLock::new(|| {
+    vec![
+        Command {
+            name: "Search",
+            key_binding: "/",
+            description: "Edit the query, matching environment, DAG id and tags",
+        },
+        Command {
+            name: "Open",
+            key_binding: "Enter",
+            description: "Switch to the environment and show the runs of the DAG",
+        },
+        Command {
+            name: "Mark",
+            key_binding: "Shift+M",
+            description: "Mark or unmark the environment of the selected DAG to compare",
+        },
+        Command {
+            name: "Compare",
+            key_binding: "c",
+            description: "Compare the DAG across its marked environments, or all it is listed in",
+        },
+        Command {
+            name: "Refresh",
+            key_binding: "r",
+            description: "Fetch the DAGs of all environments again",
+        },
+        Command {
+            name: "Sort",
+            key_binding: "highlighted letter",
+            description: "Sort by a column, e.g. health to show broken DAGs first",
+        },
+        Command {
+            name: "Next",
+            key_binding: "j / Down",
+            description: "Move to the next DAG",
+        },
+        Command {
+            name: "Previous",
+            key_binding: "k / Up",
+            description: "Move to the previous DAG",
+        },
+        Command {
+            name: "Back",
+            key_binding: "Esc / Left",
+            description: "Return to the environment list",
+        },
+    ]
+});
+
+pub fn create_search_command_popup() -> CommandPopUp<'static> {
+    CommandPopUp::new("Search Commands".into(), SEARCH_COMMANDS.clone())
+}
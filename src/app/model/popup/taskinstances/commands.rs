@@ -12,19 +12,52 @@ pub static TASK_COMMANDS: LazyLock<Vec<Command<'static>>> = LazyLock::new(|| {
         Command {
             name: "Mark",
             key_binding: "m",
-            description: "Mark a task instance",
+            description: "Mark the selected task instances, optionally with upstream, downstream, past or future ones",
+        },
+        Command {
+            name: "Select",
+            key_binding: "M",
+            description: "Select a task instance for marking",
+        },
+        Command {
+            name: "Show",
+            key_binding: "v",
+            description: "Show all fields, rendered template fields and docs of a task instance (Enter for logs)",
+        },
+        Command {
+            name: "Note",
+            key_binding: "n",
+            description: "Edit the note of a task instance in the external editor",
         },
         Command {
             name: "Filter",
             key_binding: "/",
             description: "Filter task instances",
         },
+        Command {
+            name: "Export CSV",
+            key_binding: "x",
+            description: "Export filtered task instances to CSV",
+        },
+        Command {
+            name: "Export JSON",
+            key_binding: "X",
+            description: "Export filtered task instances to JSON",
+        },
     ];
 
     commands.append(&mut DefaultCommands::new().0);
     commands
 });
 
-pub fn create_task_command_popup() -> CommandPopUp<'static> {
-    CommandPopUp::new("Task Commands".into(), TASK_COMMANDS.clone())
+/// Key bindings that change the environment, hidden when it is read-only
+const MUTATING_KEY_BINDINGS: [&str; 4] = ["c", "m", "M", "n"];
+
+pub fn create_task_command_popup(read_only: bool) -> CommandPopUp<'static> {
+    let popup = CommandPopUp::new("Task Commands".into(), TASK_COMMANDS.clone());
+    if read_only {
+        popup.without(&MUTATING_KEY_BINDINGS)
+    } else {
+        popup
+    }
 }
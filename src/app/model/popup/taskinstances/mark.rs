@@ -2,25 +2,44 @@ use crossterm::event::KeyCode;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Flex, Layout, Rect},
-    style::{Modifier, Stylize},
+    style::{Color, Modifier, Stylize},
+    text::{Line, Span},
     widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
 };
 use strum::Display;
 
 use crate::{
+    airflow::model::common::{MarkScope, TaskInstanceReference},
     app::{
         events::custom::FlowrsEvent,
         model::{popup::popup_area, Model},
         worker::WorkerMessage,
     },
-    ui::constants::DEFAULT_STYLE,
+    ui::constants::{DEFAULT_STYLE, RED},
 };
+
+/// The task instances of a DAG run to mark, the state to set and the related task instances
+/// to include
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkRequest {
+    pub dag_id: String,
+    pub dag_run_id: String,
+    pub task_ids: Vec<String>,
+    pub status: MarkState,
+    pub scope: MarkScope,
+}
+
 pub struct MarkTaskInstancePopup {
     pub dag_id: String,
     pub dag_run_id: String,
     pub status: MarkState,
-    pub confirm: bool,
+    pub scope: MarkScope,
     pub marked: Vec<String>,
+    /// The request being previewed, set once Enter is pressed in the options step
+    request: Option<MarkRequest>,
+    /// The task instances the dry run reported, `None` while it is running
+    preview: Option<Result<Vec<TaskInstanceReference>, String>>,
+    scroll: u16,
 }
 
 #[derive(Clone, Debug, PartialEq, Display)]
@@ -38,9 +57,12 @@ impl MarkTaskInstancePopup {
         MarkTaskInstancePopup {
             dag_id: dag_id.to_string(),
             status: MarkState::Success,
-            confirm: false,
+            scope: MarkScope::default(),
             marked,
             dag_run_id: dag_run_id.to_string(),
+            request: None,
+            preview: None,
+            scroll: 0,
         }
     }
 
@@ -59,41 +81,164 @@ impl MarkTaskInstancePopup {
             MarkState::Skipped => MarkState::Failed,
         };
     }
+
+    fn mark_request(&self) -> MarkRequest {
+        MarkRequest {
+            dag_id: self.dag_id.clone(),
+            dag_run_id: self.dag_run_id.clone(),
+            task_ids: self.marked.clone(),
+            status: self.status.clone(),
+            scope: self.scope,
+        }
+    }
+
+    /// Store the dry run result, unless the options changed since it was requested
+    pub fn set_preview(
+        &mut self,
+        request: &MarkRequest,
+        preview: Result<Vec<TaskInstanceReference>, String>,
+    ) {
+        if self.request.as_ref() != Some(request) {
+            return;
+        }
+        // Related task instances of several marked tasks overlap
+        self.preview = Some(preview.map(|mut task_instances| {
+            task_instances
+                .sort_by(|a, b| (&a.dag_run_id, &a.task_id).cmp(&(&b.dag_run_id, &b.task_id)));
+            task_instances.dedup_by(|a, b| a.dag_run_id == b.dag_run_id && a.task_id == b.task_id);
+            task_instances
+        }));
+    }
+
+    fn is_previewed(&self) -> bool {
+        matches!(self.preview, Some(Ok(_)))
+    }
+
+    fn preview_lines(&self) -> Vec<Line<'_>> {
+        match &self.preview {
+            None => vec![Line::from(Span::styled(
+                "Previewing affected task instances…",
+                DEFAULT_STYLE.fg(Color::DarkGray),
+            ))],
+            Some(Err(error)) => vec![
+                Line::from(Span::styled("Preview failed:", DEFAULT_STYLE.fg(RED))),
+                Line::from(Span::styled(error.as_str(), DEFAULT_STYLE.fg(RED))),
+            ],
+            Some(Ok(task_instances)) => {
+                let count = task_instances.len();
+                let mut lines = vec![Line::from(format!(
+                    "{count} task instance{} will be marked {}:",
+                    if count == 1 { "" } else { "s" },
+                    self.status
+                ))];
+                lines.extend(task_instances.iter().map(|task_instance| {
+                    let mut spans =
+                        vec![Span::raw("  "), Span::raw(task_instance.task_id.as_str())];
+                    if let Some(state) = &task_instance.state {
+                        spans.push(Span::styled(
+                            format!(" ({state})"),
+                            DEFAULT_STYLE.fg(Color::DarkGray),
+                        ));
+                    }
+                    if task_instance.dag_run_id != self.dag_run_id {
+                        spans.push(Span::styled(
+                            format!("  {}", task_instance.dag_run_id),
+                            DEFAULT_STYLE.fg(Color::DarkGray),
+                        ));
+                    }
+                    Line::from(spans)
+                }));
+                lines
+            }
+        }
+    }
+
+    fn scope_line(&self) -> Line<'_> {
+        let checkbox = |checked: bool, label: &'static str| {
+            let style = if checked {
+                DEFAULT_STYLE.add_modifier(Modifier::BOLD)
+            } else {
+                DEFAULT_STYLE.fg(Color::DarkGray)
+            };
+            Span::styled(
+                format!("[{}] {label}  ", if checked { "x" } else { " " }),
+                style,
+            )
+        };
+        Line::from(vec![
+            checkbox(self.scope.upstream, "(u)pstream"),
+            checkbox(self.scope.downstream, "(d)ownstream"),
+            checkbox(self.scope.past, "(p)ast"),
+            checkbox(self.scope.future, "(f)uture"),
+        ])
+        .centered()
+    }
 }
 
 impl Model for MarkTaskInstancePopup {
     fn update(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
-        if let FlowrsEvent::Key(key_event) = event {
+        let FlowrsEvent::Key(key_event) = event else {
+            return (Some(event.clone()), vec![]);
+        };
+        if let Some(request) = &self.request {
             match key_event.code {
-                KeyCode::Enter => {
+                // Only mark once the preview showed what will change
+                KeyCode::Enter if self.is_previewed() => {
                     // On Enter, we always return the key event, so the parent can close the popup
                     return (
                         Some(FlowrsEvent::Key(*key_event)),
-                        self.marked
-                            .iter()
-                            .map(|i| WorkerMessage::MarkTaskInstance {
-                                dag_run_id: self.dag_run_id.clone(),
-                                dag_id: self.dag_id.clone(),
-                                task_id: i.clone(),
-                                status: self.status.clone(),
-                            })
-                            .collect(),
+                        vec![WorkerMessage::MarkTaskInstances(request.clone())],
                     );
                 }
-                KeyCode::Char('j' | 'h') | KeyCode::Down | KeyCode::Left => {
-                    self.previous_state();
-                    return (None, vec![]);
-                }
-                KeyCode::Char('k' | 'l') | KeyCode::Up | KeyCode::Right => {
-                    self.next_state();
-                    return (None, vec![]);
-                }
-                KeyCode::Char('q') | KeyCode::Esc => {
-                    // On Esc, we always return the key event, so the parent can close the popup, without clearing the dag run
-                    return (Some(FlowrsEvent::Key(*key_event)), vec![]);
+                KeyCode::Esc => {
+                    // Back to the options
+                    self.request = None;
+                    self.preview = None;
                 }
+                KeyCode::Char('q') => return (Some(FlowrsEvent::Key(*key_event)), vec![]),
+                KeyCode::Char('j') | KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+                KeyCode::Char('k') | KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
                 _ => {}
             }
+            return (None, vec![]);
+        }
+        match key_event.code {
+            KeyCode::Enter => {
+                let request = self.mark_request();
+                self.request = Some(request.clone());
+                self.preview = None;
+                self.scroll = 0;
+                return (None, vec![WorkerMessage::PreviewMarkTaskInstances(request)]);
+            }
+            KeyCode::Char('j' | 'h') | KeyCode::Down | KeyCode::Left => {
+                self.previous_state();
+                return (None, vec![]);
+            }
+            KeyCode::Char('k' | 'l') | KeyCode::Up | KeyCode::Right => {
+                self.next_state();
+                return (None, vec![]);
+            }
+            KeyCode::Char('u') => {
+                self.scope.upstream = !self.scope.upstream;
+                return (None, vec![]);
+            }
+            KeyCode::Char('d') => {
+                self.scope.downstream = !self.scope.downstream;
+                return (None, vec![]);
+            }
+            KeyCode::Char('p') => {
+                self.scope.past = !self.scope.past;
+                return (None, vec![]);
+            }
+            KeyCode::Char('f') => {
+                self.scope.future = !self.scope.future;
+                return (None, vec![]);
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                // On Esc, we always return the key event, so the parent can close the popup, without clearing the dag run
+                return (Some(FlowrsEvent::Key(*key_event)), vec![]);
+            }
+            _ => {}
         }
         (Some(event.clone()), vec![])
     }
@@ -101,30 +246,47 @@ impl Model for MarkTaskInstancePopup {
 
 impl Widget for &mut MarkTaskInstancePopup {
     fn render(self, area: Rect, buffer: &mut Buffer) {
-        let area = popup_area(area, 50, 50);
+        let area = popup_area(area, 60, 60);
 
-        let [_, header, options, _] = Layout::vertical([
-            Constraint::Length(2),
+        let [_, header, options, scope, preview] = Layout::vertical([
+            Constraint::Length(1),
             Constraint::Length(3),
             Constraint::Length(3),
+            Constraint::Length(2),
             Constraint::Min(1),
         ])
         .flex(Flex::Center)
         .areas(area);
 
+        let keys = if self.is_previewed() {
+            "[Enter] Mark  [j/k] Scroll  [Esc] Back"
+        } else if self.request.is_some() {
+            "[j/k] Scroll  [Esc] Back"
+        } else {
+            "[Enter] Preview  [h/l] State  [u/d/p/f] Scope  [Esc] Cancel"
+        };
         let popup_block = Block::default()
             .border_type(BorderType::Rounded)
             .borders(Borders::ALL)
             .title("Mark Task Instance")
+            .title_bottom(Line::from(Span::styled(
+                keys,
+                DEFAULT_STYLE.fg(Color::DarkGray),
+            )))
             .border_style(DEFAULT_STYLE)
             .style(DEFAULT_STYLE)
             .title_style(DEFAULT_STYLE.add_modifier(Modifier::BOLD));
 
-        let text = Paragraph::new("Select the status to mark this TaskInstance with:")
-            .style(DEFAULT_STYLE)
-            .block(Block::default().border_type(BorderType::Rounded))
-            .centered()
-            .wrap(Wrap { trim: true });
+        let count = self.marked.len();
+        let text = Paragraph::new(if count == 1 {
+            "Select the status to mark this TaskInstance with:".to_string()
+        } else {
+            format!("Select the status to mark these {count} TaskInstances with:")
+        })
+        .style(DEFAULT_STYLE)
+        .block(Block::default().border_type(BorderType::Rounded))
+        .centered()
+        .wrap(Wrap { trim: true });
 
         let [_, success, _, failed, _, queued, _] = Layout::horizontal([
             Constraint::Fill(1),
@@ -182,5 +344,102 @@ impl Widget for &mut MarkTaskInstancePopup {
         success_text.render(success, buffer);
         failed_text.render(failed, buffer);
         queued_text.render(queued, buffer);
+        Paragraph::new(self.scope_line()).render(scope, buffer);
+        if self.request.is_some() {
+            let [_, preview, _] = Layout::horizontal([
+                Constraint::Length(2),
+                Constraint::Fill(1),
+                Constraint::Length(2),
+            ])
+            .areas(preview);
+            let [preview, _] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(preview);
+            Paragraph::new(self.preview_lines())
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0))
+                .render(preview, buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    fn key(code: KeyCode) -> FlowrsEvent {
+        FlowrsEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn task_instance(dag_run_id: &str, task_id: &str) -> TaskInstanceReference {
+        TaskInstanceReference {
+            task_id: task_id.to_string(),
+            dag_id: "etl".to_string(),
+            dag_run_id: dag_run_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_mark_with_scope_after_preview() {
+        let mut popup = MarkTaskInstancePopup::new(
+            vec!["extract".to_string(), "load".to_string()],
+            "etl",
+            "manual__1",
+        );
+        popup.update(&key(KeyCode::Char('l')));
+        popup.update(&key(KeyCode::Char('d')));
+        popup.update(&key(KeyCode::Char('f')));
+
+        // Enter previews without closing the popup
+        let (event, messages) = popup.update(&key(KeyCode::Enter));
+        assert!(event.is_none());
+        let [WorkerMessage::PreviewMarkTaskInstances(request)] = messages.as_slice() else {
+            panic!("expected a preview, got {messages:?}");
+        };
+        assert_eq!(request.status, MarkState::Failed);
+        assert_eq!(
+            request.scope,
+            MarkScope {
+                downstream: true,
+                future: true,
+                ..Default::default()
+            }
+        );
+
+        // Overlapping downstream tasks are listed once
+        popup.set_preview(
+            request,
+            Ok(vec![
+                task_instance("manual__1", "load"),
+                task_instance("manual__1", "extract"),
+                task_instance("manual__1", "load"),
+                task_instance("manual__2", "load"),
+            ]),
+        );
+        assert!(matches!(&popup.preview, Some(Ok(task_instances)) if task_instances.len() == 3));
+
+        // Esc goes back to the options, and a stale preview is ignored
+        popup.update(&key(KeyCode::Esc));
+        let stale = request.clone();
+        popup.update(&key(KeyCode::Char('u')));
+        popup.update(&key(KeyCode::Enter));
+        popup.set_preview(&stale, Ok(vec![]));
+        assert!(popup.preview.is_none());
+
+        // Enter is ignored until the preview loaded without error
+        assert!(popup.update(&key(KeyCode::Enter)).1.is_empty());
+        let request = popup.request.clone().unwrap();
+        popup.set_preview(&request, Err("timeout".to_string()));
+        assert!(popup.update(&key(KeyCode::Enter)).1.is_empty());
+        popup.set_preview(&request, Ok(vec![task_instance("manual__1", "extract")]));
+
+        let (event, messages) = popup.update(&key(KeyCode::Enter));
+        assert!(event.is_some());
+        let [WorkerMessage::MarkTaskInstances(request)] = messages.as_slice() else {
+            panic!("expected marking, got {messages:?}");
+        };
+        assert_eq!(request.task_ids, vec!["extract", "load"]);
+        assert!(request.scope.upstream && request.scope.downstream);
     }
 }
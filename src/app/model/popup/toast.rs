@@ -0,0 +1,74 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::ui::constants::DEFAULT_STYLE;
+
+/// Number of ticks a toast stays visible (ticks are 200ms, so ~5 seconds)
+const TOAST_DURATION_TICKS: u32 = 25;
+
+/// A short-lived, non-blocking notification rendered in the bottom-right corner
+/// Unlike popups it does not capture key events and disappears on its own
+pub struct Toast {
+    pub message: String,
+    ticks_remaining: u32,
+}
+
+impl Toast {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ticks_remaining: TOAST_DURATION_TICKS,
+        }
+    }
+
+    /// Advance the toast by one tick - returns false once it has expired
+    pub fn tick(&mut self) -> bool {
+        self.ticks_remaining = self.ticks_remaining.saturating_sub(1);
+        self.ticks_remaining > 0
+    }
+}
+
+/// Tick an optional toast, clearing it once expired
+pub fn tick_toast(toast: &mut Option<Toast>) {
+    if let Some(t) = toast {
+        if !t.tick() {
+            *toast = None;
+        }
+    }
+}
+
+impl Widget for &Toast {
+    #[allow(clippy::cast_possible_truncation)]
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Size to the message, capped to the available area (2 for borders)
+        let width = (self.message.chars().count() as u16 + 4).max(10).min(area.width);
+        let inner_width = width.saturating_sub(2).max(1);
+        let lines = (self.message.chars().count() as u16).div_ceil(inner_width).max(1);
+        let height = (lines + 2).min(area.height);
+
+        let toast_area = Rect {
+            x: area.x + area.width.saturating_sub(width + 1),
+            y: area.y + area.height.saturating_sub(height + 1),
+            width,
+            height,
+        };
+
+        Clear.render(toast_area, buf);
+
+        let block = Block::default()
+            .border_type(BorderType::Rounded)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green))
+            .style(DEFAULT_STYLE);
+
+        Paragraph::new(Line::from(self.message.as_str()))
+            .wrap(Wrap { trim: false })
+            .block(block)
+            .render(toast_area, buf);
+    }
+}
@@ -0,0 +1,472 @@
+use std::collections::BTreeSet;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, BorderType, Borders, Paragraph, Row, StatefulWidget, Table, Widget, Wrap,
+};
+
+use crate::airflow::model::common::Dag;
+use crate::app::events::custom::FlowrsEvent;
+use crate::app::worker::WorkerMessage;
+use crate::ui::constants::{ALTERNATING_ROW_COLOR, DEFAULT_STYLE, HEADER_STYLE, MARKED_COLOR, RED};
+
+use super::filter::Filter;
+use super::popup::commands_help::CommandPopUp;
+use super::popup::search::commands::create_search_command_popup;
+use super::sortable_table::{CustomSort, SortableTable};
+use super::{handle_command_popup_events, Model, HALF_PAGE_SIZE};
+
+/// A DAG found in one of the configured environments
+#[derive(Debug, Clone, PartialEq)]
+pub struct DagSearchResult {
+    pub environment: String,
+    pub dag_id: String,
+    pub is_paused: bool,
+    pub has_import_errors: bool,
+    pub tags: Vec<String>,
+}
+
+impl DagSearchResult {
+    pub fn from_dag(environment: &str, dag: Dag) -> Self {
+        Self {
+            environment: environment.to_string(),
+            dag_id: dag.dag_id,
+            is_paused: dag.is_paused,
+            has_import_errors: dag.has_import_errors,
+            tags: dag.tags.into_iter().map(|tag| tag.name).collect(),
+        }
+    }
+
+    fn label(&self) -> String {
+        format!("{} / {}", self.environment, self.dag_id)
+    }
+
+    fn health(&self) -> &'static str {
+        if self.has_import_errors {
+            "import errors"
+        } else {
+            "ok"
+        }
+    }
+
+    /// Every whitespace separated term of the query must appear in the label or a tag (case insensitive)
+    fn matches(&self, query: &str) -> bool {
+        let label = self.label().to_lowercase();
+        query.to_lowercase().split_whitespace().all(|term| {
+            label.contains(term)
+                || self
+                    .tags
+                    .iter()
+                    .any(|tag| tag.to_lowercase().contains(term))
+        })
+    }
+}
+
+impl CustomSort for DagSearchResult {
+    fn comparator(column_index: usize) -> Option<fn(&Self, &Self) -> std::cmp::Ordering> {
+        match column_index {
+            1 => Some(|a, b| a.is_paused.cmp(&b.is_paused)),
+            2 => Some(|a, b| b.has_import_errors.cmp(&a.has_import_errors)),
+            _ => None,
+        }
+    }
+
+    fn column_value(&self, column_index: usize) -> String {
+        match column_index {
+            0 => self.label(),
+            3 => self.tags.join(", "),
+            _ => String::new(),
+        }
+    }
+}
+
+pub struct SearchModel {
+    /// DAGs of every environment that has answered so far
+    pub all: Vec<DagSearchResult>,
+    pub results: SortableTable<DagSearchResult>,
+    pub filter: Filter,
+    /// Environments whose DAGs are still being fetched
+    pub pending: Vec<String>,
+    pub errors: Vec<String>,
+    /// Environment and DAG id of the results marked to compare
+    pub marked: BTreeSet<(String, String)>,
+    pub commands: Option<CommandPopUp<'static>>,
+}
+
+impl SearchModel {
+    pub fn new() -> Self {
+        let headers = ["DAG", "Paused", "Health", "Tags"];
+        // Reserved keys: j/k (scroll), c (compare), r (refresh), / (search), ? (help), q (quit)
+        let reserved = &['j', 'k', 'c', 'r', '/', '?', 'q'];
+        SearchModel {
+            all: vec![],
+            results: SortableTable::new(&headers, vec![], reserved),
+            filter: Filter::new(),
+            pending: vec![],
+            errors: vec![],
+            marked: BTreeSet::new(),
+            commands: None,
+        }
+    }
+
+    /// Start fetching the DAGs of the given environments, opening the search input.
+    /// The query of a previous search is kept so it can be refined.
+    pub fn reset(&mut self, environments: Vec<String>) {
+        self.all.clear();
+        self.errors.clear();
+        self.marked.clear();
+        self.pending = environments;
+        self.filter.enabled = true;
+        self.filter_results();
+    }
+
+    pub fn add_environment_results(&mut self, environment: &str, dags: Vec<Dag>) {
+        self.pending.retain(|name| name != environment);
+        self.all.retain(|result| result.environment != environment);
+        self.all.extend(
+            dags.into_iter()
+                .map(|dag| DagSearchResult::from_dag(environment, dag)),
+        );
+        self.filter_results();
+    }
+
+    pub fn add_environment_error(&mut self, environment: &str, error: &str) {
+        self.pending.retain(|name| name != environment);
+        self.errors.push(format!("{environment}: {error}"));
+    }
+
+    pub fn filter_results(&mut self) {
+        let query = self.filter.prefix().cloned().unwrap_or_default();
+        let mut results: Vec<DagSearchResult> = self
+            .all
+            .iter()
+            .filter(|result| result.matches(&query))
+            .cloned()
+            .collect();
+        // Keep the same DAG of different environments next to each other
+        results.sort_by(|a, b| (&a.dag_id, &a.environment).cmp(&(&b.dag_id, &b.environment)));
+        self.results.items = results;
+        self.results.reapply_sort();
+    }
+
+    pub fn selected(&self) -> Option<&DagSearchResult> {
+        self.results
+            .state
+            .selected()
+            .and_then(|idx| self.results.items.get(idx))
+    }
+
+    fn is_marked(&self, result: &DagSearchResult) -> bool {
+        self.marked
+            .contains(&(result.environment.clone(), result.dag_id.clone()))
+    }
+
+    /// Mark or unmark the environment of the selected result for a comparison
+    fn toggle_mark(&mut self) {
+        if let Some(result) = self.selected() {
+            let key = (result.environment.clone(), result.dag_id.clone());
+            if !self.marked.remove(&key) {
+                self.marked.insert(key);
+            }
+        }
+    }
+
+    /// Compare the selected DAG across its marked environments,
+    /// or every environment it is listed in when none are marked
+    fn compare_selected(&self) -> Option<WorkerMessage> {
+        let dag_id = &self.selected()?.dag_id;
+        let listed = self
+            .results
+            .items
+            .iter()
+            .filter(|result| &result.dag_id == dag_id);
+        let environments = if listed.clone().any(|result| self.is_marked(result)) {
+            listed
+                .filter(|result| self.is_marked(result))
+                .map(|result| result.environment.clone())
+                .collect()
+        } else {
+            listed.map(|result| result.environment.clone()).collect()
+        };
+        Some(WorkerMessage::CompareDag {
+            dag_id: dag_id.clone(),
+            environments,
+        })
+    }
+
+    fn title(&self) -> String {
+        let loading = if self.pending.is_empty() {
+            String::new()
+        } else {
+            format!(", waiting for {} environment(s)", self.pending.len())
+        };
+        format!(
+            " Search - {} of {} DAGs{loading} - press <?> for help ",
+            self.results.items.len(),
+            self.all.len()
+        )
+    }
+}
+
+impl Default for SearchModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Model for SearchModel {
+    fn update(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
+        match event {
+            FlowrsEvent::Tick => (Some(FlowrsEvent::Tick), vec![]),
+            FlowrsEvent::Key(key_event) => {
+                if self.filter.is_enabled() {
+                    self.filter.update(key_event);
+                    self.filter_results();
+                    return (None, vec![]);
+                }
+                if self.commands.is_some() {
+                    return handle_command_popup_events(&mut self.commands, key_event);
+                }
+                if key_event.modifiers == KeyModifiers::CONTROL {
+                    match key_event.code {
+                        KeyCode::Char('d') => {
+                            self.results.scroll_by(HALF_PAGE_SIZE.cast_signed());
+                            return (None, vec![]);
+                        }
+                        KeyCode::Char('u') => {
+                            self.results.scroll_by(-HALF_PAGE_SIZE.cast_signed());
+                            return (None, vec![]);
+                        }
+                        _ => {}
+                    }
+                }
+                match key_event.code {
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        self.results.scroll_by(1);
+                        (None, vec![])
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        self.results.scroll_by(-1);
+                        (None, vec![])
+                    }
+                    KeyCode::Char('/') => {
+                        self.filter.toggle();
+                        (None, vec![])
+                    }
+                    KeyCode::Char('r') => (None, vec![WorkerMessage::SearchDags]),
+                    KeyCode::Char('M') => {
+                        self.toggle_mark();
+                        (None, vec![])
+                    }
+                    KeyCode::Char('c') => match self.compare_selected() {
+                        Some(message) => (None, vec![message]),
+                        None => (None, vec![]),
+                    },
+                    KeyCode::Char('?') => {
+                        self.commands = Some(create_search_command_popup());
+                        (None, vec![])
+                    }
+                    KeyCode::Char(c)
+                        if key_event.modifiers == KeyModifiers::NONE
+                            && self.results.handle_key(c) =>
+                    {
+                        (None, vec![])
+                    }
+                    KeyCode::Enter | KeyCode::Right => match self.selected() {
+                        Some(result) => (
+                            None,
+                            vec![WorkerMessage::OpenDagInEnvironment {
+                                environment: result.environment.clone(),
+                                dag_id: result.dag_id.clone(),
+                            }],
+                        ),
+                        None => (None, vec![]),
+                    },
+                    _ => (Some(event.clone()), vec![]),
+                }
+            }
+            FlowrsEvent::Mouse => (Some(event.clone()), vec![]),
+        }
+    }
+}
+
+impl Widget for &mut SearchModel {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        #[allow(clippy::cast_possible_truncation)]
+        let error_height = if self.errors.is_empty() {
+            0
+        } else {
+            (self.errors.len() as u16 + 2).min(8)
+        };
+        let filter_height = if self.filter.is_enabled() { 3 } else { 0 };
+        let [table_area, error_area, filter_area] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(error_height),
+            Constraint::Length(filter_height),
+        ])
+        .areas(area);
+
+        let header_row = self.results.render_headers(HEADER_STYLE, RED);
+        let header = Row::new(header_row).style(HEADER_STYLE);
+
+        let alert_style = Style::default().fg(Color::Red);
+        let ok_style = Style::default().fg(Color::Green);
+        let rows = self.results.items.iter().enumerate().map(|(idx, item)| {
+            Row::new(vec![
+                Line::from(vec![
+                    Span::styled(item.environment.as_str(), Style::default().fg(Color::Cyan)),
+                    Span::raw(" / "),
+                    Span::raw(item.dag_id.as_str()),
+                ]),
+                Line::from(if item.is_paused { "paused" } else { "active" }),
+                Line::from(Span::styled(
+                    item.health(),
+                    if item.has_import_errors {
+                        alert_style
+                    } else {
+                        ok_style
+                    },
+                )),
+                Line::from(item.tags.join(", ")),
+            ])
+            .style(if self.is_marked(item) {
+                DEFAULT_STYLE.bg(MARKED_COLOR)
+            } else if (idx % 2) == 0 {
+                DEFAULT_STYLE
+            } else {
+                DEFAULT_STYLE.bg(ALTERNATING_ROW_COLOR)
+            })
+        });
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Percentage(50),
+                Constraint::Length(8),
+                Constraint::Length(14),
+                Constraint::Percentage(30),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .border_type(BorderType::Rounded)
+                .borders(Borders::ALL)
+                .title(self.title()),
+        )
+        .style(DEFAULT_STYLE)
+        .row_highlight_style(crate::ui::constants::SELECTED_STYLE);
+        StatefulWidget::render(table, table_area, buf, &mut self.results.state);
+
+        if !self.errors.is_empty() {
+            let errors: Vec<Line> = self.errors.iter().map(|e| Line::from(e.as_str())).collect();
+            Paragraph::new(errors)
+                .wrap(Wrap { trim: true })
+                .style(DEFAULT_STYLE)
+                .block(
+                    Block::default()
+                        .border_type(BorderType::Rounded)
+                        .borders(Borders::ALL)
+                        .title(" Errors "),
+                )
+                .render(error_area, buf);
+        }
+
+        if self.filter.is_enabled() {
+            self.filter.render(filter_area, buf);
+        }
+
+        if let Some(commands) = &mut self.commands {
+            commands.render(area, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airflow::model::common::dag::Tag;
+
+    fn dag(dag_id: &str, tags: &[&str]) -> Dag {
+        Dag {
+            dag_id: dag_id.to_string(),
+            tags: tags
+                .iter()
+                .map(|name| Tag {
+                    name: (*name).to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_results_are_filtered_across_environments() {
+        let mut model = SearchModel::new();
+        model.reset(vec!["prod".to_string(), "dev".to_string()]);
+        model.add_environment_results(
+            "prod",
+            vec![dag("load_sales", &["finance"]), dag("export", &[])],
+        );
+        model.add_environment_results("dev", vec![dag("load_sales", &[])]);
+        assert!(model.pending.is_empty());
+
+        model.filter.prefix = Some("sales".to_string());
+        model.filter_results();
+        let labels: Vec<String> = model
+            .results
+            .items
+            .iter()
+            .map(DagSearchResult::label)
+            .collect();
+        assert_eq!(labels, vec!["dev / load_sales", "prod / load_sales"]);
+
+        // Terms can match the environment and tags, in any order
+        model.filter.prefix = Some("FINANCE prod".to_string());
+        model.filter_results();
+        assert_eq!(model.results.items.len(), 1);
+        assert_eq!(model.selected().unwrap().environment, "prod");
+    }
+
+    #[test]
+    fn test_refreshing_an_environment_replaces_its_results() {
+        let mut model = SearchModel::new();
+        model.reset(vec!["prod".to_string(), "dev".to_string()]);
+        model.add_environment_results("prod", vec![dag("a", &[]), dag("b", &[])]);
+        model.add_environment_error("dev", "connection refused");
+        assert_eq!(model.errors, vec!["dev: connection refused"]);
+
+        model.add_environment_results("prod", vec![dag("a", &[])]);
+        assert_eq!(model.all.len(), 1);
+        assert_eq!(model.results.items.len(), 1);
+    }
+
+    #[test]
+    fn test_compare_only_the_marked_environments() {
+        let mut model = SearchModel::new();
+        model.reset(vec![
+            "prod".to_string(),
+            "acc".to_string(),
+            "dev".to_string(),
+        ]);
+        for environment in ["prod", "acc", "dev"] {
+            model.add_environment_results(environment, vec![dag("etl", &[])]);
+        }
+        let compared = |model: &SearchModel| match model.compare_selected() {
+            Some(WorkerMessage::CompareDag { environments, .. }) => environments,
+            _ => panic!("expected a comparison"),
+        };
+
+        model.results.state.select(Some(0));
+        assert_eq!(compared(&model), vec!["acc", "dev", "prod"]);
+
+        model.toggle_mark();
+        model.results.state.select(Some(2));
+        model.toggle_mark();
+        assert_eq!(compared(&model), vec!["acc", "prod"]);
+    }
+}
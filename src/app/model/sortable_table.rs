@@ -1,11 +1,13 @@
 use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::TableState;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
 /// Direction of sorting
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortDirection {
     Ascending,
     Descending,
@@ -50,6 +52,11 @@ pub trait CustomSort {
     
     /// Extract the string value for a column (for default sorting)
     fn column_value(&self, column_index: usize) -> String;
+
+    /// Pinned items stay at the top of the table, whatever the sort
+    fn pinned(&self) -> bool {
+        false
+    }
 }
 
 /// A sortable table that automatically handles column sorting
@@ -108,6 +115,19 @@ impl<T> SortableTable<T> {
         }
     }
     
+    /// Sort by a column as if the user had selected it, e.g. when restoring a session
+    /// Ignored for unknown columns
+    pub fn set_sort(&mut self, column_index: usize, direction: SortDirection)
+    where
+        T: CustomSort,
+    {
+        if column_index < self.columns.len() && direction != SortDirection::None {
+            self.sort_column = Some(column_index);
+            self.sort_direction = direction;
+            self.apply_sort();
+        }
+    }
+    
     /// Render headers with sort keys highlighted
     pub fn render_headers(&self, header_style: Style, red_color: ratatui::style::Color) -> Vec<Line<'static>> {
         self.columns.iter().enumerate().map(|(idx, col)| {
@@ -217,8 +237,19 @@ impl<T> SortableTable<T> {
         }
     }
     
-    /// Apply current sort configuration
+    /// Apply current sort configuration, then move pinned items to the top
     fn apply_sort(&mut self)
+    where
+        T: CustomSort,
+    {
+        self.sort_by_column();
+        if self.items.iter().any(CustomSort::pinned) {
+            // Stable, so pinned and other items keep their sorted order
+            self.items.sort_by_key(|item| !item.pinned());
+        }
+    }
+
+    fn sort_by_column(&mut self)
     where
         T: CustomSort,
     {
@@ -256,6 +287,28 @@ impl<T> SortableTable<T> {
         }
     }
     
+    /// Render the current rows (in their filtered and sorted order) as CSV
+    /// Uses the column headers as the header row and `column_value` for each cell
+    pub fn to_csv(&self) -> String
+    where
+        T: CustomSort,
+    {
+        let mut csv = String::new();
+        let header: Vec<String> = self.columns.iter().map(|c| escape_csv_field(&c.name)).collect();
+        csv.push_str(&header.join(","));
+        csv.push('\n');
+
+        for item in &self.items {
+            let row: Vec<String> = (0..self.columns.len())
+                .map(|col_idx| escape_csv_field(&item.column_value(col_idx)))
+                .collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
     /// Scroll by delta rows (reused from StatefulTable)
     pub fn scroll_by(&mut self, delta: isize) {
         if self.items.is_empty() {
@@ -275,6 +328,15 @@ impl<T> SortableTable<T> {
     }
 }
 
+/// Quote a CSV field if it contains a delimiter, quote or line break (RFC 4180)
+fn escape_csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 /// Automatically assign sort keys to columns, avoiding conflicts and reserved keys
 /// Algorithm:
 /// 1. Try first letter of each column (lowercase)
@@ -493,4 +555,50 @@ mod tests {
         table.ensure_valid_selection();
         assert_eq!(table.state.selected(), Some(0));
     }
+
+    #[test]
+    fn test_to_csv_uses_current_order_and_escapes_fields() {
+        let items = vec![
+            TestItem { value: "b, with comma".to_string() },
+            TestItem { value: "a \"quoted\"".to_string() },
+        ];
+        let mut table: SortableTable<TestItem> = SortableTable::new(&["Value"], items, &[]);
+
+        // Sort ascending on the only column
+        table.handle_key('v');
+
+        assert_eq!(
+            table.to_csv(),
+            "value\n\"a \"\"quoted\"\"\"\n\"b, with comma\"\n"
+        );
+    }
+    struct PinnedItem {
+        value: &'static str,
+        pinned: bool,
+    }
+
+    impl CustomSort for PinnedItem {
+        fn column_value(&self, _column_index: usize) -> String {
+            self.value.to_string()
+        }
+
+        fn pinned(&self) -> bool {
+            self.pinned
+        }
+    }
+
+    #[test]
+    fn test_pinned_items_stay_on_top() {
+        let item = |value, pinned| PinnedItem { value, pinned };
+        let items = vec![item("d", false), item("c", true), item("b", false), item("a", true)];
+        let mut table = SortableTable::new(&["Value"], items, &[]);
+
+        table.set_sort(0, SortDirection::Ascending);
+        let values: Vec<&str> = table.items.iter().map(|i| i.value).collect();
+        assert_eq!(values, vec!["a", "c", "b", "d"]);
+
+        table.set_sort(0, SortDirection::Descending);
+        let values: Vec<&str> = table.items.iter().map(|i| i.value).collect();
+        assert_eq!(values, vec!["c", "a", "d", "b"]);
+    }
 }
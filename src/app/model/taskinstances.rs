@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 use std::vec;
 
+use super::note::{render_note, split_note_area, visible_note, NOTE_ICON};
 use super::popup::commands_help::CommandPopUp;
 use super::popup::error::ErrorPopup;
 use super::popup::taskinstances::commands::create_task_command_popup;
+use super::popup::toast::{tick_toast, Toast};
 use crossterm::event::{KeyCode, KeyModifiers};
 use log::debug;
 use ratatui::buffer::Buffer;
@@ -16,14 +18,15 @@ use crate::airflow::graph_layout::GraphPrefix;
 use crate::airflow::model::common::TaskInstance;
 use crate::app::events::custom::FlowrsEvent;
 use crate::ui::common::format_duration_seconds;
-use crate::ui::constants::{AirflowStateColor, ALTERNATING_ROW_COLOR, CYAN, DEFAULT_STYLE, HEADER_STYLE, MARKED_COLOR, RED};
+use crate::ui::constants::{AirflowStateColor, ALTERNATING_ROW_COLOR, CYAN, DEFAULT_STYLE, HEADER_STYLE, MARKED_COLOR, RED, YELLOW};
 
 use super::popup::taskinstances::clear::ClearTaskInstancePopup;
 use super::popup::taskinstances::mark::MarkTaskInstancePopup;
 use super::popup::taskinstances::TaskInstancePopUp;
 use super::sortable_table::{CustomSort, SortableTable};
 use super::{filter::Filter, handle_command_popup_events, Model, HALF_PAGE_SIZE};
-use crate::app::worker::{OpenItem, WorkerMessage};
+use crate::app::export::{export_table, ExportFormat};
+use crate::app::worker::{NoteTarget, OpenItem, WorkerMessage};
 use std::cmp::Ordering;
 
 // Implement CustomSort for TaskInstance
@@ -104,6 +107,9 @@ pub struct TaskInstanceModel {
     pub marked: Vec<usize>,
     commands: Option<CommandPopUp<'static>>,
     pub error_popup: Option<ErrorPopup>,
+    pub toast: Option<Toast>,
+    /// Disables the actions that change the environment
+    pub read_only: bool,
     pub graph_layout: HashMap<String, GraphPrefix>,
     ticks: u32,
     event_buffer: Vec<FlowrsEvent>,
@@ -112,8 +118,8 @@ pub struct TaskInstanceModel {
 impl TaskInstanceModel {
     pub fn new() -> Self {
         let headers = ["Graph", "Task ID", "Duration", "State", "Tries"];
-        // Reserved keys: j/k (scroll), g/G (jump), m (mark), c (clear), o (open), ? (help), / (filter)
-        let reserved = &['j', 'k', 'g', 'G', 'm', 'c', 'o', '?', '/'];
+        // Reserved keys: j/k (scroll), g/G (jump), m (mark), c (clear), n (note), o (open), v (details), x (export), ? (help), / (filter)
+        let reserved = &['j', 'k', 'g', 'G', 'm', 'c', 'n', 'o', 'v', 'x', '?', '/'];
         TaskInstanceModel {
             dag_id: None,
             dag_run_id: None,
@@ -124,6 +130,8 @@ impl TaskInstanceModel {
             marked: vec![],
             commands: None,
             error_popup: None,
+            toast: None,
+            read_only: false,
             graph_layout: HashMap::new(),
             ticks: 0,
             event_buffer: vec![],
@@ -153,6 +161,34 @@ impl TaskInstanceModel {
             .selected()
             .map(|i| &mut self.filtered.items[i])
     }
+    /// Export the filtered task instances and report the file path in a toast
+    fn export_task_instances(&mut self, format: ExportFormat) {
+        let name = format!(
+            "taskinstances_{}_{}",
+            self.dag_id.as_deref().unwrap_or_default(),
+            self.dag_run_id.as_deref().unwrap_or_default()
+        );
+        match export_table(&self.filtered, &name, format) {
+            Ok(filepath) => {
+                self.toast = Some(Toast::new(format!("Exported to {}", filepath.display())));
+            }
+            Err(e) => {
+                self.error_popup = Some(ErrorPopup::from_strings(vec![
+                    "Failed to export task instances:".into(),
+                    e.to_string(),
+                ]));
+            }
+        }
+    }
+
+    pub fn set_note(&mut self, task_id: &str, note: Option<&str>) {
+        self.all
+            .iter_mut()
+            .chain(self.filtered.items.iter_mut())
+            .filter(|task_instance| task_instance.task_id == task_id)
+            .for_each(|task_instance| task_instance.note = note.map(str::to_string));
+    }
+
     pub fn mark_task_instance(&mut self, task_id: &str, status: &str) {
         self.filtered.items.iter_mut().for_each(|task_instance| {
             if task_instance.task_id == task_id {
@@ -173,6 +209,7 @@ impl Model for TaskInstanceModel {
         match event {
             FlowrsEvent::Tick => {
                 self.ticks += 1;
+                tick_toast(&mut self.toast);
                 // No automatic refresh - use 'r' key to refresh manually
                 (Some(FlowrsEvent::Tick), vec![])
             }
@@ -273,9 +310,11 @@ impl Model for TaskInstanceModel {
                                 self.event_buffer.push(FlowrsEvent::Key(*key_event));
                             }
                         }
-                        KeyCode::Char('m') => {
+                        KeyCode::Char('m') if !self.read_only => {
                             if let Some(index) = self.filtered.state.selected() {
-                                self.marked.push(index);
+                                if !self.marked.contains(&index) {
+                                    self.marked.push(index);
+                                }
 
                                 let dag_id = self.current().unwrap().dag_id.clone();
                                 let dag_run_id = self.current().unwrap().dag_run_id.clone();
@@ -291,7 +330,7 @@ impl Model for TaskInstanceModel {
                                     )));
                             }
                         }
-                        KeyCode::Char('M') => {
+                        KeyCode::Char('M') if !self.read_only => {
                             if let Some(index) = self.filtered.state.selected() {
                                 if self.marked.contains(&index) {
                                     self.marked.retain(|&i| i != index);
@@ -300,7 +339,7 @@ impl Model for TaskInstanceModel {
                                 }
                             }
                         }
-                        KeyCode::Char('c') => {
+                        KeyCode::Char('c') if !self.read_only => {
                             if let Some(task_instance) = self.current() {
                                 self.popup =
                                     Some(TaskInstancePopUp::Clear(ClearTaskInstancePopup::new(
@@ -310,8 +349,14 @@ impl Model for TaskInstanceModel {
                                     )));
                             }
                         }
+                        KeyCode::Char('x') => {
+                            self.export_task_instances(ExportFormat::Csv);
+                        }
+                        KeyCode::Char('X') => {
+                            self.export_task_instances(ExportFormat::Json);
+                        }
                         KeyCode::Char('?') => {
-                            self.commands = Some(create_task_command_popup());
+                            self.commands = Some(create_task_command_popup(self.read_only));
                         }
                         KeyCode::Char('/') => {
                             self.filter.toggle();
@@ -335,6 +380,34 @@ impl Model for TaskInstanceModel {
                                 );
                             }
                         }
+                        KeyCode::Char('v') => {
+                            if let Some(task_instance) = self.current() {
+                                return (
+                                    None,
+                                    vec![WorkerMessage::GetTaskInstanceDetail {
+                                        dag_id: task_instance.dag_id.clone(),
+                                        dag_run_id: task_instance.dag_run_id.clone(),
+                                        task_id: task_instance.task_id.clone(),
+                                        map_index: task_instance.map_index,
+                                    }],
+                                );
+                            }
+                        }
+                        KeyCode::Char('n') if !self.read_only => {
+                            if let Some(task_instance) = self.current() {
+                                return (
+                                    None,
+                                    vec![WorkerMessage::EditNote {
+                                        target: NoteTarget::TaskInstance {
+                                            dag_id: task_instance.dag_id.clone(),
+                                            dag_run_id: task_instance.dag_run_id.clone(),
+                                            task_id: task_instance.task_id.clone(),
+                                        },
+                                        note: task_instance.note.clone(),
+                                    }],
+                                );
+                            }
+                        }
                         KeyCode::Char('o') => {
                             if let Some(task_instance) = self.current() {
                                 return (
@@ -391,6 +464,13 @@ impl Widget for &mut TaskInstanceModel {
                 .split(area)
         };
 
+        // The note of the selected task is shown below the table
+        let note = self.current().and_then(|task_instance| visible_note(task_instance.note.as_ref())).map(str::to_string);
+        let (table_area, note_area) = split_note_area(rects[0], note.as_deref());
+        if let (Some(note), Some(note_area)) = (&note, note_area) {
+            render_note(note, note_area, buffer);
+        }
+
         let selected_style = crate::ui::constants::SELECTED_STYLE;
 
         // Automatically generated sortable headers
@@ -432,7 +512,13 @@ impl Widget for &mut TaskInstanceModel {
             
             Row::new(vec![
                 graph_line,
-                Line::from(item.task_id.as_str()),
+                Line::from(vec![
+                    Span::raw(item.task_id.as_str()),
+                    Span::styled(
+                        if visible_note(item.note.as_ref()).is_some() { format!(" {NOTE_ICON}") } else { String::new() },
+                        DEFAULT_STYLE.fg(YELLOW),
+                    ),
+                ]),
                 Line::from(format_duration_seconds(item.duration)),
                 Line::from(Span::styled(state_text, DEFAULT_STYLE.fg(state_color))),
                 Line::from(format!("{:?}", item.try_number)),
@@ -482,7 +568,7 @@ impl Widget for &mut TaskInstanceModel {
         .style(DEFAULT_STYLE)
         .row_highlight_style(selected_style);
 
-        StatefulWidget::render(t, rects[0], buffer, &mut self.filtered.state);
+        StatefulWidget::render(t, table_area, buffer, &mut self.filtered.state);
 
         match &mut self.popup {
             Some(TaskInstancePopUp::Clear(popup)) => {
@@ -498,6 +584,10 @@ impl Widget for &mut TaskInstanceModel {
             commands.render(area, buffer);
         }
 
+        if let Some(toast) = &self.toast {
+            toast.render(area, buffer);
+        }
+
         if let Some(error_popup) = &self.error_popup {
             error_popup.render(area, buffer);
         }
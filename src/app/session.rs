@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::app::model::filter::Filter;
+use crate::app::model::logs::LogLevel;
+use crate::app::model::sortable_table::{CustomSort, SortDirection, SortableTable};
+use crate::app::state::{App, Panel};
+
+/// The panels a session can be restored to, in navigation order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionPanel {
+    Dag,
+    DagRun,
+    TaskInstance,
+    Logs,
+}
+
+impl SessionPanel {
+    /// Panels outside the main navigation flow are restored as the DAG panel
+    fn from_panel(panel: &Panel) -> Option<Self> {
+        match panel {
+            Panel::Config => None,
+            Panel::DAGRun => Some(SessionPanel::DagRun),
+            // The detail view is reopened from its task instance list
+            Panel::TaskInstance | Panel::TaskInstanceDetail => Some(SessionPanel::TaskInstance),
+            Panel::Logs => Some(SessionPanel::Logs),
+            Panel::Dag
+            | Panel::VariableDetail
+            | Panel::ConnectionDetail
+            | Panel::ImportErrorDetail
+            | Panel::Overview
+            | Panel::Search
+            | Panel::Compare
+            | Panel::EventLog => Some(SessionPanel::Dag),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortState {
+    pub column: usize,
+    pub direction: SortDirection,
+}
+
+/// Navigation and view state saved on exit and restored on startup when
+/// `restore_session` is enabled. Stored in `~/.local/state/flowrs/session.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub environment: Option<String>,
+    pub panel: Option<SessionPanel>,
+    pub dag_id: Option<String>,
+    pub dag_run_id: Option<String>,
+    pub task_id: Option<String>,
+    pub task_try: Option<u16>,
+    /// Filter text per table
+    #[serde(default)]
+    pub filters: BTreeMap<String, String>,
+    /// Sort column per table
+    #[serde(default)]
+    pub sort: BTreeMap<String, SortState>,
+    pub show_paused: Option<bool>,
+    pub log_level: Option<String>,
+}
+
+impl Session {
+    pub fn default_path() -> PathBuf {
+        crate::get_state_dir().join("session.json")
+    }
+
+    /// The saved session, if there is a readable one
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(session) => Some(session),
+            Err(e) => {
+                warn!("Ignoring unreadable session file {}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Capture where the user is and how the tables are filtered and sorted
+    pub fn capture(app: &App) -> Self {
+        let environment = app
+            .environment_state
+            .get_active_environment_name()
+            .map(str::to_string);
+        let panel = environment
+            .as_ref()
+            .and_then(|_| SessionPanel::from_panel(&app.active_panel));
+        let reached = |level: SessionPanel| panel.is_some_and(|panel| panel >= level);
+
+        let mut session = Session {
+            environment,
+            panel,
+            show_paused: Some(app.dags.show_paused),
+            log_level: Some(app.logs.min_log_level.to_string()),
+            ..Default::default()
+        };
+        if reached(SessionPanel::DagRun) {
+            session.dag_id.clone_from(&app.dagruns.dag_id);
+        }
+        if reached(SessionPanel::TaskInstance) {
+            session
+                .dag_run_id
+                .clone_from(&app.task_instances.dag_run_id);
+        }
+        if reached(SessionPanel::Logs) {
+            session.task_id.clone_from(&app.logs.task_id);
+            session.task_try = u16::try_from(app.logs.current_attempt).ok();
+        }
+
+        session.save_filter("config", &app.configs.filter);
+        session.save_filter("dags", &app.dags.filter);
+        session.save_filter("dag_runs", &app.dagruns.filter);
+        session.save_filter("task_instances", &app.task_instances.filter);
+
+        session.save_sort("config", &app.configs.filtered);
+        session.save_sort("dags", &app.dags.filtered);
+        session.save_sort("variables", &app.dags.filtered_variables);
+        session.save_sort("connections", &app.dags.filtered_connections);
+        session.save_sort("import_errors", &app.dags.filtered_import_errors);
+        session.save_sort("dag_runs", &app.dagruns.filtered);
+        session.save_sort("task_instances", &app.task_instances.filtered);
+        session
+    }
+
+    /// Restore filters, sorting and display settings.
+    /// Navigation needs the API and is restored by the worker.
+    pub fn apply_view_state(&self, app: &mut App) {
+        self.restore_filter("config", &mut app.configs.filter);
+        self.restore_filter("dags", &mut app.dags.filter);
+        self.restore_filter("dag_runs", &mut app.dagruns.filter);
+        self.restore_filter("task_instances", &mut app.task_instances.filter);
+        app.configs.filter_configs();
+
+        self.restore_sort("config", &mut app.configs.filtered);
+        self.restore_sort("dags", &mut app.dags.filtered);
+        self.restore_sort("variables", &mut app.dags.filtered_variables);
+        self.restore_sort("connections", &mut app.dags.filtered_connections);
+        self.restore_sort("import_errors", &mut app.dags.filtered_import_errors);
+        self.restore_sort("dag_runs", &mut app.dagruns.filtered);
+        self.restore_sort("task_instances", &mut app.task_instances.filtered);
+
+        if let Some(show_paused) = self.show_paused {
+            app.dags.show_paused = show_paused;
+        }
+        if let Some(level) = self.log_level() {
+            app.logs.min_log_level = level;
+        }
+    }
+
+    pub fn log_level(&self) -> Option<LogLevel> {
+        self.log_level.as_deref()?.parse().ok()
+    }
+
+    fn save_filter(&mut self, table: &str, filter: &Filter) {
+        if let Some(prefix) = filter.prefix().filter(|prefix| !prefix.is_empty()) {
+            self.filters.insert(table.to_string(), prefix.clone());
+        }
+    }
+
+    fn restore_filter(&self, table: &str, filter: &mut Filter) {
+        if let Some(prefix) = self.filters.get(table) {
+            filter.prefix = Some(prefix.clone());
+        }
+    }
+
+    fn save_sort<T>(&mut self, table: &str, sortable: &SortableTable<T>) {
+        if let Some((column, direction)) = sortable.sort_state() {
+            self.sort.insert(
+                table.to_string(),
+                SortState {
+                    column,
+                    direction: *direction,
+                },
+            );
+        }
+    }
+
+    fn restore_sort<T: CustomSort>(&self, table: &str, sortable: &mut SortableTable<T>) {
+        if let Some(state) = self.sort.get(table) {
+            sortable.set_sort(state.column, state.direction);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airflow::config::FlowrsConfig;
+
+    #[test]
+    fn test_session_round_trip() {
+        let mut app = App::new(FlowrsConfig::default());
+        app.dags.filter.prefix = Some("sales".to_string());
+        app.dags.show_paused = false;
+        app.dagruns.filtered.set_sort(1, SortDirection::Ascending);
+        app.logs.min_log_level = LogLevel::Warning;
+
+        let session = Session::capture(&app);
+        // Without an active environment there is nothing to navigate to
+        assert_eq!(session.environment, None);
+        assert_eq!(session.panel, None);
+
+        let path = std::env::temp_dir().join(format!("flowrs-session-{}.json", std::process::id()));
+        session.save(&path).unwrap();
+        let loaded = Session::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, session);
+        assert_eq!(loaded.log_level(), Some(LogLevel::Warning));
+
+        let mut restored = App::new(FlowrsConfig::default());
+        loaded.apply_view_state(&mut restored);
+        assert_eq!(restored.dags.filter.prefix(), Some(&"sales".to_string()));
+        assert!(!restored.dags.filter.is_enabled());
+        assert!(!restored.dags.show_paused);
+        assert_eq!(restored.logs.min_log_level, LogLevel::Warning);
+        // The default sort of the DAG runs table (logical date) is replaced
+        assert_eq!(
+            restored.dagruns.filtered.sort_state(),
+            Some((1, &SortDirection::Ascending))
+        );
+    }
+}
@@ -1,15 +1,30 @@
-use crate::airflow::config::FlowrsConfig;
+use crate::airflow::client::oidc::DeviceLoginPrompt;
+use crate::airflow::config::{FlowrsConfig, Protection};
+use crate::airflow::watch::WatchEvent;
 use crate::app::environment_state::EnvironmentStateContainer;
 use crate::app::model::dagruns::DagRunModel;
 use crate::app::model::dags::DagModel;
 use throbber_widgets_tui::ThrobberState;
 use log::debug;
+use crossterm::event::KeyEvent;
+
+use super::events::custom::FlowrsEvent;
+use super::worker::WorkerMessage;
 
 use super::model::{
+    compare::CompareModel,
     config::ConfigModel,
-    detail::{ConnectionDetailModel, ImportErrorDetailModel, VariableDetailModel},
+    detail::{
+        ConnectionDetailModel, ImportErrorDetailModel, TaskInstanceDetailModel,
+        VariableDetailModel,
+    },
+    eventlogs::EventLogModel,
     logs::LogModel, 
+    overview::OverviewModel,
+    popup::{audit::RecentActionsPopup, confirm::ConfirmEnvironmentPopup},
+    search::SearchModel,
     taskinstances::TaskInstanceModel,
+    Model,
 };
 
 pub struct App {
@@ -20,14 +35,31 @@ pub struct App {
     pub dagruns: DagRunModel,
     pub task_instances: TaskInstanceModel,
     pub logs: LogModel,
+    pub task_instance_detail: TaskInstanceDetailModel,
     pub variable_detail: VariableDetailModel,
     pub connection_detail: ConnectionDetailModel,
     pub import_error_detail: ImportErrorDetailModel,
+    pub overview: OverviewModel,
+    pub search: SearchModel,
+    pub compare: CompareModel,
+    pub event_logs: EventLogModel,
     pub ticks: u32,
     pub active_panel: Panel,
     pub loading: bool,
     pub startup: bool,
     pub throbber_state: ThrobberState,
+    /// Events reported by the background watcher, most recent last
+    pub watch_events: Vec<WatchEvent>,
+    /// Errors of the latest watcher poll, cleared once a poll succeeds
+    pub watch_errors: Vec<String>,
+    /// Popup listing the actions from the audit log, shown on top of any panel
+    pub recent_actions: Option<RecentActionsPopup>,
+    /// Protection of the active environment
+    pub protection: Protection,
+    /// Changes to a protected environment waiting for its name to be typed
+    pub confirm_environment: Option<ConfirmEnvironmentPopup>,
+    /// A login the user has to approve in the browser, e.g. an OIDC device code
+    pub pending_login: Option<DeviceLoginPrompt>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -36,10 +68,15 @@ pub enum Panel {
     Dag,
     DAGRun,
     TaskInstance,
+    TaskInstanceDetail,
     Logs,
     VariableDetail,
     ConnectionDetail,
     ImportErrorDetail,
+    Overview,
+    Search,
+    Compare,
+    EventLog,
 }
 
 impl App {
@@ -56,7 +93,16 @@ impl App {
         dags.timezone_offset = timezone_offset.clone();
         
         let mut dagruns = DagRunModel::new();
-        dagruns.timezone_offset = timezone_offset;
+        dagruns.timezone_offset.clone_from(&timezone_offset);
+
+        let mut compare = CompareModel::new();
+        compare.timezone_offset.clone_from(&timezone_offset);
+
+        let mut task_instance_detail = TaskInstanceDetailModel::new();
+        task_instance_detail.timezone_offset.clone_from(&timezone_offset);
+
+        let mut event_logs = EventLogModel::new();
+        event_logs.timezone_offset = timezone_offset;
         
         App {
             config,
@@ -66,24 +112,135 @@ impl App {
             dagruns,
             task_instances: TaskInstanceModel::new(),
             logs: LogModel::new(),
+            task_instance_detail,
             variable_detail: VariableDetailModel::new(),
             connection_detail: ConnectionDetailModel::new(),
             import_error_detail: ImportErrorDetailModel::new(),
+            overview: OverviewModel::new(),
+            search: SearchModel::new(),
+            compare,
+            event_logs,
             active_panel: Panel::Config,
             ticks: 0,
             loading: true,
             startup: true,
             throbber_state: ThrobberState::default(),
+            watch_events: Vec::new(),
+            watch_errors: Vec::new(),
+            recent_actions: None,
+            protection: Protection::None,
+            confirm_environment: None,
+            pending_login: None,
         }
     }
 
+    /// Look for a login waiting for the user in any of the environments
+    pub fn update_pending_login(&mut self) {
+        self.pending_login = self
+            .environment_state
+            .environments
+            .values()
+            .find_map(|env| env.client.pending_login());
+    }
+
+    /// Disable the actions that change a read-only environment in the panels
+    pub fn set_protection(&mut self, protection: Protection) {
+        self.protection = protection;
+        let read_only = protection == Protection::ReadOnly;
+        self.dags.read_only = read_only;
+        self.dagruns.read_only = read_only;
+        self.task_instances.read_only = read_only;
+    }
+
+    /// Hold back messages that change a protected environment: they are dropped for a
+    /// read-only environment and wait for the environment name to be typed when it requires confirmation
+    pub fn protect(&mut self, messages: Vec<WorkerMessage>) -> Vec<WorkerMessage> {
+        if !messages.iter().any(WorkerMessage::changes_environment) {
+            return messages;
+        }
+        match self.protection {
+            Protection::None => messages,
+            Protection::ReadOnly => {
+                log::warn!("Dropping changes to a read-only environment: {messages:?}");
+                messages
+                    .into_iter()
+                    .filter(|message| !message.changes_environment())
+                    .collect()
+            }
+            Protection::Confirm => {
+                let environment = self
+                    .environment_state
+                    .get_active_environment_name()
+                    .unwrap_or_default();
+                // Keep the messages together so a refresh is only sent along with the change
+                self.confirm_environment = Some(ConfirmEnvironmentPopup::new(environment, messages));
+                vec![]
+            }
+        }
+    }
+
+    /// Pass a key to the popup shown on top of the panels. Returns the messages to send,
+    /// `None` if no such popup is open.
+    pub fn overlay_key(&mut self, key: &KeyEvent) -> Option<Vec<WorkerMessage>> {
+        if self.confirm_environment.is_some() {
+            return Some(self.confirm_environment_key(key));
+        }
+        let popup = self.recent_actions.as_mut()?;
+        if !popup.update(key) {
+            self.recent_actions = None;
+        }
+        Some(vec![])
+    }
+
+    /// Pass an event to the active panel. Returns the event for the generic handling, if the
+    /// panel didn't consume it, and the messages to send.
+    pub fn update_active_panel(&mut self, event: &FlowrsEvent) -> (Option<FlowrsEvent>, Vec<WorkerMessage>) {
+        match self.active_panel {
+            Panel::Config => self.configs.update(event),
+            Panel::Dag => self.dags.update(event),
+            Panel::DAGRun => self.dagruns.update(event),
+            Panel::TaskInstance => self.task_instances.update(event),
+            Panel::TaskInstanceDetail => self.task_instance_detail.update(event),
+            Panel::Logs => self.logs.update(event),
+            Panel::VariableDetail => self.variable_detail.update(event),
+            Panel::ConnectionDetail => self.connection_detail.update(event),
+            Panel::ImportErrorDetail => self.import_error_detail.update(event),
+            Panel::Overview => self.overview.update(event),
+            Panel::Search => self.search.update(event),
+            Panel::Compare => self.compare.update(event),
+            Panel::EventLog => self.event_logs.update(event),
+        }
+    }
+
+    /// Returns the held back messages once confirmed, none while typing or when cancelled
+    fn confirm_environment_key(&mut self, key: &KeyEvent) -> Vec<WorkerMessage> {
+        let Some(confirmed) = self.confirm_environment.as_mut().and_then(|popup| popup.update(key)) else {
+            return vec![];
+        };
+        let Some(popup) = self.confirm_environment.take() else {
+            return vec![];
+        };
+        if confirmed {
+            return popup.messages;
+        }
+        // Undo the pause toggle that is shown right away
+        for message in &popup.messages {
+            if let WorkerMessage::ToggleDag { dag_id, is_paused } = message {
+                if let Some(dag) = self.dags.filtered.items.iter_mut().find(|dag| &dag.dag_id == dag_id) {
+                    dag.is_paused = *is_paused;
+                }
+            }
+        }
+        vec![]
+    }
+
     pub fn next_panel(&mut self) {
         match self.active_panel {
             Panel::Config => self.active_panel = Panel::Dag,
             Panel::Dag => self.active_panel = Panel::DAGRun,
             Panel::DAGRun => self.active_panel = Panel::TaskInstance,
-            Panel::TaskInstance => self.active_panel = Panel::Logs,
-            Panel::Logs => (),
+            Panel::TaskInstance | Panel::TaskInstanceDetail => self.active_panel = Panel::Logs,
+            Panel::Logs | Panel::Overview | Panel::Search | Panel::Compare | Panel::EventLog => (),
             // Detail panels go back to DAG panel (they're not in the main flow)
             Panel::VariableDetail | Panel::ConnectionDetail | Panel::ImportErrorDetail => self.active_panel = Panel::Dag,
         }
@@ -92,10 +249,12 @@ impl App {
     pub fn previous_panel(&mut self) {
         match self.active_panel {
             Panel::Config => (),
-            Panel::Dag => self.active_panel = Panel::Config,
+            Panel::Dag | Panel::Overview | Panel::Search => self.active_panel = Panel::Config,
             Panel::DAGRun => self.active_panel = Panel::Dag,
+            Panel::Compare => self.active_panel = Panel::Search,
+            Panel::EventLog => self.active_panel = self.event_logs.return_panel.clone(),
             Panel::TaskInstance => self.active_panel = Panel::DAGRun,
-            Panel::Logs => self.active_panel = Panel::TaskInstance,
+            Panel::TaskInstanceDetail | Panel::Logs => self.active_panel = Panel::TaskInstance,
             // Detail panels go back to DAG panel
             Panel::VariableDetail | Panel::ConnectionDetail | Panel::ImportErrorDetail => self.active_panel = Panel::Dag,
         }
@@ -121,6 +280,7 @@ impl App {
             Panel::Dag => {
                 let dag_count = self.environment_state.get_active_dags().len();
                 self.dags.all = self.environment_state.get_active_dags();
+                self.dags.favorites = self.environment_state.get_active_favorite_dags();
                 self.dags.filter_dags();
                 debug!("sync_panel_data: Synced {} DAGs to panel, recent_runs has {} entries", dag_count, self.dags.recent_runs.len());
                 // Restore tab and selection state when returning from detail views
@@ -197,10 +357,13 @@ impl App {
                     self.logs.current_log_data = None;
                 }
             }
-            Panel::Config => {
-                // Config panel doesn't need syncing
+            Panel::Config | Panel::Overview | Panel::Search | Panel::Compare | Panel::EventLog => {
+                // Config, overview, search, compare and event log panels don't need syncing
             }
-            Panel::VariableDetail | Panel::ConnectionDetail | Panel::ImportErrorDetail => {
+            Panel::TaskInstanceDetail
+            | Panel::VariableDetail
+            | Panel::ConnectionDetail
+            | Panel::ImportErrorDetail => {
                 // Detail panels don't sync from environment_state
                 // They're populated by worker messages when navigating to them
             }
@@ -225,3 +388,31 @@ impl App {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::worker::NoteTarget;
+
+    #[test]
+    fn test_confirm_protection_holds_back_note_changes() {
+        let mut app = App::new(FlowrsConfig::default());
+        app.set_protection(Protection::Confirm);
+        let note = WorkerMessage::SetNote {
+            target: NoteTarget::DagRun {
+                dag_id: "etl".to_string(),
+                dag_run_id: "manual__1".to_string(),
+            },
+            note: "rerun after fix".to_string(),
+        };
+
+        assert!(app.protect(vec![note]).is_empty());
+        let popup = app.confirm_environment.as_ref().unwrap();
+        assert!(matches!(
+            popup.messages.as_slice(),
+            [WorkerMessage::SetNote { .. }]
+        ));
+        // Reading doesn't need a confirmation
+        assert_eq!(app.protect(vec![WorkerMessage::UpdateDags]).len(), 1);
+    }
+}
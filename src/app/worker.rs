@@ -1,25 +1,74 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use crate::airflow::model::common::Dag;
+use crate::airflow::config::AirflowConfig;
+use crate::airflow::model::common::{Dag, EventLogQuery};
 
 use super::model::popup::error::ErrorPopup;
-use super::model::popup::taskinstances::mark::MarkState as taskMarkState;
+use super::model::compare::EnvironmentDag;
+use super::model::dagruns::DagCodeWidget;
+use super::model::overview::EnvironmentOverview;
+use super::model::popup::dags::bulk::{BulkDagAction, BulkProgressPopup};
+use super::model::popup::dags::repair::{RepairRequest, RepairStatus};
+use super::model::popup::dags::DagPopUp;
+use super::model::popup::toast::Toast;
+use super::audit::{AuditAction, AuditEntry, AuditLog};
+use super::session::{Session, SessionPanel};
+use crate::airflow::traits::AirflowClient;
+use futures::StreamExt;
+use super::model::popup::taskinstances::mark::MarkRequest;
+use super::model::popup::taskinstances::TaskInstancePopUp;
 use super::{model::popup::dagruns::mark::MarkState, state::{App, Panel}};
 use anyhow::Result;
 use futures::future::join_all;
 use log::debug;
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::{RwLock, Semaphore};
 
+/// Maximum number of requests the worker runs at the same time
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// Maximum number of environments queried at the same time by the overview
+const MAX_CONCURRENT_OVERVIEW_ENVIRONMENTS: usize = 8;
+
+/// Maximum number of DAGs changed at the same time by a bulk action
+const MAX_CONCURRENT_BULK_DAG_ACTIONS: usize = 4;
+
+/// Number of DAGs requested per page when fetching all DAGs for the search
+const SEARCH_PAGE_SIZE: i64 = 100;
+
+/// How often running requests check whether their panel context is still active
+const STALE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone)]
 pub struct Worker {
     app: Arc<Mutex<App>>,
-    rx: Receiver<WorkerMessage>,
     tx: Sender<WorkerMessage>,
+    in_flight: Arc<Mutex<InFlight>>,
 }
 
 #[derive(Debug)]
 pub enum WorkerMessage {
     ConfigSelected(usize),
+    /// Query the managed services again, bypassing the discovery cache
+    RediscoverEnvironments,
+    /// Fetch health and activity of every configured environment for the overview
+    UpdateOverview,
+    /// Fetch the DAGs of every configured environment for the cross-environment search
+    SearchDags,
+    /// Switch to an environment and show the runs of one of its DAGs
+    OpenDagInEnvironment {
+        environment: String,
+        dag_id: String,
+    },
+    /// Return to the environment, DAG, run and task of a saved session
+    RestoreSession(Session),
+    /// Fetch one DAG from several environments to compare them side by side
+    CompareDag {
+        dag_id: String,
+        environments: Vec<String>,
+    },
     UpdateDags,
     FetchMoreDags {
         offset: i64,
@@ -29,6 +78,20 @@ pub enum WorkerMessage {
         dag_id: String,
         is_paused: bool,
     },
+    /// Pause, unpause or trigger several DAGs, reporting per-DAG progress
+    BulkDagAction {
+        action: BulkDagAction,
+        dag_ids: Vec<String>,
+    },
+    /// List the task instances a repair would clear with a dry run
+    PreviewRepair(RepairRequest),
+    /// Clear the task instances selected by a repair, one request per DAG
+    RepairTaskInstances(RepairRequest),
+    /// Pin or unpin a DAG
+    ToggleFavorite {
+        dag_id: String,
+        favorite: bool,
+    },
     UpdateDagRuns {
         dag_id: String,
         clear: bool,
@@ -46,6 +109,13 @@ pub enum WorkerMessage {
     FetchTaskOrder {
         dag_id: String,
     },
+    /// Show all fields of a task instance with the definition and docs of its task
+    GetTaskInstanceDetail {
+        dag_id: String,
+        dag_run_id: String,
+        task_id: String,
+        map_index: i64,
+    },
     GetDagCode {
         dag_id: String,
     },
@@ -93,12 +163,10 @@ pub enum WorkerMessage {
         dag_id: String,
         dag_run_id: String,
     },
-    MarkTaskInstance {
-        task_id: String,
-        dag_id: String,
-        dag_run_id: String,
-        status: taskMarkState,
-    },
+    /// List the task instances marking would change with a dry run
+    PreviewMarkTaskInstances(MarkRequest),
+    /// Mark task instances of a DAG run, with the related task instances of the scope
+    MarkTaskInstances(MarkRequest),
     TriggerDagRun {
         dag_id: String,
     },
@@ -106,6 +174,16 @@ pub enum WorkerMessage {
     OpenInEditor {
         filepath: std::path::PathBuf,
     },
+    /// Edit the note of a DAG run or task instance in the external editor
+    EditNote {
+        target: NoteTarget,
+        note: Option<String>,
+    },
+    /// Save an edited note, an empty note clears it
+    SetNote {
+        target: NoteTarget,
+        note: String,
+    },
     // Variables and Connections
     UpdateVariables,
     GetVariableDetail {
@@ -119,6 +197,42 @@ pub enum WorkerMessage {
     GetImportErrorDetail {
         import_error_id: i64,
     },
+    /// Fetch the event log entries matching the query and show them in the event log panel
+    UpdateEventLogs(EventLogQuery),
+}
+
+impl WorkerMessage {
+    /// Messages that switch environments or change state on the server.
+    /// They run on their own so that requests sent afterwards (e.g. a refresh after
+    /// marking a DAG run) observe their effect.
+    fn is_exclusive(&self) -> bool {
+        self.changes_environment()
+            || matches!(
+                self,
+                WorkerMessage::ConfigSelected(_)
+                    | WorkerMessage::RediscoverEnvironments
+                    | WorkerMessage::OpenDagInEnvironment { .. }
+                    | WorkerMessage::RestoreSession(_)
+                    | WorkerMessage::ToggleFavorite { .. }
+            )
+    }
+
+    /// Messages that change DAGs, runs or tasks on the server, guarded by the
+    /// `protection` of the environment
+    pub fn changes_environment(&self) -> bool {
+        matches!(
+            self,
+            WorkerMessage::ToggleDag { .. }
+                | WorkerMessage::BulkDagAction { .. }
+                | WorkerMessage::RepairTaskInstances(_)
+                | WorkerMessage::ClearDagRun { .. }
+                | WorkerMessage::MarkDagRun { .. }
+                | WorkerMessage::ClearTaskInstance { .. }
+                | WorkerMessage::MarkTaskInstances(_)
+                | WorkerMessage::TriggerDagRun { .. }
+                | WorkerMessage::SetNote { .. }
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -145,9 +259,42 @@ pub enum OpenItem {
     },
 }
 
+/// The DAG run or task instance a note belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub enum NoteTarget {
+    DagRun {
+        dag_id: String,
+        dag_run_id: String,
+    },
+    TaskInstance {
+        dag_id: String,
+        dag_run_id: String,
+        task_id: String,
+    },
+}
+
+impl NoteTarget {
+    /// Name of the file the note is edited in
+    pub fn file_name(&self) -> String {
+        let name = match self {
+            NoteTarget::DagRun { dag_id, dag_run_id } => format!("{dag_id}_{dag_run_id}"),
+            NoteTarget::TaskInstance {
+                dag_id,
+                dag_run_id,
+                task_id,
+            } => format!("{dag_id}_{dag_run_id}_{task_id}"),
+        };
+        format!("note_{}.md", crate::app::environment_state::sanitize_filename(&name))
+    }
+}
+
 impl Worker {
-    pub fn new(app: Arc<Mutex<App>>, rx_worker: Receiver<WorkerMessage>, tx_worker: Sender<WorkerMessage>) -> Self {
-        Worker { app, rx: rx_worker, tx: tx_worker }
+    pub fn new(app: Arc<Mutex<App>>, tx_worker: Sender<WorkerMessage>) -> Self {
+        Worker {
+            app,
+            tx: tx_worker,
+            in_flight: Arc::default(),
+        }
     }
     
     /// Helper function to persist logs to disk after adding a chunk
@@ -217,20 +364,41 @@ impl Worker {
         app.sync_panel_data();
     }
 
-    pub async fn process_message(&mut self, message: WorkerMessage) -> Result<()> {
-        // Set loading state at the start
-        {
-            let mut app = self.app.lock().unwrap();
-            app.loading = true;
-        }
-
+    pub async fn process_message(&self, message: WorkerMessage) -> Result<()> {
         // Handle ConfigSelected BEFORE checking for client (since it creates the client)
         if let WorkerMessage::ConfigSelected(idx) = message {
             self.switch_airflow_client(idx);
-            let mut app = self.app.lock().unwrap();
-            app.loading = false;
             return Ok(());
         }
+        if let WorkerMessage::RediscoverEnvironments = message {
+            return self.rediscover_environments().await;
+        }
+        if let WorkerMessage::UpdateOverview = message {
+            self.update_overview().await;
+            return Ok(());
+        }
+        if let WorkerMessage::SearchDags = message {
+            self.search_dags().await;
+            return Ok(());
+        }
+        if let WorkerMessage::CompareDag {
+            dag_id,
+            environments,
+        } = message
+        {
+            self.compare_dag(&dag_id, &environments).await;
+            return Ok(());
+        }
+        if let WorkerMessage::OpenDagInEnvironment {
+            environment,
+            dag_id,
+        } = message
+        {
+            return self.open_dag_in_environment(&environment, dag_id).await;
+        }
+        if let WorkerMessage::RestoreSession(session) = message {
+            return self.restore_session(session).await;
+        }
 
         // Get the active client from the environment state
         let client = {
@@ -239,15 +407,14 @@ impl Worker {
         };
 
         if client.is_none() {
-            // Reset loading state before returning
             let mut app = self.app.lock().unwrap();
             app.dags.error_popup = Some(ErrorPopup::from_strings(vec![
                 "No active environment selected".into(),
             ]));
-            app.loading = false;
             return Ok(());
         }
         let client = client.unwrap();
+        let audit = AuditAction::for_message(&message);
         match message {
             WorkerMessage::UpdateDags => {
                 // Always clear backend first (instant if empty on initial load)
@@ -258,11 +425,24 @@ impl Worker {
                     app.dags.recent_runs.clear();
                 }
                 
-                // Fetch initial 10 DAGs for immediate display
+                // Fetch initial 10 DAGs for immediate display, and the pinned DAGs to show on top
                 let start = std::time::Instant::now();
                 debug!("[PERF] Starting UpdateDags - fetching first 10 DAGs");
-                let dag_list = client.list_dags_paginated(0, 10).await;
+                let (dag_list, favorites) = tokio::join!(
+                    client.list_dags_paginated(0, 10),
+                    client.list_favorite_dags()
+                );
                 debug!("[PERF] UpdateDags: list_dags_paginated took {:?}", start.elapsed());
+                match favorites {
+                    Ok(favorites) => {
+                        let mut app = self.app.lock().unwrap();
+                        if let Some(env) = app.environment_state.get_active_environment_mut() {
+                            env.favorite_dags = favorites;
+                        }
+                    }
+                    // Favorites are optional, failing to load them shouldn't hide the DAGs
+                    Err(e) => log::warn!("Failed to fetch favorite DAGs: {e}"),
+                }
                 match dag_list {
                     Ok(dag_list) => {
                         let total = dag_list.total_entries;
@@ -568,11 +748,45 @@ impl Worker {
             }
             WorkerMessage::ToggleDag { dag_id, is_paused } => {
                 let dag = client.toggle_dag(&dag_id, is_paused).await;
+                self.audit(audit, &dag);
                 if let Err(e) = dag {
                     let mut app = self.app.lock().unwrap();
                     app.dags.error_popup = Some(ErrorPopup::from_strings(vec![e.to_string()]));
                 }
             }
+            WorkerMessage::BulkDagAction { action, dag_ids } => {
+                self.bulk_dag_action(client.as_ref(), action, &dag_ids).await;
+            }
+            WorkerMessage::PreviewRepair(request) => {
+                self.repair(client.as_ref(), &request, true).await;
+            }
+            WorkerMessage::RepairTaskInstances(request) => {
+                self.repair(client.as_ref(), &request, false).await;
+            }
+            WorkerMessage::ToggleFavorite { dag_id, favorite } => {
+                let result = client.set_dag_favorite(&dag_id, favorite).await;
+                self.audit(audit, &result);
+                let mut app = self.app.lock().unwrap();
+                match result {
+                    Ok(()) => {
+                        if let Some(env) = app.environment_state.get_active_environment_mut() {
+                            if favorite {
+                                env.favorite_dags.insert(dag_id);
+                            } else {
+                                env.favorite_dags.remove(&dag_id);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        // Undo the optimistic update of the DAG panel
+                        app.dags.set_favorite(&dag_id, !favorite);
+                        app.dags.error_popup = Some(ErrorPopup::from_strings(vec![
+                            format!("Failed to {} {dag_id}:", if favorite { "pin" } else { "unpin" }),
+                            e.to_string(),
+                        ]));
+                    }
+                }
+            }
             WorkerMessage::UpdateDagRuns { dag_id, clear: _ } => {
                 let dag_runs = client.list_dagruns(&dag_id).await;
                 let mut app = self.app.lock().unwrap();
@@ -780,6 +994,15 @@ impl Worker {
                 app.dagruns.dag_code.file_path = Some(filepath);
                 app.dagruns.dag_code.pending_editor_open = true;
             }
+            WorkerMessage::GetTaskInstanceDetail {
+                dag_id,
+                dag_run_id,
+                task_id,
+                map_index,
+            } => {
+                self.task_instance_detail(client.as_ref(), &dag_id, &dag_run_id, &task_id, map_index)
+                    .await;
+            }
             WorkerMessage::GetDagDetails { dag_id } => {
                 match client.get_dag_details(&dag_id).await {
                     Ok(dag_details) => {
@@ -854,6 +1077,7 @@ impl Worker {
             WorkerMessage::ClearDagRun { dag_run_id, dag_id } => {
                 debug!("Clearing dag_run: {dag_run_id}");
                 let dag_run = client.clear_dagrun(&dag_id, &dag_run_id).await;
+                self.audit(audit, &dag_run);
                 if let Err(e) = dag_run {
                     debug!("Error clearing dag_run: {e}");
                     let mut app = self.app.lock().unwrap();
@@ -1033,6 +1257,7 @@ impl Worker {
                 let dag_run = client
                     .mark_dag_run(&dag_id, &dag_run_id, &status.to_string())
                     .await;
+                self.audit(audit, &dag_run);
                 if let Err(e) = dag_run {
                     debug!("Error marking dag_run: {e}");
                     let mut app = self.app.lock().unwrap();
@@ -1048,6 +1273,7 @@ impl Worker {
                 let task_instance = client
                     .clear_task_instance(&dag_id, &dag_run_id, &task_id)
                     .await;
+                self.audit(audit, &task_instance);
                 if let Err(e) = task_instance {
                     debug!("Error clearing task_instance: {e}");
                     let mut app = self.app.lock().unwrap();
@@ -1055,32 +1281,29 @@ impl Worker {
                         Some(ErrorPopup::from_strings(vec![e.to_string()]));
                 }
             }
-            WorkerMessage::MarkTaskInstance {
-                task_id,
-                dag_id,
-                dag_run_id,
-                status,
-            } => {
-                debug!("Marking task_instance: {task_id}");
-                {
-                    // Update the local state before sending the request; this way, the UI will update immediately
-                    let mut app = self.app.lock().unwrap();
-                    app.task_instances
-                        .mark_task_instance(&task_id, &status.to_string());
-                }
-                let task_instance = client
-                    .mark_task_instance(&dag_id, &dag_run_id, &task_id, &status.to_string())
-                    .await;
-                if let Err(e) = task_instance {
-                    debug!("Error marking task_instance: {e}");
-                    let mut app = self.app.lock().unwrap();
-                    app.task_instances.error_popup =
-                        Some(ErrorPopup::from_strings(vec![e.to_string()]));
+            WorkerMessage::PreviewMarkTaskInstances(request) => {
+                let task_instances = client
+                    .preview_mark_task_instances(
+                        &request.dag_id,
+                        &request.dag_run_id,
+                        &request.task_ids,
+                        &request.status.to_string(),
+                        &request.scope,
+                    )
+                    .await
+                    .map_err(|e| e.to_string());
+                let mut app = self.app.lock().unwrap();
+                if let Some(TaskInstancePopUp::Mark(popup)) = &mut app.task_instances.popup {
+                    popup.set_preview(&request, task_instances);
                 }
             }
+            WorkerMessage::MarkTaskInstances(request) => {
+                self.mark_task_instances(client.as_ref(), &request).await;
+            }
             WorkerMessage::TriggerDagRun { dag_id } => {
                 debug!("Triggering dag_run: {dag_id}");
                 let dag_run = client.trigger_dag_run(&dag_id, None).await;
+                self.audit(audit, &dag_run);
                 if let Err(e) = dag_run {
                     debug!("Error triggering dag_run: {e}");
                     let mut app = self.app.lock().unwrap();
@@ -1109,6 +1332,16 @@ impl Worker {
                 }
             }
 
+            WorkerMessage::UpdateEventLogs(query) => {
+                debug!("Fetching event logs: {query:?}");
+                let event_logs = client.list_event_logs(&query).await;
+                if let Err(e) = &event_logs {
+                    debug!("Error fetching event logs: {e}");
+                }
+                let mut app = self.app.lock().unwrap();
+                app.event_logs.set_event_logs(&query, event_logs);
+            }
+
             WorkerMessage::OpenItem(item) => {
                 let url = client.build_open_url(&item)?;
                 webbrowser::open(&url).unwrap();
@@ -1119,6 +1352,39 @@ impl Worker {
                 // This case should never be reached
                 log::warn!("OpenInEditor message received in worker (should be handled in main loop)");
             }
+            WorkerMessage::EditNote { .. } => {
+                // Like OpenInEditor, editing needs the terminal and is handled in the main loop
+                log::warn!("EditNote message received in worker (should be handled in main loop)");
+            }
+            WorkerMessage::SetNote { target, note } => {
+                let result = match &target {
+                    NoteTarget::DagRun { dag_id, dag_run_id } => {
+                        client.set_dag_run_note(dag_id, dag_run_id, &note).await
+                    }
+                    NoteTarget::TaskInstance {
+                        dag_id,
+                        dag_run_id,
+                        task_id,
+                    } => {
+                        client
+                            .set_task_instance_note(dag_id, dag_run_id, task_id, &note)
+                            .await
+                    }
+                };
+                self.audit(audit, &result);
+                let mut app = self.app.lock().unwrap();
+                match result {
+                    Ok(()) => Self::apply_note(&mut app, &target, Some(note.as_str()).filter(|note| !note.is_empty())),
+                    Err(e) => {
+                        debug!("Error saving note: {e}");
+                        let error = ErrorPopup::from_strings(vec!["Failed to save note:".into(), e.to_string()]);
+                        match target {
+                            NoteTarget::DagRun { .. } => app.dagruns.error_popup = Some(error),
+                            NoteTarget::TaskInstance { .. } => app.task_instances.error_popup = Some(error),
+                        }
+                    }
+                }
+            }
             WorkerMessage::UpdateVariables => {
                 use crate::airflow::traits::VariableOperations;
                 match client.list_variables().await {
@@ -1211,30 +1477,460 @@ impl Worker {
                     ]));
                 }
             }
-            // ConfigSelected is handled before the client check above
-            WorkerMessage::ConfigSelected(_) => {
+            // These don't need an active environment and are handled before the client check above
+            WorkerMessage::ConfigSelected(_)
+            | WorkerMessage::RediscoverEnvironments
+            | WorkerMessage::UpdateOverview
+            | WorkerMessage::SearchDags
+            | WorkerMessage::CompareDag { .. }
+            | WorkerMessage::OpenDagInEnvironment { .. }
+            | WorkerMessage::RestoreSession(_) => {
                 // This should never be reached as it's handled earlier
-                unreachable!("ConfigSelected should be handled before client check")
+                unreachable!("{message:?} should be handled before client check")
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Append a change made on the server to the audit log, whether it succeeded or not
+    fn audit<T>(&self, action: Option<AuditAction>, result: &Result<T>) {
+        let Some(action) = action else {
+            return;
+        };
+        let environment = {
+            let app = self.app.lock().unwrap();
+            app.environment_state
+                .get_active_environment_name()
+                .unwrap_or_default()
+                .to_string()
+        };
+        let entry = AuditEntry::new(&environment, action, result.as_ref().err().map(ToString::to_string));
+        if let Err(e) = AuditLog::default().append(&entry) {
+            log::warn!("Failed to write the audit log: {e}");
+        }
+    }
+
+    /// Show a saved note in the panels and keep it in the environment cache
+    fn apply_note(app: &mut App, target: &NoteTarget, note: Option<&str>) {
+        let env = app.environment_state.get_active_environment_mut();
+        match target {
+            NoteTarget::DagRun { dag_id, dag_run_id } => {
+                if let Some(data) = env
+                    .and_then(|env| env.dags.get_mut(dag_id))
+                    .and_then(|dag| dag.dag_runs.get_mut(dag_run_id))
+                {
+                    data.dag_run.note = note.map(str::to_string);
+                }
+                app.dagruns.set_note(dag_run_id, note);
+                app.dagruns.toast = Some(Toast::new("Note saved"));
+            }
+            NoteTarget::TaskInstance {
+                dag_id,
+                dag_run_id,
+                task_id,
+            } => {
+                if let Some(data) = env
+                    .and_then(|env| env.dags.get_mut(dag_id))
+                    .and_then(|dag| dag.dag_runs.get_mut(dag_run_id))
+                    .and_then(|run| run.task_instances.get_mut(task_id))
+                {
+                    data.task_instance.note = note.map(str::to_string);
+                }
+                app.task_instances.set_note(task_id, note);
+                app.task_instances.toast = Some(Toast::new("Note saved"));
+            }
+        }
+    }
+
+    /// Replace the managed servers in the config panel with freshly discovered ones
+    async fn rediscover_environments(&self) -> Result<()> {
+        let config = self.app.lock().unwrap().config.clone();
+        let (config, errors) = match config.rediscover_managed_services().await {
+            Ok(result) => result,
+            Err(e) => {
+                let mut app = self.app.lock().unwrap();
+                app.configs.error_popup = Some(ErrorPopup::from_strings(vec![format!(
+                    "Failed to rediscover environments: {e}"
+                )]));
+                return Ok(());
+            }
+        };
+
+        let mut app = self.app.lock().unwrap();
+        let servers = config.servers.clone().unwrap_or_default();
+        let managed = servers.iter().filter(|s| s.managed.is_some()).count();
+        app.config = config;
+        app.configs.set_configs(servers);
+        if !errors.is_empty() {
+            app.configs.error_popup = Some(ErrorPopup::from_strings(errors));
+        }
+        app.configs.toast = Some(Toast::new(format!(
+            "Discovered {managed} managed environment(s)"
+        )));
+        Ok(())
+    }
+
+    /// Clients of all configured environments, reusing the ones that were already created
+    fn all_environment_clients(&self) -> Vec<(String, Result<Arc<dyn AirflowClient>>)> {
+        let app = self.app.lock().unwrap();
+        app.config
+            .servers
+            .iter()
+            .flatten()
+            .map(|server| {
+                let client = match app.environment_state.environments.get(&server.name) {
+                    Some(env) => Ok(env.client.clone()),
+                    None => crate::airflow::client::create_client(server),
+                };
+                (server.name.clone(), client)
+            })
+            .collect()
+    }
+
+    /// Refresh the overview of all configured environments, querying them concurrently.
+    /// Rows are updated as soon as an environment has answered.
+    async fn update_overview(&self) {
+        futures::stream::iter(self.all_environment_clients())
+            .for_each_concurrent(MAX_CONCURRENT_OVERVIEW_ENVIRONMENTS, |(name, client)| async move {
+                let overview = match client {
+                    Ok(client) => fetch_environment_overview(name, client.as_ref()).await,
+                    Err(e) => EnvironmentOverview {
+                        name,
+                        errors: vec![format!("Failed to create client: {e}")],
+                        ..Default::default()
+                    },
+                };
+                self.app.lock().unwrap().overview.update_environment(overview);
+            })
+            .await;
+    }
+
+    /// Fetch the DAGs of all configured environments concurrently for the search panel.
+    /// Results are shown as soon as an environment has answered.
+    async fn search_dags(&self) {
+        futures::stream::iter(self.all_environment_clients())
+            .for_each_concurrent(MAX_CONCURRENT_OVERVIEW_ENVIRONMENTS, |(name, client)| async move {
+                let dags = match client {
+                    Ok(client) => fetch_all_dags(client.as_ref()).await,
+                    Err(e) => Err(anyhow::anyhow!("Failed to create client: {e}")),
+                };
+                let mut app = self.app.lock().unwrap();
+                match dags {
+                    Ok(dags) => app.search.add_environment_results(&name, dags),
+                    Err(e) => app.search.add_environment_error(&name, &e.to_string()),
+                }
+            })
+            .await;
+    }
+
+    /// Apply a bulk action to each DAG, a few at a time, and show the results in a progress popup.
+    /// Every DAG is audited on its own, as if the action had been performed on it alone.
+    async fn bulk_dag_action(&self, client: &dyn AirflowClient, action: BulkDagAction, dag_ids: &[String]) {
+        self.app.lock().unwrap().dags.popup =
+            Some(DagPopUp::Progress(BulkProgressPopup::new(action, dag_ids)));
+        futures::stream::iter(dag_ids)
+            .for_each_concurrent(MAX_CONCURRENT_BULK_DAG_ACTIONS, |dag_id| async move {
+                let result = match action {
+                    BulkDagAction::Pause => client.toggle_dag(dag_id, false).await,
+                    BulkDagAction::Unpause => client.toggle_dag(dag_id, true).await,
+                    BulkDagAction::Trigger => client.trigger_dag_run(dag_id, None).await,
+                };
+                self.audit(AuditAction::for_message(&action.message(dag_id)), &result);
+                let mut app = self.app.lock().unwrap();
+                if result.is_ok() && action != BulkDagAction::Trigger {
+                    app.dags.set_paused(dag_id, action == BulkDagAction::Pause);
+                }
+                // The popup may have been closed before all DAGs were done
+                if let Some(DagPopUp::Progress(progress)) = &mut app.dags.popup {
+                    progress.set_result(dag_id, &result);
+                }
+            })
+            .await;
+    }
+
+    /// Mark the task instances of a request, auditing each task with its own result
+    async fn mark_task_instances(&self, client: &dyn AirflowClient, request: &MarkRequest) {
+        debug!("Marking task_instances: {:?}", request.task_ids);
+        let status = request.status.to_string();
+        {
+            // Update the local state before sending the request; this way, the UI will update immediately
+            let mut app = self.app.lock().unwrap();
+            for task_id in &request.task_ids {
+                app.task_instances.mark_task_instance(task_id, &status);
+            }
+        }
+        let results = client
+            .mark_task_instances(
+                &request.dag_id,
+                &request.dag_run_id,
+                &request.task_ids,
+                &status,
+                &request.scope,
+            )
+            .await;
+        let mut errors = Vec::new();
+        for (task_id, result) in &results {
+            self.audit(Some(AuditAction::for_mark(request, task_id)), result);
+            if let Err(e) = result {
+                debug!("Error marking task_instance {task_id}: {e}");
+                errors.push(format!("{task_id}: {e}"));
+            }
+        }
+        if !errors.is_empty() {
+            let mut app = self.app.lock().unwrap();
+            app.task_instances.error_popup = Some(ErrorPopup::from_strings(errors));
+        }
+    }
+
+    /// Clear the task instances selected by a repair in each DAG, or only list them with `dry_run`.
+    /// Results are shown in the repair popup as soon as a DAG is done.
+    async fn repair(&self, client: &dyn AirflowClient, request: &RepairRequest, dry_run: bool) {
+        if !dry_run {
+            if let Some(DagPopUp::Repair(popup)) = &mut self.app.lock().unwrap().dags.popup {
+                popup.start_clearing(request);
             }
         }
+        futures::stream::iter(&request.dag_ids)
+            .for_each_concurrent(MAX_CONCURRENT_BULK_DAG_ACTIONS, |dag_id| async move {
+                let result = client.clear_task_instances(dag_id, &request.clear, dry_run).await;
+                if !dry_run {
+                    self.audit(Some(AuditAction::for_repair(dag_id, &request.clear)), &result);
+                }
+                let status = match result {
+                    Ok(task_instances) if dry_run => RepairStatus::Matched(task_instances),
+                    Ok(task_instances) => RepairStatus::Cleared(task_instances.len()),
+                    Err(e) => RepairStatus::Failed(e.to_string()),
+                };
+                if let Some(DagPopUp::Repair(popup)) = &mut self.app.lock().unwrap().dags.popup {
+                    popup.set_result(request, dag_id, status);
+                }
+            })
+            .await;
+    }
+
+    /// Show the listed fields of a task instance right away, the definition of its task fills in
+    /// once fetched
+    async fn task_instance_detail(
+        &self,
+        client: &dyn AirflowClient,
+        dag_id: &str,
+        dag_run_id: &str,
+        task_id: &str,
+        map_index: i64,
+    ) {
+        {
+            let mut app = self.app.lock().unwrap();
+            let task_instance = app.task_instances.all.iter().find(|ti| {
+                ti.dag_id == dag_id
+                    && ti.dag_run_id == dag_run_id
+                    && ti.task_id == task_id
+                    && ti.map_index == map_index
+            });
+            let Some(task_instance) = task_instance.cloned() else {
+                return;
+            };
+            app.task_instance_detail.set_task_instance(task_instance);
+            app.active_panel = Panel::TaskInstanceDetail;
+        }
+        let task = client.get_task(dag_id, task_id).await;
+        if let Err(e) = &task {
+            log::error!("Failed to fetch task {task_id} of DAG {dag_id}: {e}");
+        }
+        let mut app = self.app.lock().unwrap();
+        app.task_instance_detail
+            .set_task(dag_id, task_id, task.map_err(|e| e.to_string()));
+    }
+
+    /// Fetch a DAG, its latest run and its source from each of the given environments concurrently
+    async fn compare_dag(&self, dag_id: &str, environments: &[String]) {
+        let targets = self
+            .all_environment_clients()
+            .into_iter()
+            .filter(|(name, _)| environments.contains(name));
+        futures::stream::iter(targets)
+            .for_each_concurrent(MAX_CONCURRENT_OVERVIEW_ENVIRONMENTS, |(name, client)| async move {
+                let environment = match client {
+                    Ok(client) => fetch_environment_dag(name, dag_id, client.as_ref()).await,
+                    Err(e) => EnvironmentDag {
+                        name,
+                        errors: vec![format!("Failed to create client: {e}")],
+                        ..Default::default()
+                    },
+                };
+                let pending = {
+                    let mut app = self.app.lock().unwrap();
+                    app.compare.update_environment(environment);
+                    app.compare.pending_comparison()
+                };
+                // Diffing large sources is slow, so it runs without holding the app lock
+                if let Some(pending) = pending {
+                    match tokio::task::spawn_blocking(move || pending.compare()).await {
+                        Ok(comparison) => {
+                            self.app.lock().unwrap().compare.apply_comparison(comparison);
+                        }
+                        Err(e) => log::error!("Failed to compare DAG sources: {e}"),
+                    }
+                }
+            })
+            .await;
+    }
 
-        // Reset loading state at the end
+    /// Switch to the environment of a search result and show the runs of the DAG
+    async fn open_dag_in_environment(&self, environment: &str, dag_id: String) -> Result<()> {
+        let has_task_order;
         {
             let mut app = self.app.lock().unwrap();
-            app.loading = false;
+            let Some(config) = app
+                .config
+                .servers
+                .iter()
+                .flatten()
+                .find(|server| server.name == environment)
+                .cloned()
+            else {
+                app.search.errors.push(format!("{environment}: environment no longer configured"));
+                return Ok(());
+            };
+            if !Self::activate_environment(&mut app, &config) {
+                // The error popup lives on the config panel
+                app.active_panel = Panel::Config;
+                return Ok(());
+            }
+
+            // Same as selecting the DAG in the DAG panel
+            app.active_panel = Panel::DAGRun;
+            app.dagruns.dag_id = Some(dag_id.clone());
+            app.dagruns.dag_code = DagCodeWidget::default();
+            app.dagruns.all = app.environment_state.get_active_dag_runs(&dag_id);
+            app.dagruns.filter_dag_runs();
+            has_task_order = app.environment_state.has_task_order(&dag_id);
         }
 
+        let mut messages = vec![
+            WorkerMessage::UpdateDagRuns {
+                dag_id: dag_id.clone(),
+                clear: true,
+            },
+            WorkerMessage::GetDagDetails {
+                dag_id: dag_id.clone(),
+            },
+        ];
+        if !has_task_order {
+            messages.push(WorkerMessage::FetchTaskOrder { dag_id });
+        }
+        for message in messages {
+            self.tx.send(message).await?;
+        }
         Ok(())
     }
 
-    pub fn switch_airflow_client(&mut self, idx: usize) {
+    /// Navigate as far into the saved session as it reached: environment, DAG, run, task logs
+    async fn restore_session(&self, session: Session) -> Result<()> {
+        let Some(environment) = session.environment.clone() else {
+            return Ok(());
+        };
+        let panel = session.panel.unwrap_or(SessionPanel::Dag);
+        let reached = |level: SessionPanel| panel >= level;
+
+        let config = self
+            .app
+            .lock()
+            .unwrap()
+            .config
+            .servers
+            .iter()
+            .flatten()
+            .find(|server| server.name == environment)
+            .cloned();
+        let Some(config) = config else {
+            log::warn!("Not restoring session, environment '{environment}' is no longer configured");
+            return Ok(());
+        };
+
+        let Some(dag_id) = session.dag_id.clone().filter(|_| reached(SessionPanel::DagRun)) else {
+            let mut app = self.app.lock().unwrap();
+            if Self::activate_environment(&mut app, &config) {
+                app.active_panel = Panel::Dag;
+            }
+            return Ok(());
+        };
+        self.open_dag_in_environment(&environment, dag_id.clone()).await?;
+
+        let Some(dag_run_id) = session
+            .dag_run_id
+            .clone()
+            .filter(|_| reached(SessionPanel::TaskInstance))
+        else {
+            return Ok(());
+        };
+        {
+            let mut app = self.app.lock().unwrap();
+            if app.active_panel != Panel::DAGRun {
+                // Opening the environment failed
+                return Ok(());
+            }
+            app.active_panel = Panel::TaskInstance;
+            app.task_instances.dag_id = Some(dag_id.clone());
+            app.task_instances.dag_run_id = Some(dag_run_id.clone());
+            app.sync_panel_data();
+        }
+        self.tx
+            .send(WorkerMessage::UpdateTaskInstances {
+                dag_id: dag_id.clone(),
+                dag_run_id: dag_run_id.clone(),
+                clear: true,
+            })
+            .await?;
+
+        if !reached(SessionPanel::Logs) {
+            return Ok(());
+        }
+        let (Some(task_id), Some(task_try)) = (session.task_id.clone(), session.task_try) else {
+            return Ok(());
+        };
+        {
+            let mut app = self.app.lock().unwrap();
+            app.active_panel = Panel::Logs;
+            app.logs.reset_for_new_task(
+                dag_id.clone(),
+                dag_run_id.clone(),
+                task_id.clone(),
+                task_try,
+            );
+            if let Some(level) = session.log_level() {
+                app.logs.min_log_level = level;
+            }
+        }
+        self.tx
+            .send(WorkerMessage::UpdateTaskLogs {
+                dag_id,
+                dag_run_id,
+                task_id,
+                task_try,
+                clear: true,
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub fn switch_airflow_client(&self, idx: usize) {
         let mut app = self.app.lock().unwrap();
         let selected_config = app.configs.filtered.items[idx].clone();
+        if Self::activate_environment(&mut app, &selected_config) {
+            // Reset to Dag panel when switching environments
+            app.active_panel = Panel::Dag;
+        }
+    }
+
+    /// Make an environment the active one, creating its client on first use.
+    /// Returns false (and shows an error) if the client could not be created.
+    fn activate_environment(app: &mut App, selected_config: &AirflowConfig) -> bool {
         let env_name = selected_config.name.clone();
 
         // Check if environment already exists, if not create it
         if !app.environment_state.environments.contains_key(&env_name) {
-            match crate::airflow::client::create_client(&selected_config) {
+            match crate::airflow::client::create_client(selected_config) {
                 Ok(client) => {
                     let env_data = crate::app::environment_state::EnvironmentData::new(client);
                     app.environment_state
@@ -1251,7 +1947,7 @@ impl Worker {
                         "- Invalid endpoint URL".to_string(),
                         "- Network connectivity issues".to_string(),
                     ]));
-                    return;
+                    return false;
                 }
             }
         }
@@ -1259,25 +1955,333 @@ impl Worker {
         // Set this as the active environment
         app.environment_state
             .set_active_environment(env_name.clone());
-
-        // Reset to Dag panel when switching environments
-        app.active_panel = Panel::Dag;
+        app.set_protection(selected_config.protection);
 
         // Clear the view state but NOT the environment data
         app.clear_state();
 
         // Sync panel data from the new environment
         app.sync_panel_data();
+        true
+    }
+
+    /// Receive messages and process them.
+    /// Independent requests run concurrently (at most `MAX_CONCURRENT_REQUESTS`), identical
+    /// requests that are already in flight are dropped, and requests are cancelled once the
+    /// panel or environment they were issued for is no longer active.
+    /// Exclusive messages (see `WorkerMessage::is_exclusive`) wait for all running requests
+    /// and hold back later messages until they are done.
+    pub async fn run(self, mut rx: Receiver<WorkerMessage>) -> Result<()> {
+        let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+        let gate = Arc::new(RwLock::new(()));
+
+        while let Some(message) = rx.recv().await {
+            if message.is_exclusive() {
+                let _exclusive = gate.write().await;
+                self.start_request(None);
+                if let Err(e) = self.process_message(message).await {
+                    log::error!("Worker failed to process message: {e}");
+                }
+                self.finish_request(None);
+                continue;
+            }
+
+            let key = format!("{message:?}");
+            if !self.start_request(Some(&key)) {
+                debug!("Skipping duplicate in-flight request: {key}");
+                continue;
+            }
+
+            // Capture the context before waiting for a slot, the user may navigate meanwhile
+            let context = RequestContext::for_message(&message, &self.app.lock().unwrap());
+            let permit = semaphore.clone().acquire_owned().await?;
+            let shared = gate.clone().read_owned().await;
+            let worker = self.clone();
+            tokio::spawn(async move {
+                tokio::select! {
+                    result = worker.process_message(message) => {
+                        if let Err(e) = result {
+                            log::error!("Worker failed to process {key}: {e}");
+                        }
+                    }
+                    () = worker.wait_until_stale(&context) => {
+                        debug!("Cancelled stale request: {key}");
+                    }
+                }
+                worker.finish_request(Some(&key));
+                drop(shared);
+                drop(permit);
+            });
+        }
+        Ok(())
+    }
+
+    /// Register a request as in flight and show the loading indicator.
+    /// Returns false if a request with the same key is already running.
+    fn start_request(&self, key: Option<&str>) -> bool {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if let Some(key) = key {
+            if !in_flight.keys.insert(key.to_string()) {
+                return false;
+            }
+        }
+        in_flight.count += 1;
+        self.app.lock().unwrap().loading = true;
+        true
+    }
+
+    fn finish_request(&self, key: Option<&str>) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if let Some(key) = key {
+            in_flight.keys.remove(key);
+        }
+        in_flight.count = in_flight.count.saturating_sub(1);
+        self.app.lock().unwrap().loading = in_flight.count > 0;
     }
 
-    pub async fn run(&mut self) -> Result<()> {
+    /// Resolves once the user has navigated away from the context a request was issued for
+    async fn wait_until_stale(&self, context: &RequestContext) {
+        let mut interval = tokio::time::interval(STALE_CHECK_INTERVAL);
         loop {
-            if let Some(message) = self.rx.recv().await {
-                // tokio::spawn(async move {
-                //     self.process_message(message).await;
-                // }); //TODO: check how we can send messages to a pool of workers
-                self.process_message(message).await?;
+            interval.tick().await;
+            if !context.is_active(&self.app.lock().unwrap()) {
+                return;
             }
         }
     }
 }
+
+/// Fetch all overview metrics of one environment at the same time
+async fn fetch_environment_overview(name: String, client: &dyn AirflowClient) -> EnvironmentOverview {
+    let since = chrono::Utc::now() - chrono::Duration::hours(24);
+    let (health, version, import_errors, failed_runs, running_tasks, queued_tasks) = tokio::join!(
+        client.get_health(),
+        client.get_server_version(),
+        client.get_import_error_count(),
+        client.count_dag_runs("failed", since),
+        client.count_task_instances("running"),
+        client.count_task_instances("queued"),
+    );
+
+    let mut errors = Vec::new();
+    let health = record_error(&mut errors, "health", health);
+    let airflow_version = record_error(&mut errors, "version", version);
+    let import_errors = record_error(&mut errors, "import errors", import_errors);
+    let failed_runs = record_error(&mut errors, "failed runs", failed_runs);
+    let running_tasks = record_error(&mut errors, "running tasks", running_tasks);
+    let queued_tasks = record_error(&mut errors, "queued tasks", queued_tasks);
+
+    EnvironmentOverview {
+        name,
+        loading: false,
+        health,
+        airflow_version,
+        import_errors,
+        failed_runs,
+        running_tasks,
+        queued_tasks,
+        errors,
+    }
+}
+
+/// Fetch the compared details of one DAG in one environment
+async fn fetch_environment_dag(name: String, dag_id: &str, client: &dyn AirflowClient) -> EnvironmentDag {
+    let (dag, runs) = tokio::join!(
+        client.get_dag_details(dag_id),
+        client.list_dagruns_paginated(dag_id, 0, 1),
+    );
+
+    let mut errors = Vec::new();
+    let dag = record_error(&mut errors, "DAG", dag);
+    let latest_run = record_error(&mut errors, "latest run", runs).and_then(|runs| runs.dag_runs.into_iter().next());
+    let code = match &dag {
+        Some(dag) => record_error(&mut errors, "source", client.get_dag_code(dag).await),
+        None => None,
+    };
+
+    EnvironmentDag {
+        name,
+        loading: false,
+        dag,
+        latest_run,
+        code,
+        errors,
+    }
+}
+
+/// Fetch every DAG of an environment, page by page
+async fn fetch_all_dags(client: &dyn AirflowClient) -> Result<Vec<Dag>> {
+    let mut dags = Vec::new();
+    loop {
+        let page = client
+            .list_dags_paginated(dags.len().try_into()?, SEARCH_PAGE_SIZE)
+            .await?;
+        let fetched = page.dags.len();
+        dags.extend(page.dags);
+        if fetched == 0 || i64::try_from(dags.len())? >= page.total_entries {
+            return Ok(dags);
+        }
+    }
+}
+
+fn record_error<T>(errors: &mut Vec<String>, metric: &str, result: Result<T>) -> Option<T> {
+    result.map_err(|e| errors.push(format!("{metric}: {e}"))).ok()
+}
+
+/// Requests in flight, used for deduplication and the loading indicator
+#[derive(Default)]
+struct InFlight {
+    keys: HashSet<String>,
+    count: usize,
+}
+
+/// The environment and panel a request was issued for
+struct RequestContext {
+    environment: Option<String>,
+    panel: Option<PanelContext>,
+}
+
+/// Panel specific context of a request, compared against the ids the panel currently shows
+enum PanelContext {
+    DagRuns {
+        dag_id: String,
+    },
+    TaskInstances {
+        dag_id: String,
+        dag_run_id: String,
+    },
+    Logs {
+        dag_id: String,
+        dag_run_id: String,
+        task_id: String,
+    },
+    EventLogs {
+        query: EventLogQuery,
+    },
+}
+
+impl RequestContext {
+    fn for_message(message: &WorkerMessage, app: &App) -> Self {
+        let panel = match message {
+            WorkerMessage::UpdateDagRuns { dag_id, .. }
+            | WorkerMessage::FetchMoreDagRuns { dag_id, .. } => Some(PanelContext::DagRuns {
+                dag_id: dag_id.clone(),
+            }),
+            WorkerMessage::UpdateTaskInstances {
+                dag_id, dag_run_id, ..
+            } => Some(PanelContext::TaskInstances {
+                dag_id: dag_id.clone(),
+                dag_run_id: dag_run_id.clone(),
+            }),
+            WorkerMessage::UpdateTaskLogs {
+                dag_id,
+                dag_run_id,
+                task_id,
+                ..
+            }
+            | WorkerMessage::EnsureTaskLogLoaded {
+                dag_id,
+                dag_run_id,
+                task_id,
+                ..
+            }
+            | WorkerMessage::LoadMoreTaskLogChunk {
+                dag_id,
+                dag_run_id,
+                task_id,
+                ..
+            } => Some(PanelContext::Logs {
+                dag_id: dag_id.clone(),
+                dag_run_id: dag_run_id.clone(),
+                task_id: task_id.clone(),
+            }),
+            WorkerMessage::UpdateEventLogs(query) => Some(PanelContext::EventLogs {
+                query: query.clone(),
+            }),
+            _ => None,
+        };
+        Self {
+            environment: app
+                .environment_state
+                .get_active_environment_name()
+                .map(str::to_string),
+            panel,
+        }
+    }
+
+    fn is_active(&self, app: &App) -> bool {
+        if app.environment_state.get_active_environment_name() != self.environment.as_deref() {
+            return false;
+        }
+        match &self.panel {
+            None => true,
+            Some(PanelContext::DagRuns { dag_id }) => app.dagruns.dag_id.as_ref() == Some(dag_id),
+            Some(PanelContext::TaskInstances { dag_id, dag_run_id }) => {
+                app.task_instances.dag_id.as_ref() == Some(dag_id)
+                    && app.task_instances.dag_run_id.as_ref() == Some(dag_run_id)
+            }
+            Some(PanelContext::Logs {
+                dag_id,
+                dag_run_id,
+                task_id,
+            }) => {
+                app.logs.dag_id.as_ref() == Some(dag_id)
+                    && app.logs.dag_run_id.as_ref() == Some(dag_run_id)
+                    && app.logs.task_id.as_ref() == Some(task_id)
+            }
+            Some(PanelContext::EventLogs { query }) => app.event_logs.query == *query,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airflow::config::FlowrsConfig;
+
+    fn test_worker() -> Worker {
+        let app = Arc::new(Mutex::new(App::new(FlowrsConfig::default())));
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        Worker::new(app, tx)
+    }
+
+    #[test]
+    fn test_duplicate_requests_are_skipped_while_in_flight() {
+        let worker = test_worker();
+        let key = format!("{:?}", WorkerMessage::UpdateDags);
+
+        assert!(worker.start_request(Some(&key)));
+        assert!(!worker.start_request(Some(&key)));
+        assert!(worker.app.lock().unwrap().loading);
+
+        worker.finish_request(Some(&key));
+        assert!(!worker.app.lock().unwrap().loading);
+        assert!(worker.start_request(Some(&key)));
+    }
+
+    #[test]
+    fn test_request_context_becomes_stale_after_navigation() {
+        let worker = test_worker();
+        let mut app = worker.app.lock().unwrap();
+        app.dagruns.dag_id = Some("dag_a".to_string());
+
+        let message = WorkerMessage::UpdateDagRuns {
+            dag_id: "dag_a".to_string(),
+            clear: false,
+        };
+        let context = RequestContext::for_message(&message, &app);
+        let global = RequestContext::for_message(&WorkerMessage::UpdateDags, &app);
+        assert!(context.is_active(&app));
+
+        app.dagruns.dag_id = Some("dag_b".to_string());
+        assert!(!context.is_active(&app));
+        assert!(global.is_active(&app));
+
+        // Issued for an environment that is no longer active
+        let other_environment = RequestContext {
+            environment: Some("other".to_string()),
+            panel: None,
+        };
+        assert!(!other_environment.is_active(&app));
+    }
+}
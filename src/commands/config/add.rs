@@ -6,10 +6,11 @@ use strum::IntoEnumIterator;
 use super::model::AddCommand;
 use crate::{
     airflow::config::{
-        AirflowAuth, AirflowConfig, AirflowVersion, BasicAuth, FlowrsConfig, TokenCmd,
+        AirflowAuth, AirflowConfig, AirflowVersion, BasicAuth, FlowrsConfig, Protection, TokenCmd,
     },
+    airflow::client::jwt::JwtAuth,
     airflow::managed_services::composer,
-    commands::config::model::{prompt_proxy_config, validate_endpoint, validate_keyfile_path, ConfigOption},
+    commands::config::model::{prompt_oidc_config, prompt_proxy_config, validate_endpoint, validate_keyfile_path, ConfigOption},
 };
 use anyhow::Result;
 
@@ -63,8 +64,61 @@ impl AddCommand {
                     managed: None,
                     version,
                     proxy,
+                    tls: None,
+                    timeout_secs: None,
+                    connect_timeout_secs: None,
+                    max_retries: None,
+                    backoff_ms: None,
+                    max_backoff_secs: None,
+                    protection: Protection::None,
                 }
             }
+            ConfigOption::Jwt => {
+                println!("\n📝 Enter environment variable names for credentials.");
+                println!("   They are exchanged for a short-lived access token at /auth/token (Airflow 3 only).");
+                println!("   The access token is kept in memory and never written to disk.\n");
+
+                let username = inquire::Text::new("username environment variable")
+                    .with_placeholder("AIRFLOW_USERNAME")
+                    .prompt()?;
+                let password = inquire::Text::new("password environment variable")
+                    .with_placeholder("AIRFLOW_PASSWORD")
+                    .prompt()?;
+
+                AirflowConfig {
+                    name,
+                    endpoint,
+                    auth: AirflowAuth::Jwt(JwtAuth::new(
+                        format!("${{{}}}", username.trim_start_matches('$').trim_start_matches('{').trim_end_matches('}')),
+                        format!("${{{}}}", password.trim_start_matches('$').trim_start_matches('{').trim_end_matches('}')),
+                    )),
+                    managed: None,
+                    version: AirflowVersion::V3,
+                    proxy,
+                    tls: None,
+                    timeout_secs: None,
+                    connect_timeout_secs: None,
+                    max_retries: None,
+                    backoff_ms: None,
+                    max_backoff_secs: None,
+                    protection: Protection::None,
+                }
+            }
+            ConfigOption::Oidc => AirflowConfig {
+                name,
+                endpoint,
+                auth: AirflowAuth::Oidc(prompt_oidc_config()?),
+                managed: None,
+                version,
+                proxy,
+                tls: None,
+                timeout_secs: None,
+                connect_timeout_secs: None,
+                max_retries: None,
+                backoff_ms: None,
+                max_backoff_secs: None,
+                protection: Protection::None,
+            },
             ConfigOption::Composer => {
                 // This case is already handled at the top of the function
                 unreachable!("Composer auth is handled separately")
@@ -98,10 +152,21 @@ impl AddCommand {
                 AirflowConfig {
                     name,
                     endpoint,
-                    auth: AirflowAuth::Token(TokenCmd { cmd, token }),
+                    auth: AirflowAuth::Token(TokenCmd {
+                        cmd,
+                        token,
+                        ..Default::default()
+                    }),
                     managed: None,
                     version,
                     proxy,
+                    tls: None,
+                    timeout_secs: None,
+                    connect_timeout_secs: None,
+                    max_retries: None,
+                    backoff_ms: None,
+                    max_backoff_secs: None,
+                    protection: Protection::None,
                 }
             }
         };
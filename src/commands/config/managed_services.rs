@@ -6,6 +6,7 @@ use strum::IntoEnumIterator;
 use super::model::ManagedServiceCommand;
 use crate::airflow::config::FlowrsConfig;
 use crate::airflow::config::ManagedService;
+use crate::airflow::managed_services::composer::ComposerDiscoveryConfig;
 use anyhow::Result;
 
 impl ManagedServiceCommand {
@@ -19,19 +20,25 @@ impl ManagedServiceCommand {
         let path = self.file.as_ref().map(PathBuf::from);
         let mut config = FlowrsConfig::from_file(path.as_ref())?;
 
-        match config.managed_services {
-            Some(ref mut services) => {
-                if services.contains(&managed_service) {
-                    println!("Managed service already enabled!");
-                    return Ok(());
-                }
-                services.push(managed_service);
-            }
-            None => {
-                config.managed_services = Some(vec![managed_service]);
-            }
+        if config
+            .managed_services
+            .as_ref()
+            .is_some_and(|services| services.contains(&managed_service))
+        {
+            println!("Managed service already enabled!");
+            return Ok(());
         }
 
+        // Composer discovery needs to know where to look
+        if managed_service == ManagedService::Gcc && config.composer.is_none() {
+            config.composer = Some(prompt_composer_discovery()?);
+        }
+
+        config
+            .managed_services
+            .get_or_insert_with(Vec::new)
+            .push(managed_service);
+
         config.write_to_file()?;
 
         println!("✅ Managed service added successfully!");
@@ -62,3 +69,37 @@ impl ManagedServiceCommand {
         Ok(())
     }
 }
+
+fn prompt_composer_discovery() -> Result<ComposerDiscoveryConfig> {
+    println!("\n🌩️  Google Cloud Composer discovery");
+    println!("   Environments are listed in every combination of the projects and locations below.\n");
+
+    let split = |value: String| -> Vec<String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    let projects = inquire::Text::new("projects")
+        .with_placeholder("my-project, other-project")
+        .with_help_message("Comma separated GCP project ids")
+        .prompt()?;
+    let locations = inquire::Text::new("locations")
+        .with_placeholder("europe-west1, us-central1")
+        .with_help_message("Comma separated Composer regions")
+        .prompt()?;
+    let keyfile_path = inquire::Text::new("service account keyfile (optional)")
+        .with_help_message("Leave empty to use Application Default Credentials")
+        .prompt_skippable()?
+        .filter(|path| !path.trim().is_empty());
+
+    Ok(ComposerDiscoveryConfig {
+        projects: split(projects),
+        locations: split(locations),
+        keyfile_path,
+        ..Default::default()
+    })
+}
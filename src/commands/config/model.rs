@@ -1,3 +1,4 @@
+use crate::airflow::client::oidc::OidcAuth;
 use crate::airflow::config::ManagedService;
 use anyhow::Result;
 use clap::Parser;
@@ -60,6 +61,10 @@ pub struct UpdateCommand {
 #[derive(EnumIter, Debug, Display)]
 pub enum ConfigOption {
     BasicAuth,
+    #[strum(serialize = "JWT (Airflow 3)")]
+    Jwt,
+    #[strum(serialize = "OIDC (SSO)")]
+    Oidc,
     Token(Command),
     #[strum(serialize = "Google Cloud Composer")]
     Composer,
@@ -94,6 +99,30 @@ pub fn validate_endpoint(
     }
 }
 
+/// Prompts for the OIDC issuer, client id and scopes.
+/// Refresh tokens are obtained on first use via the device login, not here.
+pub fn prompt_oidc_config() -> Result<OidcAuth> {
+    println!("\n🔐 OIDC configuration");
+    println!("   On first use flowrs opens your browser to approve a device login.");
+    println!("   The refresh token is stored in the flowrs state directory (readable only by you).\n");
+
+    let issuer = inquire::Text::new("issuer URL")
+        .with_placeholder("https://keycloak.example.com/realms/data")
+        .with_validator(validate_endpoint)
+        .prompt()?;
+    let client_id = inquire::Text::new("client id").prompt()?;
+    let scopes = inquire::Text::new("scopes")
+        .with_default("openid offline_access")
+        .with_help_message("Space separated, offline_access is needed to receive a refresh token")
+        .prompt()?;
+
+    Ok(OidcAuth::new(
+        issuer,
+        client_id,
+        scopes.split_whitespace().map(str::to_string).collect(),
+    ))
+}
+
 /// Validates a keyfile path for service account authentication.
 /// Checks that the path exists, is a file, and contains valid JSON.
 #[allow(clippy::unnecessary_wraps)]
@@ -5,8 +5,9 @@ use strum::IntoEnumIterator;
 
 use super::model::UpdateCommand;
 use crate::{
-    airflow::config::{AirflowAuth, AirflowConfig, BasicAuth, FlowrsConfig, TokenCmd},
-    commands::config::model::{prompt_proxy_config, validate_endpoint, ConfigOption},
+    airflow::client::jwt::JwtAuth,
+    airflow::config::{AirflowAuth, AirflowConfig, AirflowVersion, BasicAuth, FlowrsConfig, TokenCmd},
+    commands::config::model::{prompt_oidc_config, prompt_proxy_config, validate_endpoint, ConfigOption},
 };
 
 use anyhow::Result;
@@ -73,6 +74,27 @@ impl UpdateCommand {
                     password: format!("${{{}}}", password.trim_start_matches('$').trim_start_matches('{').trim_end_matches('}')),
                 });
             }
+            ConfigOption::Jwt => {
+                println!("\n📝 Enter environment variable names for credentials.");
+                println!("   They are exchanged for a short-lived access token at /auth/token (Airflow 3 only).");
+                println!("   The access token is kept in memory and never written to disk.\n");
+
+                let username = inquire::Text::new("username environment variable")
+                    .with_placeholder("AIRFLOW_USERNAME")
+                    .prompt()?;
+                let password = inquire::Text::new("password environment variable")
+                    .with_placeholder("AIRFLOW_PASSWORD")
+                    .prompt()?;
+
+                airflow_config.auth = AirflowAuth::Jwt(JwtAuth::new(
+                    format!("${{{}}}", username.trim_start_matches('$').trim_start_matches('{').trim_end_matches('}')),
+                    format!("${{{}}}", password.trim_start_matches('$').trim_start_matches('{').trim_end_matches('}')),
+                ));
+                airflow_config.version = AirflowVersion::V3;
+            }
+            ConfigOption::Oidc => {
+                airflow_config.auth = AirflowAuth::Oidc(prompt_oidc_config()?);
+            }
             ConfigOption::Composer => {
                 println!("⚠️  Composer authentication cannot be updated directly.");
                 println!("   Please remove the config and add it again.");
@@ -104,7 +126,11 @@ impl UpdateCommand {
                     }
                 };
 
-                airflow_config.auth = AirflowAuth::Token(TokenCmd { cmd, token });
+                airflow_config.auth = AirflowAuth::Token(TokenCmd {
+                    cmd,
+                    token,
+                    ..Default::default()
+                });
             }
         }
 
@@ -50,7 +50,7 @@ impl RunCommand {
     }
 }
 
-fn setup_logging(log_level: &str) -> Result<()> {
+pub(crate) fn setup_logging(log_level: &str) -> Result<()> {
     // Get the XDG state directory for logs
     let log_dir = crate::get_state_dir().join("logs");
     
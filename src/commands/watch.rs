@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use log::info;
+
+use super::run::setup_logging;
+use crate::airflow::client::create_client;
+use crate::airflow::config::FlowrsConfig;
+use crate::airflow::traits::AirflowClient;
+use crate::airflow::watch::notify::Notifier;
+use crate::airflow::watch::{watch, WatchConfig, WatchEvent, WatchEventKind};
+
+/// Watch DAG runs without the TUI and notify about failures, retries and SLA misses
+#[derive(Parser, Debug)]
+pub struct WatchCommand {
+    #[clap(short, long)]
+    pub file: Option<String>,
+    /// Seconds between two polls, overrides `watch.interval_secs`
+    #[clap(short, long)]
+    pub interval: Option<u64>,
+    /// Send a sample event to every notification sink and webhook, then exit
+    #[clap(long)]
+    pub test_notify: bool,
+}
+
+impl WatchCommand {
+    pub async fn run(&self) -> Result<()> {
+        if let Ok(log_level) = std::env::var("FLOWRS_LOG") {
+            setup_logging(&log_level)?;
+        }
+
+        let path = self.file.as_ref().map(PathBuf::from);
+        let (config, errors) = FlowrsConfig::from_file(path.as_ref())?
+            .expand_managed_services()
+            .await?;
+        for error in errors {
+            eprintln!("{error}");
+        }
+
+        let mut watch_config = config.watch.clone().unwrap_or_default();
+        if let Some(interval) = self.interval {
+            watch_config.interval_secs = Some(interval);
+        }
+        watch_config.validate()?;
+
+        if self.test_notify {
+            return send_test_notification(&watch_config).await;
+        }
+
+        let mut environments: Vec<(String, Arc<dyn AirflowClient>)> = Vec::new();
+        for server in config.servers.iter().flatten() {
+            match create_client(server) {
+                Ok(client) => environments.push((server.name.clone(), client)),
+                Err(e) => eprintln!("Skipping {}: {e}", server.name),
+            }
+        }
+        if environments.is_empty() {
+            return Err(anyhow::anyhow!("No environments configured to watch"));
+        }
+
+        info!("Watching {} environment(s)", environments.len());
+        println!(
+            "Watching {} environment(s) every {}s, press Ctrl+C to stop",
+            environments.len(),
+            watch_config.interval().as_secs()
+        );
+        watch(&watch_config, &environments, |poll| {
+            let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+            for event in &poll.events {
+                println!("{now} [{}] {}", event.kind, event.summary());
+            }
+            for error in &poll.errors {
+                eprintln!("{now} [error] {error}");
+            }
+        })
+        .await
+    }
+}
+
+async fn send_test_notification(config: &WatchConfig) -> Result<()> {
+    let event = WatchEvent {
+        kind: WatchEventKind::Failed,
+        environment: "flowrs".to_string(),
+        dag_id: "test_notification".to_string(),
+        dag_run_id: "manual__test".to_string(),
+        task_id: None,
+        try_number: None,
+        running_for: None,
+        tags: vec![],
+    };
+    let failures = Notifier::new(config)?.notify_all(&event).await;
+    if failures.is_empty() {
+        println!("Test notification sent");
+        return Ok(());
+    }
+    for failure in &failures {
+        eprintln!("{failure}");
+    }
+    Err(anyhow::anyhow!("{} notification(s) failed", failures.len()))
+}
@@ -102,6 +102,24 @@ pub fn open_in_editor_with_suspend<B: ratatui::backend::Backend>(
     Ok(())
 }
 
+/// Let the user edit a piece of text in their editor.
+/// The text is written to `filepath`, which is removed again once the editor closes.
+/// Returns the edited text with trailing whitespace trimmed.
+pub fn edit_text_with_suspend<B: ratatui::backend::Backend>(
+    terminal: &mut ratatui::Terminal<B>,
+    filepath: &Path,
+    text: &str,
+) -> Result<String> {
+    if let Some(parent) = filepath.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(filepath, text)?;
+    let result = open_in_editor_with_suspend(terminal, filepath)
+        .and_then(|()| Ok(std::fs::read_to_string(filepath)?));
+    let _ = std::fs::remove_file(filepath);
+    Ok(result?.trim_end().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
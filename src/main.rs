@@ -13,6 +13,7 @@ mod ui;
 use anyhow::Result;
 use commands::config::model::ConfigCommand;
 use commands::run::RunCommand;
+use commands::watch::WatchCommand;
 use dirs::{config_dir, home_dir, state_dir};
 
 /// Get the configuration file path using XDG Base Directory specification
@@ -69,6 +70,7 @@ struct FlowrsApp {
 #[derive(Parser)]
 enum FlowrsCommand {
     Run(RunCommand),
+    Watch(WatchCommand),
     #[clap(subcommand)]
     Config(ConfigCommand),
 }
@@ -77,6 +79,7 @@ impl FlowrsApp {
     pub async fn run(&self) -> Result<()> {
         match &self.command {
             Some(FlowrsCommand::Run(cmd)) => cmd.run().await,
+            Some(FlowrsCommand::Watch(cmd)) => cmd.run().await,
             Some(FlowrsCommand::Config(cmd)) => cmd.run(),
             None => RunCommand { file: None }.run().await,
         }
@@ -1,8 +1,11 @@
 use crate::app::state::{App, Panel};
-use crate::ui::constants::DEFAULT_STYLE;
+use crate::app::model::popup::toast::Toast;
+use crate::airflow::config::Protection;
+use crate::ui::constants::{DEFAULT_STYLE, GREEN, RED, YELLOW};
 use init_screen::render_init_screen;
 use ratatui::layout::{Constraint, Layout};
-use ratatui::text::Line;
+use ratatui::style::Modifier;
+use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Paragraph, Widget};
 use ratatui::Frame;
 use std::sync::{Arc, Mutex};
@@ -45,10 +48,35 @@ pub fn draw_ui(f: &mut Frame, app: &Arc<Mutex<App>>) {
     let [app_info, throbber_area] =
         Layout::horizontal([Constraint::Min(0), Constraint::Length(20)]).areas(top_line);
 
-    // Render app name and version on the left
+    // Render app name and version on the left, followed by the latest watcher event and error
     let version = env!("CARGO_PKG_VERSION");
+    let mut info = vec![Span::raw(format!(" Flowrs v{version}"))];
+    if let Some(environment) = app.environment_state.get_active_environment_name() {
+        // Color-code the environment by how it is protected, so a production environment stands out
+        let (color, label) = match app.protection {
+            Protection::None => (GREEN, ""),
+            Protection::Confirm => (RED, " [confirm]"),
+            Protection::ReadOnly => (YELLOW, " [read-only]"),
+        };
+        info.push(Span::styled(
+            format!("  ● {environment}{label}"),
+            DEFAULT_STYLE.fg(color).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(event) = app.watch_events.last() {
+        info.push(Span::styled(
+            format!("  ⚠ {} ({} total)", event.summary(), app.watch_events.len()),
+            DEFAULT_STYLE.fg(RED),
+        ));
+    }
+    if let Some(error) = app.watch_errors.last() {
+        info.push(Span::styled(
+            format!("  ✗ watcher: {error} ({} error(s))", app.watch_errors.len()),
+            DEFAULT_STYLE.fg(YELLOW),
+        ));
+    }
     f.render_widget(
-        Paragraph::new(Line::from(format!(" Flowrs v{version}"))).style(DEFAULT_STYLE),
+        Paragraph::new(Line::from(info)).style(DEFAULT_STYLE),
         app_info,
     );
 
@@ -92,9 +120,34 @@ pub fn draw_ui(f: &mut Frame, app: &Arc<Mutex<App>>) {
                 f.set_cursor_position(app.task_instances.filter.cursor.position);
             }
         }
+        Panel::TaskInstanceDetail => app.task_instance_detail.render(panel_area, f.buffer_mut()),
         Panel::Logs => app.logs.render(panel_area, f.buffer_mut()),
         Panel::VariableDetail => app.variable_detail.render(panel_area, f.buffer_mut()),
         Panel::ConnectionDetail => app.connection_detail.render(panel_area, f.buffer_mut()),
         Panel::ImportErrorDetail => app.import_error_detail.render(panel_area, f.buffer_mut()),
+        Panel::Overview => app.overview.render(panel_area, f.buffer_mut()),
+        Panel::Compare => app.compare.render(panel_area, f.buffer_mut()),
+        Panel::Search => {
+            app.search.render(panel_area, f.buffer_mut());
+            if app.search.filter.is_enabled() {
+                f.set_cursor_position(app.search.filter.cursor.position);
+            }
+        }
+        Panel::EventLog => {
+            app.event_logs.render(panel_area, f.buffer_mut());
+            if app.event_logs.filter.is_enabled() {
+                f.set_cursor_position(app.event_logs.filter.cursor.position);
+            }
+        }
+    }
+
+    if let Some(login) = &app.pending_login {
+        f.render_widget(&Toast::new(login.to_string()), panel_area);
+    }
+    if let Some(recent_actions) = &mut app.recent_actions {
+        recent_actions.render(panel_area, f.buffer_mut());
+    }
+    if let Some(confirm) = &mut app.confirm_environment {
+        confirm.render(panel_area, f.buffer_mut());
     }
 }